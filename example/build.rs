@@ -7,7 +7,13 @@ fn main() {
     let mut text = String::new();
     writeln!(&mut text, "// File automatically generated by build.rs.").unwrap();
     writeln!(&mut text, "// Changes made to this file will not be saved.").unwrap();
-    text += &wgsl_to_wgpu::create_shader_module(&wgsl_source, "shader.wgsl").unwrap();
+    text += &wgsl_to_wgpu::create_shader_module(
+        &wgsl_source,
+        "shader.wgsl",
+        wgsl_to_wgpu::WriterOptions::default(),
+    )
+    .unwrap();
 
-    std::fs::write("src/shader.rs", text.as_bytes()).unwrap();
+    // Avoid rebuilding the example when the bindings are unchanged.
+    wgsl_to_wgpu::write_if_changed("src/shader.rs", &text).unwrap();
 }
@@ -9,6 +9,8 @@ use winit::{
 };
 
 // Include the bindings generated by build.rs.
+// Not every generated item is used by the example.
+#[allow(dead_code)]
 mod shader;
 
 struct State {
@@ -51,29 +53,27 @@ impl State {
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
-            width: size.width as u32,
-            height: size.height as u32,
+            width: size.width,
+            height: size.height,
             present_mode: wgpu::PresentMode::Fifo,
         };
         surface.configure(&device, &config);
 
         // Use the generated bindings to create the pipeline.
         let shader = crate::shader::create_shader_module(&device);
-        let render_pipeline_layout = crate::shader::create_pipeline_layout(&device);
+        // Create the bind group layouts once and share them with the bind groups.
+        let bind_group_layouts = crate::shader::bind_groups::BindGroupLayouts::new(&device);
+        let render_pipeline_layout =
+            crate::shader::create_pipeline_layout_with_layouts(&device, &bind_group_layouts);
 
         let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
             layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[surface_format.into()],
-            }),
+            vertex: crate::shader::entry_points::vs_main::vertex_state(&shader),
+            fragment: Some(crate::shader::entry_points::fs_main::fragment_state(
+                &shader,
+                &[surface_format.into()],
+            )),
             primitive: wgpu::PrimitiveState::default(),
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
@@ -127,7 +127,7 @@ impl State {
         });
 
         // Use the generated types to ensure the correct bind group is assigned to each slot.
-        let bind_group0 = crate::shader::bind_groups::BindGroup0::from_bindings(
+        let bind_group0 = bind_group_layouts.create_bind_group0(
             &device,
             crate::shader::bind_groups::BindGroupLayout0 {
                 color_texture: &view,
@@ -1,10 +1,12 @@
 // File automatically generated by build.rs.
 // Changes made to this file will not be saved.
+/// Generated from the WGSL struct `VertexInput`.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct VertexInput {
     pub position: [f32; 3],
 }
+/// Generated from the WGSL struct `VertexOutput`.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct VertexOutput {
@@ -12,17 +14,40 @@ pub struct VertexOutput {
     pub tex_coords: [f32; 2],
 }
 pub mod bind_groups {
+    /// Types like render or compute passes that can set bind groups.
+    pub trait SetBindGroup<'a> {
+        fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup, offsets: &[wgpu::DynamicOffset]);
+    }
+    impl<'a> SetBindGroup<'a> for wgpu::RenderPass<'a> {
+        fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup, offsets: &[wgpu::DynamicOffset]) {
+            wgpu::RenderPass::set_bind_group(self, index, bind_group, offsets);
+        }
+    }
+    impl<'a> SetBindGroup<'a> for wgpu::ComputePass<'a> {
+        fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup, offsets: &[wgpu::DynamicOffset]) {
+            wgpu::ComputePass::set_bind_group(self, index, bind_group, offsets);
+        }
+    }
+    impl<'a> SetBindGroup<'a> for wgpu::RenderBundleEncoder<'a> {
+        fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup, offsets: &[wgpu::DynamicOffset]) {
+            wgpu::RenderBundleEncoder::set_bind_group(self, index, bind_group, offsets);
+        }
+    }
+    /// The bind group for group 0 in the shader.
     pub struct BindGroup0(wgpu::BindGroup);
+    /// The resources for the bindings in group 0.
     pub struct BindGroupLayout0<'a> {
+        /// Group 0 binding 0 for `var color_texture: texture_2d<f32>`.
         pub color_texture: &'a wgpu::TextureView,
+        /// Group 0 binding 1 for `var color_sampler: sampler`.
         pub color_sampler: &'a wgpu::Sampler,
     }
     const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
-        label: None,
+        label: Some("shader.wgsl bind group layout 0"),
         entries: &[
             wgpu::BindGroupLayoutEntry {
                 binding: 0u32,
-                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                visibility: wgpu::ShaderStages::FRAGMENT,
                 ty: wgpu::BindingType::Texture {
                     multisampled: false,
                     view_dimension: wgpu::TextureViewDimension::D2,
@@ -32,21 +57,39 @@ pub mod bind_groups {
             },
             wgpu::BindGroupLayoutEntry {
                 binding: 1u32,
-                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                visibility: wgpu::ShaderStages::FRAGMENT,
                 ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                 count: None,
             },
         ]
     };
     impl BindGroup0 {
+        /// The index of the group in the shader.
+        pub const GROUP_INDEX: u32 = 0;
+        /// The binding index for `var color_texture: texture_2d<f32>`.
+        pub const COLOR_TEXTURE_BINDING: u32 = 0;
+        /// The binding index for `var color_sampler: sampler`.
+        pub const COLOR_SAMPLER_BINDING: u32 = 1;
+
+        /// Create a new layout for the bindings in this group.
         pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
             device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0)
         }
-    
+
+        /// Create the bind group with a new layout from [Self::get_bind_group_layout].
         pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout0) -> Self {
-            let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR0);
+            let bind_group_layout = Self::get_bind_group_layout(device);
+            Self::from_bindings_with_layout(device, &bind_group_layout, bindings)
+        }
+
+        /// Create the bind group with an existing layout like one from [BindGroupLayouts].
+        pub fn from_bindings_with_layout(
+            device: &wgpu::Device,
+            bind_group_layout: &wgpu::BindGroupLayout,
+            bindings: BindGroupLayout0,
+        ) -> Self {
             let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                layout: &bind_group_layout,
+                layout: bind_group_layout,
                 entries: &[
                     wgpu::BindGroupEntry {
                         binding: 0u32,
@@ -57,38 +100,267 @@ pub mod bind_groups {
                         resource: wgpu::BindingResource::Sampler(bindings.color_sampler),
                     },
                 ],
-                label: None,
+                label: Some("shader.wgsl bind group 0 (color_texture, color_sampler)"),
             });
             Self(bind_group)
         }
-    
-        pub fn set<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
-            render_pass.set_bind_group(0u32, &self.0, &[]);
+
+        /// Create the bind group with placeholder resources for tests that only need a valid pipeline.
+        pub fn dummy(device: &wgpu::Device) -> Self {
+            let color_texture = device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: None,
+                    size: wgpu::Extent3d {
+                        width: 1,
+                        height: 1,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                })
+                .create_view(&wgpu::TextureViewDescriptor {
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    ..Default::default()
+                });
+            let color_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+                compare: None,
+                ..Default::default()
+            });
+            Self::from_bindings(device, BindGroupLayout0 {
+                color_texture: &color_texture,
+                color_sampler: &color_sampler,
+            })
+        }
+
+        /// Set the bind group at [Self::GROUP_INDEX] for the `pass`.
+        pub fn set<'a>(&'a self, pass: &mut impl SetBindGroup<'a>) {
+            pass.set_bind_group(0u32, &self.0, &[]);
         }
     }
+    /// The layouts for all bind groups to avoid creating duplicate layouts.
+    /// Use the same layouts for the pipeline layout and bind groups.
+    pub struct BindGroupLayouts {
+        pub bind_group_layout0: wgpu::BindGroupLayout,
+    }
+    impl BindGroupLayouts {
+        /// Create the layouts for all bind groups in the shader.
+        pub fn new(device: &wgpu::Device) -> Self {
+            Self {
+                bind_group_layout0: BindGroup0::get_bind_group_layout(device),
+            }
+        }
+        /// Create bind group 0 with the layout in `bind_group_layout0`.
+        pub fn create_bind_group0(
+            &self,
+            device: &wgpu::Device,
+            bindings: BindGroupLayout0,
+        ) -> BindGroup0 {
+            BindGroup0::from_bindings_with_layout(device, &self.bind_group_layout0, bindings)
+        }
+    }
+    /// A description of a binding in the shader for inspecting the bind groups at runtime.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct BindingInfo {
+        pub group: u32,
+        pub binding: u32,
+        /// The name of the global variable in the shader.
+        pub name: &'static str,
+        pub visibility: wgpu::ShaderStages,
+        pub ty: wgpu::BindingType,
+    }
+    /// All bindings in the shader ordered by group and binding index.
+    pub const BINDINGS: &[BindingInfo] = &[
+        BindingInfo {
+            group: 0,
+            binding: 0,
+            name: "color_texture",
+            visibility: LAYOUT_DESCRIPTOR0.entries[0].visibility,
+            ty: LAYOUT_DESCRIPTOR0.entries[0].ty,
+        },
+        BindingInfo {
+            group: 0,
+            binding: 1,
+            name: "color_sampler",
+            visibility: LAYOUT_DESCRIPTOR0.entries[1].visibility,
+            ty: LAYOUT_DESCRIPTOR0.entries[1].ty,
+        },
+    ];
     pub struct BindGroups<'a> {
         pub bind_group0: &'a BindGroup0,
     }
     pub fn set_bind_groups<'a>(
-        pass: &mut wgpu::RenderPass<'a>,
+        pass: &mut impl SetBindGroup<'a>,
         bind_groups: BindGroups<'a>,
     ) {
         bind_groups.bind_group0.set(pass);
     }
+    /// Set only bind group 0 and leave any other bind groups unchanged.
+    pub fn set_bind_group0<'a>(
+        pass: &mut impl SetBindGroup<'a>,
+        bind_group: &'a BindGroup0,
+    ) {
+        bind_group.set(pass);
+    }
 }
 pub mod vertex {
 }
+pub const ENTRY_FS_MAIN: &str = "fs_main";
+pub const ENTRY_VS_MAIN: &str = "vs_main";
+pub mod entry_points {
+    /// Generated from the fragment entry point `fs_main`.
+    pub mod fs_main {
+        /// The number of color targets required by the outputs of the entry point.
+        pub const TARGET_COUNT: usize = 1;
+        /// The locations of the color outputs in increasing order.
+        pub const OUTPUT_LOCATIONS: [u32; 1] = [0];
+        /// The component type of each color output.
+        /// Float outputs are always `filterable: false`, so compare variants with `matches!`.
+        pub const OUTPUT_SAMPLE_TYPES: [wgpu::TextureSampleType; 1] = [wgpu::TextureSampleType::Float { filterable: false }];
+        /// The number of components of each color output.
+        pub const OUTPUT_COMPONENTS: [u32; 1] = [4];
+        /// The fragment stage with the entry point filled in.
+        pub fn fragment_state<'a>(
+            module: &'a wgpu::ShaderModule,
+            targets: &'a [wgpu::ColorTargetState],
+        ) -> wgpu::FragmentState<'a> {
+            debug_assert!(
+                targets.len() >= TARGET_COUNT,
+                "expected at least {TARGET_COUNT} color targets but found {}",
+                targets.len()
+            );
+            wgpu::FragmentState {
+                module,
+                entry_point: super::super::ENTRY_FS_MAIN,
+                targets,
+            }
+        }
+    }
+    /// Generated from the vertex entry point `vs_main`.
+    pub mod vs_main {
+        /// The vertex buffer layouts for the vertex inputs in entry point parameter order.
+        pub const VERTEX_BUFFERS: [wgpu::VertexBufferLayout<'static>; 0] = [];
+        /// An owned copy of [VERTEX_BUFFERS] for building pipelines without the generated vertex state.
+        pub const fn vertex_buffer_layouts() -> [wgpu::VertexBufferLayout<'static>; 0] {
+            VERTEX_BUFFERS
+        }
+        /// The vertex stage with the entry point and vertex buffer layouts filled in.
+        pub fn vertex_state(module: &wgpu::ShaderModule) -> wgpu::VertexState<'_> {
+            wgpu::VertexState {
+                module,
+                entry_point: super::super::ENTRY_VS_MAIN,
+                buffers: &VERTEX_BUFFERS,
+            }
+        }
+    }
+}
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EntryPoint {
+    FsMain,
+    VsMain,
+}
+impl EntryPoint {
+    /// The name of the entry point function in the shader.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::FsMain => ENTRY_FS_MAIN,
+            Self::VsMain => ENTRY_VS_MAIN,
+        }
+    }
+    /// The shader stage of the entry point.
+    pub const fn stage(self) -> wgpu::ShaderStages {
+        match self {
+            Self::FsMain => wgpu::ShaderStages::FRAGMENT,
+            Self::VsMain => wgpu::ShaderStages::VERTEX,
+        }
+    }
+}
+/// The device features required by the shader such as [wgpu::Features::PUSH_CONSTANTS] for push constants.
+pub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::empty();
+/// Raise the limits in `base` to at least the limits required by the shader.
+pub fn required_limits(base: wgpu::Limits) -> wgpu::Limits {
+    wgpu::Limits {
+        max_bind_groups: base.max_bind_groups.max(1),
+        max_sampled_textures_per_shader_stage: base.max_sampled_textures_per_shader_stage.max(1),
+        max_samplers_per_shader_stage: base.max_samplers_per_shader_stage.max(1),
+        ..base
+    }
+}
+/// An error from [verify_device] for a device that can't use the shader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyDeviceError {
+    /// The device doesn't support these features from [REQUIRED_FEATURES].
+    MissingFeatures(wgpu::Features),
+    /// The device limit with the name from [wgpu::Limits] is lower than required by the shader.
+    InsufficientLimit {
+        name: &'static str,
+        required: u32,
+        supported: u32,
+    },
+}
+impl std::fmt::Display for VerifyDeviceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingFeatures(features) => {
+                write!(f, "device is missing features {features:?} required by the shader")
+            }
+            Self::InsufficientLimit {
+                name,
+                required,
+                supported,
+            } => write!(
+                f,
+                "device limit {name} is {supported} but the shader requires at least {required}"
+            ),
+        }
+    }
+}
+impl std::error::Error for VerifyDeviceError {}
+/// Check that the device supports the features and limits required by the shader.
+pub fn verify_device(device: &wgpu::Device) -> Result<(), VerifyDeviceError> {
+    let missing_features = REQUIRED_FEATURES.difference(device.features());
+    if !missing_features.is_empty() {
+        return Err(VerifyDeviceError::MissingFeatures(missing_features));
+    }
+
+    let limits = device.limits();
+    for (name, required, supported) in [
+        ("max_bind_groups", 1, limits.max_bind_groups),
+        ("max_sampled_textures_per_shader_stage", 1, limits.max_sampled_textures_per_shader_stage),
+        ("max_samplers_per_shader_stage", 1, limits.max_samplers_per_shader_stage),
+    ] {
+        if supported < required {
+            return Err(VerifyDeviceError::InsufficientLimit {
+                name,
+                required,
+                supported,
+            });
+        }
+    }
+    Ok(())
+}
+/// Create the shader module for `shader.wgsl`.
 pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
     device.create_shader_module(&wgpu::ShaderModuleDescriptor {
-        label: None,
+        label: Some("shader.wgsl"),
         source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!("shader.wgsl")))
     })
 }
+/// Create the pipeline layout with new layouts for all bind groups in the shader.
 pub fn create_pipeline_layout(device: &wgpu::Device) -> wgpu::PipelineLayout {
+    create_pipeline_layout_with_layouts(device, &bind_groups::BindGroupLayouts::new(device))
+}
+/// Create the pipeline layout with the existing bind group `layouts`.
+pub fn create_pipeline_layout_with_layouts(
+    device: &wgpu::Device,
+    layouts: &bind_groups::BindGroupLayouts,
+) -> wgpu::PipelineLayout {
     device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: None,
+        label: Some("shader.wgsl pipeline layout"),
         bind_group_layouts: &[
-            &bind_groups::BindGroup0::get_bind_group_layout(device),
+            &layouts.bind_group_layout0,
         ],
         push_constant_ranges: &[],
     })
@@ -0,0 +1,224 @@
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+const USAGE: &str = "\
+Generate Rust bindings for WGSL shaders.
+
+Usage: wgsl_to_wgpu [OPTIONS] <INPUT> <OUTPUT> [<INPUT> <OUTPUT>...]
+       wgsl_to_wgpu --config <CONFIG>
+
+Each WGSL file INPUT is written as a Rust file to the following OUTPUT path.
+The generated code includes the WGSL file with a path relative to OUTPUT.
+
+Options:
+  --watch                 Regenerate the bindings whenever an input file changes
+  --config <CONFIG>       Generate the shaders listed in a wgsl_to_wgpu.toml configuration file
+  --no-derive-bytemuck    Don't derive bytemuck::Pod and bytemuck::Zeroable for the generated structs
+  --derive-encase         Derive encase::ShaderType for uniform and storage buffer structs
+  --impl-default          Implement Default for the generated structs
+  --visibility <VIS>      The visibility of the generated items like pub(crate)
+  -h, --help              Print this help message";
+
+struct Args {
+    shaders: Shaders,
+    watch: bool,
+}
+
+enum Shaders {
+    Files(Vec<wgsl_to_wgpu::ShaderConfig>),
+    Config(PathBuf),
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut options = wgsl_to_wgpu::WriterOptions::default();
+    let mut paths = Vec::new();
+    let mut config = None;
+    let mut watch = false;
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--config" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| "missing value for --config".to_string())?;
+                config = Some(PathBuf::from(path));
+            }
+            "--watch" => watch = true,
+            "--no-derive-bytemuck" => options.derive_bytemuck = false,
+            "--derive-encase" => options.derive_encase = true,
+            "--impl-default" => options.impl_default = true,
+            "--visibility" => {
+                options.visibility = args
+                    .next()
+                    .ok_or_else(|| "missing value for --visibility".to_string())?;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unrecognized option {arg}")),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if let Some(config) = config {
+        if !paths.is_empty() {
+            return Err("unexpected paths with --config".to_string());
+        }
+        return Ok(Some(Args {
+            shaders: Shaders::Config(config),
+            watch,
+        }));
+    }
+
+    if paths.is_empty() || paths.len() % 2 != 0 {
+        return Err("expected pairs of input and output paths".to_string());
+    }
+
+    let shaders = paths
+        .chunks(2)
+        .map(|pair| wgsl_to_wgpu::ShaderConfig {
+            input: pair[0].clone(),
+            output: pair[1].clone(),
+            options: options.clone(),
+        })
+        .collect();
+    Ok(Some(Args {
+        shaders: Shaders::Files(shaders),
+        watch,
+    }))
+}
+
+fn watch(shaders: &[wgsl_to_wgpu::ShaderConfig]) {
+    // Changes to the configuration file itself require restarting the watch.
+    wgsl_to_wgpu::watch(shaders, Duration::from_millis(500), |shader, result| {
+        match result {
+            Ok(()) => println!("generated {}", shader.output.display()),
+            Err(e) => eprintln!("error: {e}"),
+        }
+        ControlFlow::Continue(())
+    });
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match (args.shaders, args.watch) {
+        (Shaders::Files(shaders), false) => shaders.iter().try_for_each(|shader| shader.generate()),
+        (Shaders::Config(path), false) => wgsl_to_wgpu::generate_from_config(path).map(|_| ()),
+        (Shaders::Files(shaders), true) => {
+            watch(&shaders);
+            Ok(())
+        }
+        (Shaders::Config(path), true) => {
+            wgsl_to_wgpu::read_config(path).map(|shaders| watch(&shaders))
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn args(args: &[&str]) -> Result<Option<Args>, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    fn files(args: &[&str]) -> Vec<wgsl_to_wgpu::ShaderConfig> {
+        match parse_args(args.iter().map(|a| a.to_string())) {
+            Ok(Some(Args {
+                shaders: Shaders::Files(shaders),
+                watch: false,
+            })) => shaders,
+            _ => panic!("expected input and output paths"),
+        }
+    }
+
+    #[test]
+    fn parse_args_options_and_files() {
+        let shaders = files(&[
+            "a.wgsl",
+            "a.rs",
+            "--visibility",
+            "pub(crate)",
+            "b.wgsl",
+            "b.rs",
+        ]);
+
+        let paths: Vec<_> = shaders
+            .iter()
+            .map(|s| (s.input.to_str().unwrap(), s.output.to_str().unwrap()))
+            .collect();
+        assert_eq!(vec![("a.wgsl", "a.rs"), ("b.wgsl", "b.rs")], paths);
+
+        // Options apply to all shaders regardless of their position.
+        for shader in &shaders {
+            assert_eq!("pub(crate)", shader.options.visibility);
+            assert!(shader.options.derive_bytemuck);
+            assert!(!shader.options.derive_encase);
+        }
+    }
+
+    #[test]
+    fn parse_args_no_derive_bytemuck() {
+        let shaders = files(&["--no-derive-bytemuck", "a.wgsl", "a.rs"]);
+        assert!(!shaders[0].options.derive_bytemuck);
+
+        let source = indoc! {r#"
+            struct Uniforms {
+                color: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return uniforms.color;
+            }
+        "#};
+        let actual =
+            wgsl_to_wgpu::create_shader_module(source, "a.wgsl", shaders[0].options.clone())
+                .unwrap();
+        assert!(actual.contains("pub struct Uniforms {"));
+        assert!(!actual.contains("bytemuck"));
+    }
+
+    #[test]
+    fn parse_args_config() {
+        assert!(matches!(
+            args(&["--config", "wgsl_to_wgpu.toml", "--watch"]),
+            Ok(Some(Args { shaders: Shaders::Config(path), watch: true })) if path == std::path::Path::new("wgsl_to_wgpu.toml")
+        ));
+        assert!(args(&["--config", "wgsl_to_wgpu.toml", "a.wgsl", "a.rs"]).is_err());
+    }
+
+    #[test]
+    fn parse_args_invalid() {
+        assert!(args(&["--help", "a.wgsl"]).unwrap().is_none());
+        assert!(args(&[]).is_err());
+        assert!(args(&["a.wgsl"]).is_err());
+        assert!(args(&["--unknown", "a.wgsl", "a.rs"]).is_err());
+        assert!(args(&["a.wgsl", "a.rs", "--visibility"]).is_err());
+    }
+}
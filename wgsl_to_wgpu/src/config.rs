@@ -0,0 +1,782 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::{
+    create_shader_module, CreateModuleError, EmptyStructs, MatrixVectorTypes, WriterOptions,
+};
+
+/// A shader to generate from a configuration file for [generate_from_config].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderConfig {
+    /// The path to the WGSL source file.
+    pub input: PathBuf,
+    /// The path for the generated Rust file.
+    pub output: PathBuf,
+    /// The options from the `[options]` table with any overrides for this shader applied.
+    pub options: WriterOptions,
+}
+
+/// Errors while reading a configuration file or generating its shaders in [generate_from_config] or [build].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file at `path` could not be read or written.
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    /// The configuration file isn't valid.
+    /// The `line` starts from 1.
+    Parse { line: usize, message: String },
+
+    /// Generating the bindings for the shader at `path` failed.
+    Shader {
+        path: PathBuf,
+        error: Box<CreateModuleError>,
+    },
+
+    /// The environment variable set by cargo for build scripts like `OUT_DIR` is missing.
+    MissingEnvVar { name: String },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            ConfigError::Parse { line, message } => write!(f, "{message} on line {line}"),
+            ConfigError::Shader { path, error } => write!(f, "{}: {error}", path.display()),
+            ConfigError::MissingEnvVar { name } => {
+                write!(
+                    f,
+                    "missing environment variable {name} outside of a build script"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { error, .. } => Some(error),
+            ConfigError::Shader { error, .. } => Some(error.as_ref()),
+            ConfigError::Parse { .. } | ConfigError::MissingEnvVar { .. } => None,
+        }
+    }
+}
+
+impl ShaderConfig {
+    /// Generate the bindings for [ShaderConfig::input] and write them to [ShaderConfig::output].
+    /// The generated code includes the WGSL file using its path relative to the output file.
+    /// The output file is only written if its contents changed.
+    pub fn generate(&self) -> Result<(), ConfigError> {
+        let wgsl_source =
+            std::fs::read_to_string(&self.input).map_err(|error| ConfigError::Io {
+                path: self.input.clone(),
+                error,
+            })?;
+        let include_path = include_path(&self.input, &self.output)?;
+
+        let text = create_shader_module(&wgsl_source, &include_path, self.options.clone())
+            .map_err(|error| ConfigError::Shader {
+                path: self.input.clone(),
+                error: Box::new(error),
+            })?;
+
+        write_if_changed(&self.output, &text)
+            .map(|_| ())
+            .map_err(|error| ConfigError::Io {
+                path: self.output.clone(),
+                error,
+            })
+    }
+}
+
+/// Write `contents` to the file at `path` only if the file doesn't already contain `contents`
+/// and return `true` if the file was written.
+///
+/// Build scripts that write generated code to `src` on every build
+/// update the file's modification time and can trigger needless rebuilds.
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+let wgsl_source = std::fs::read_to_string("src/shader.wgsl").unwrap();
+let text = wgsl_to_wgpu::create_shader_module(
+    &wgsl_source,
+    "shader.wgsl",
+    wgsl_to_wgpu::WriterOptions::default(),
+)
+.unwrap();
+wgsl_to_wgpu::write_if_changed("src/shader.rs", &text).unwrap();
+```
+ */
+pub fn write_if_changed(path: impl AsRef<Path>, contents: &str) -> std::io::Result<bool> {
+    let path = path.as_ref();
+    match std::fs::read(path) {
+        Ok(existing) if existing == contents.as_bytes() => Ok(false),
+        _ => std::fs::write(path, contents).map(|_| true),
+    }
+}
+
+/// Generate the bindings for each shader in the configuration file at `path`
+/// and return the shaders so build scripts can watch the inputs for changes.
+///
+/// The configuration uses a subset of TOML with an optional `[options]` table
+/// and a `[[shader]]` table with an `input` and `output` path for each shader.
+/// Paths are relative to the directory containing the configuration file.
+/// Option names match the fields of [WriterOptions]
+/// and can be overridden for individual shaders in their `[[shader]]` table.
+/// [WriterOptions::matrix_vector_types] and [WriterOptions::empty_structs]
+/// use the snake case variant name like `"glam_matrices"` or `"skip"`,
+/// and [WriterOptions::vertex_format_overrides] isn't supported.
+/// [WriterOptions::shared_bind_groups] isn't supported either
+/// since each shader is generated on its own instead of with [crate::create_shader_modules].
+///
+/// The supported subset of TOML is line based:
+/// - table headers `[options]` and `[[shader]]` on their own line
+/// - `key = value` pairs with bare keys like `derive_bytemuck`
+/// - basic strings like `"src/shader.wgsl"` without escape sequences
+/// - literal strings like `'C:\shaders\shader.wgsl'`, which can contain `"` and `\`
+/// - the booleans `true` and `false`
+/// - single line arrays of strings like `["camera", 'light']` with an optional trailing comma
+/// - comments starting with `#` outside of strings
+///
+/// Anything else like escape sequences, multiline strings, numbers, or inline tables
+/// is reported as a [ConfigError::Parse].
+///
+/// # Examples
+/**
+```toml
+# wgsl_to_wgpu.toml
+[options]
+derive_bytemuck = true
+visibility = "pub(crate)"
+
+[[shader]]
+input = "src/shader.wgsl"
+output = "src/shader.rs"
+
+[[shader]]
+input = "src/blur.wgsl"
+output = "src/blur.rs"
+dynamic_offset_bindings = ["camera"]
+```
+
+```rust no_run
+// build.rs
+let shaders = wgsl_to_wgpu::generate_from_config("wgsl_to_wgpu.toml").unwrap();
+println!("cargo:rerun-if-changed=wgsl_to_wgpu.toml");
+for shader in &shaders {
+    println!("cargo:rerun-if-changed={}", shader.input.display());
+}
+```
+ */
+pub fn generate_from_config(path: impl AsRef<Path>) -> Result<Vec<ShaderConfig>, ConfigError> {
+    let shaders = read_config(path)?;
+    for shader in &shaders {
+        shader.generate()?;
+    }
+    Ok(shaders)
+}
+
+/// Generate the bindings for the WGSL file at `path` relative to the crate root from a build script.
+///
+/// The bindings are written to a file in `OUT_DIR` with the same name as the shader like `shader.rs`,
+/// and the returned path is the path of the generated file.
+/// This also prints `cargo:rerun-if-changed` for the shader.
+/// Use [build_with_options] to configure the generated code.
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+wgsl_to_wgpu::build("src/shader.wgsl").unwrap();
+```
+
+```rust ignore
+// main.rs
+mod shader {
+    include!(concat!(env!("OUT_DIR"), "/shader.rs"));
+}
+```
+ */
+pub fn build(path: impl AsRef<Path>) -> Result<PathBuf, ConfigError> {
+    build_with_options(path, WriterOptions::default())
+}
+
+/// The same as [build] but with the given `options` for the generated code.
+pub fn build_with_options(
+    path: impl AsRef<Path>,
+    options: WriterOptions,
+) -> Result<PathBuf, ConfigError> {
+    let manifest_dir = env_var("CARGO_MANIFEST_DIR")?;
+    let out_dir = env_var("OUT_DIR")?;
+    let shader = build_shader(path.as_ref(), &manifest_dir, &out_dir, options);
+
+    println!("cargo:rerun-if-changed={}", shader.input.display());
+    shader.generate()?;
+    Ok(shader.output)
+}
+
+fn env_var(name: &str) -> Result<PathBuf, ConfigError> {
+    std::env::var_os(name)
+        .map(PathBuf::from)
+        .ok_or_else(|| ConfigError::MissingEnvVar {
+            name: name.to_string(),
+        })
+}
+
+fn build_shader(
+    path: &Path,
+    manifest_dir: &Path,
+    out_dir: &Path,
+    options: WriterOptions,
+) -> ShaderConfig {
+    let name = path
+        .file_stem()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy();
+    ShaderConfig {
+        input: manifest_dir.join(path),
+        output: out_dir.join(format!("{name}.rs")),
+        options,
+    }
+}
+
+/// Read the shaders from the configuration file at `path` without generating them.
+/// See [generate_from_config] for the file format.
+pub fn read_config(path: impl AsRef<Path>) -> Result<Vec<ShaderConfig>, ConfigError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|error| ConfigError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(parse_config(&text)?
+        .into_iter()
+        .map(|shader| ShaderConfig {
+            input: dir.join(shader.input),
+            output: dir.join(shader.output),
+            options: shader.options,
+        })
+        .collect())
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    String(String),
+    Bool(bool),
+    Array(Vec<String>),
+}
+
+struct Entry {
+    key: String,
+    value: Value,
+    line: usize,
+}
+
+fn parse_config(text: &str) -> Result<Vec<ShaderConfig>, ConfigError> {
+    let mut options = Vec::new();
+    let mut shaders: Vec<(usize, Vec<Entry>)> = Vec::new();
+    // Entries before any table header aren't allowed.
+    let mut table: Option<&str> = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match line {
+            "[options]" => table = Some("options"),
+            "[[shader]]" => {
+                table = Some("shader");
+                shaders.push((line_number, Vec::new()));
+            }
+            _ if line.starts_with('[') => {
+                return Err(parse_error(
+                    line_number,
+                    format!("unsupported table {line}"),
+                ))
+            }
+            _ => {
+                let (key, value) = line
+                    .split_once('=')
+                    .ok_or_else(|| parse_error(line_number, "expected key = value".to_string()))?;
+                let entry = Entry {
+                    key: key.trim().to_string(),
+                    value: parse_value(value.trim(), line_number)?,
+                    line: line_number,
+                };
+                match table {
+                    Some("options") => options.push(entry),
+                    Some(_) => shaders.last_mut().unwrap().1.push(entry),
+                    None => {
+                        return Err(parse_error(
+                            line_number,
+                            "expected [options] or [[shader]] before values".to_string(),
+                        ))
+                    }
+                }
+            }
+        }
+    }
+
+    let mut default_options = WriterOptions::default();
+    for entry in &options {
+        apply_option(&mut default_options, entry)?;
+    }
+
+    shaders
+        .into_iter()
+        .map(|(line, entries)| {
+            let mut input = None;
+            let mut output = None;
+            let mut options = default_options.clone();
+            for entry in &entries {
+                match entry.key.as_str() {
+                    "input" => input = Some(PathBuf::from(string_value(entry)?)),
+                    "output" => output = Some(PathBuf::from(string_value(entry)?)),
+                    _ => apply_option(&mut options, entry)?,
+                }
+            }
+
+            // Report missing paths on the line of the table header.
+            Ok(ShaderConfig {
+                input: input
+                    .ok_or_else(|| parse_error(line, "missing shader input".to_string()))?,
+                output: output
+                    .ok_or_else(|| parse_error(line, "missing shader output".to_string()))?,
+                options,
+            })
+        })
+        .collect()
+}
+
+fn strip_comment(line: &str) -> &str {
+    split_unquoted(line, '#')[0]
+}
+
+// Split on the delimiter outside of basic and literal strings.
+fn split_unquoted(text: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == delimiter => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            None => (),
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn parse_value(value: &str, line: usize) -> Result<Value, ConfigError> {
+    match value {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => {
+            if let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                let mut items: Vec<_> = split_unquoted(items, ',')
+                    .into_iter()
+                    .map(str::trim)
+                    .collect();
+                // Allow a trailing comma and empty arrays.
+                if items.last() == Some(&"") {
+                    items.pop();
+                }
+                items
+                    .into_iter()
+                    .map(|item| match item {
+                        "" => Err(parse_error(line, format!("empty array item in {value}"))),
+                        _ => parse_string(item, line),
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(Value::Array)
+            } else {
+                parse_string(value, line).map(Value::String)
+            }
+        }
+    }
+}
+
+fn parse_string(value: &str, line: usize) -> Result<String, ConfigError> {
+    let quoted = |quote| {
+        value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+            .filter(|v| !v.contains(quote))
+    };
+
+    // Literal strings don't have escape sequences, so backslashes are kept as is.
+    if let Some(v) = quoted('\'') {
+        return Ok(v.to_string());
+    }
+    match quoted('"') {
+        Some(v) if v.contains('\\') => Err(parse_error(
+            line,
+            format!("escape sequences aren't supported in {value}, use a 'literal string' instead"),
+        )),
+        Some(v) => Ok(v.to_string()),
+        None => Err(parse_error(
+            line,
+            format!("expected a string, boolean, or array but found {value}"),
+        )),
+    }
+}
+
+fn parse_error(line: usize, message: String) -> ConfigError {
+    ConfigError::Parse { line, message }
+}
+
+fn string_value(entry: &Entry) -> Result<String, ConfigError> {
+    match &entry.value {
+        Value::String(value) => Ok(value.clone()),
+        _ => Err(invalid_type(entry, "a string")),
+    }
+}
+
+fn bool_value(entry: &Entry) -> Result<bool, ConfigError> {
+    match &entry.value {
+        Value::Bool(value) => Ok(*value),
+        _ => Err(invalid_type(entry, "a boolean")),
+    }
+}
+
+fn array_value(entry: &Entry) -> Result<Vec<String>, ConfigError> {
+    match &entry.value {
+        Value::Array(value) => Ok(value.clone()),
+        _ => Err(invalid_type(entry, "an array of strings")),
+    }
+}
+
+fn invalid_type(entry: &Entry, expected: &str) -> ConfigError {
+    parse_error(entry.line, format!("expected {expected} for {}", entry.key))
+}
+
+fn apply_option(options: &mut WriterOptions, entry: &Entry) -> Result<(), ConfigError> {
+    match entry.key.as_str() {
+        "derive_bytemuck" => options.derive_bytemuck = bool_value(entry)?,
+        "non_filterable_textures" => options.non_filterable_textures = array_value(entry)?,
+        "non_filtering_samplers" => options.non_filtering_samplers = array_value(entry)?,
+        "dynamic_offset_bindings" => options.dynamic_offset_bindings = array_value(entry)?,
+        "instance_input_structs" => options.instance_input_structs = array_value(entry)?,
+        "owned_bind_groups" => options.owned_bind_groups = bool_value(entry)?,
+        "matrix_vector_types" => {
+            options.matrix_vector_types = match string_value(entry)?.as_str() {
+                "glam_matrices" => MatrixVectorTypes::GlamMatrices,
+                "rust" => MatrixVectorTypes::Rust,
+                "glam" => MatrixVectorTypes::Glam,
+                "nalgebra" => MatrixVectorTypes::Nalgebra,
+                "cgmath" => MatrixVectorTypes::Cgmath,
+                "mint" => MatrixVectorTypes::Mint,
+                other => {
+                    return Err(parse_error(
+                        entry.line,
+                        format!("unsupported matrix_vector_types {other}"),
+                    ))
+                }
+            }
+        }
+        "derive_encase" => options.derive_encase = bool_value(entry)?,
+        "derive_crevice" => options.derive_crevice = bool_value(entry)?,
+        "impl_default" => options.impl_default = bool_value(entry)?,
+        "derive_serde" => options.derive_serde = bool_value(entry)?,
+        "buffer_struct_derives" => options.buffer_struct_derives = array_value(entry)?,
+        "vertex_input_struct_derives" => options.vertex_input_struct_derives = array_value(entry)?,
+        "struct_derives" => options.struct_derives = array_value(entry)?,
+        "wgpu_path" => options.wgpu_path = string_value(entry)?,
+        "bytemuck_path" => options.bytemuck_path = string_value(entry)?,
+        "glam_path" => options.glam_path = string_value(entry)?,
+        "visibility" => options.visibility = string_value(entry)?,
+        "module_name" => options.module_name = Some(string_value(entry)?),
+        "generate_labels" => options.generate_labels = bool_value(entry)?,
+        "embed_spirv" => options.embed_spirv = bool_value(entry)?,
+        "minify_wgsl" => options.minify_wgsl = bool_value(entry)?,
+        "shader_module_from_path" => options.shader_module_from_path = bool_value(entry)?,
+        "type_aliases" => options.type_aliases = bool_value(entry)?,
+        "generate_structs" => options.generate_structs = bool_value(entry)?,
+        "generate_bind_groups" => options.generate_bind_groups = bool_value(entry)?,
+        "generate_vertex_module" => options.generate_vertex_module = bool_value(entry)?,
+        "generate_shader_functions" => options.generate_shader_functions = bool_value(entry)?,
+        "placeholder_bind_groups" => options.placeholder_bind_groups = bool_value(entry)?,
+        "empty_structs" => {
+            options.empty_structs = match string_value(entry)?.as_str() {
+                "generate" => EmptyStructs::Generate,
+                "unit" => EmptyStructs::Unit,
+                "skip" => EmptyStructs::Skip,
+                "error" => EmptyStructs::Error,
+                other => {
+                    return Err(parse_error(
+                        entry.line,
+                        format!("unsupported empty_structs {other}"),
+                    ))
+                }
+            }
+        }
+        key => return Err(parse_error(entry.line, format!("unknown option {key}"))),
+    }
+    Ok(())
+}
+
+fn include_path(input: &Path, output: &Path) -> Result<String, ConfigError> {
+    let canonicalize = |path: &Path| {
+        path.canonicalize().map_err(|error| ConfigError::Io {
+            path: path.to_path_buf(),
+            error,
+        })
+    };
+    let input = canonicalize(input)?;
+    let output_dir = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let output_dir = canonicalize(output_dir)?;
+
+    let input_components: Vec<_> = input.components().collect();
+    let output_components: Vec<_> = output_dir.components().collect();
+    let common = input_components
+        .iter()
+        .zip(&output_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let parents = output_components[common..].iter().map(|_| "..".to_string());
+    let remaining = input_components[common..].iter().map(|c| match c {
+        Component::Normal(name) => name.to_string_lossy().into_owned(),
+        _ => c.as_os_str().to_string_lossy().into_owned(),
+    });
+
+    // Use forward slashes since the path is used in Rust source code.
+    Ok(parents.chain(remaining).collect::<Vec<_>>().join("/"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_config_options_and_overrides() {
+        let text = indoc! {r#"
+            # Options for all shaders.
+            [options]
+            derive_bytemuck = false
+            visibility = "pub(crate)" # trailing comment
+
+            [[shader]]
+            input = "src/shader.wgsl"
+            output = "src/shader.rs"
+
+            [[shader]]
+            input = "src/blur#1.wgsl"
+            output = "src/blur.rs"
+            matrix_vector_types = "glam"
+            empty_structs = "skip"
+            module_name = "blur"
+            dynamic_offset_bindings = ["camera", "light"]
+        "#};
+
+        let options = WriterOptions {
+            derive_bytemuck: false,
+            visibility: "pub(crate)".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                ShaderConfig {
+                    input: PathBuf::from("src/shader.wgsl"),
+                    output: PathBuf::from("src/shader.rs"),
+                    options: options.clone(),
+                },
+                ShaderConfig {
+                    input: PathBuf::from("src/blur#1.wgsl"),
+                    output: PathBuf::from("src/blur.rs"),
+                    options: WriterOptions {
+                        matrix_vector_types: MatrixVectorTypes::Glam,
+                        empty_structs: EmptyStructs::Skip,
+                        module_name: Some("blur".to_string()),
+                        dynamic_offset_bindings: vec!["camera".to_string(), "light".to_string()],
+                        ..options
+                    },
+                },
+            ],
+            parse_config(text).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_config_strings_and_arrays() {
+        let text = indoc! {r#"
+            [options]
+            non_filterable_textures = ["a, b", 'c#d', "e'f",] # comment with "quotes"
+            non_filtering_samplers = []
+            dynamic_offset_bindings = [ 'camera' ]
+
+            [[shader]]
+            input = 'C:\shaders\say "hi".wgsl'
+            output = "out#1.rs"
+        "#};
+
+        let shaders = parse_config(text).unwrap();
+        assert_eq!(1, shaders.len());
+        assert_eq!(
+            PathBuf::from(r#"C:\shaders\say "hi".wgsl"#),
+            shaders[0].input
+        );
+        assert_eq!(PathBuf::from("out#1.rs"), shaders[0].output);
+        assert_eq!(
+            vec!["a, b".to_string(), "c#d".to_string(), "e'f".to_string()],
+            shaders[0].options.non_filterable_textures
+        );
+        assert!(shaders[0].options.non_filtering_samplers.is_empty());
+        assert_eq!(
+            vec!["camera".to_string()],
+            shaders[0].options.dynamic_offset_bindings
+        );
+    }
+
+    #[test]
+    fn parse_config_unsupported_strings() {
+        assert_eq!(
+            (
+                2,
+                r#"escape sequences aren't supported in "C:\\a.wgsl", use a 'literal string' instead"#
+                    .to_string()
+            ),
+            parse_error_line("[[shader]]\ninput = \"C:\\\\a.wgsl\"\n")
+        );
+        assert_eq!(
+            (
+                2,
+                r#"expected a string, boolean, or array but found "a.wgsl"#.to_string()
+            ),
+            parse_error_line("[[shader]]\ninput = \"a.wgsl\n")
+        );
+        assert_eq!(
+            (
+                2,
+                r#"expected a string, boolean, or array but found "a"b""#.to_string()
+            ),
+            parse_error_line("[[shader]]\ninput = \"a\"b\"\n")
+        );
+        assert_eq!(
+            (2, r#"empty array item in ["a", , "b"]"#.to_string()),
+            parse_error_line("[options]\nstruct_derives = [\"a\", , \"b\"]\n")
+        );
+        assert_eq!(
+            (
+                2,
+                "expected a string, boolean, or array but found 1".to_string()
+            ),
+            parse_error_line("[options]\nvisibility = 1\n")
+        );
+    }
+
+    fn parse_error_line(text: &str) -> (usize, String) {
+        match parse_config(text) {
+            Err(ConfigError::Parse { line, message }) => (line, message),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_config_errors() {
+        assert_eq!(
+            (2, "unknown option derive_all".to_string()),
+            parse_error_line("[options]\nderive_all = true\n")
+        );
+        assert_eq!(
+            (2, "expected a boolean for impl_default".to_string()),
+            parse_error_line("[options]\nimpl_default = \"yes\"\n")
+        );
+        assert_eq!(
+            (2, "unsupported empty_structs unused".to_string()),
+            parse_error_line("[options]\nempty_structs = \"unused\"\n")
+        );
+        assert_eq!(
+            (1, "missing shader output".to_string()),
+            parse_error_line("[[shader]]\ninput = \"a.wgsl\"\n")
+        );
+        assert_eq!(
+            (
+                1,
+                "expected [options] or [[shader]] before values".to_string()
+            ),
+            parse_error_line("input = \"a.wgsl\"\n")
+        );
+        assert_eq!(
+            (1, "unsupported table [shaders]".to_string()),
+            parse_error_line("[shaders]\n")
+        );
+    }
+
+    #[test]
+    fn include_path_relative_to_output() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert_eq!(
+            "lib.rs",
+            include_path(&dir.join("src/lib.rs"), &dir.join("src/shader.rs")).unwrap()
+        );
+        assert_eq!(
+            "../lib.rs",
+            include_path(&dir.join("src/lib.rs"), &dir.join("src/bin/shader.rs")).unwrap()
+        );
+        assert_eq!(
+            "src/lib.rs",
+            include_path(&dir.join("src/lib.rs"), &dir.join("shader.rs")).unwrap()
+        );
+    }
+
+    #[test]
+    fn build_shader_paths() {
+        assert_eq!(
+            ShaderConfig {
+                input: PathBuf::from("/crate/src/shaders/model.pass.wgsl"),
+                output: PathBuf::from("/out/model.pass.rs"),
+                options: WriterOptions::default(),
+            },
+            build_shader(
+                Path::new("src/shaders/model.pass.wgsl"),
+                Path::new("/crate"),
+                Path::new("/out"),
+                WriterOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn write_if_changed_only_new_contents() {
+        let path = std::env::temp_dir().join(format!(
+            "wgsl_to_wgpu_write_if_changed_{}.rs",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        assert!(write_if_changed(&path, "a").unwrap());
+        assert!(!write_if_changed(&path, "a").unwrap());
+        assert!(write_if_changed(&path, "b").unwrap());
+        assert_eq!("b", std::fs::read_to_string(&path).unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn generate_from_config_missing_file() {
+        assert!(matches!(
+            generate_from_config("missing/wgsl_to_wgpu.toml"),
+            Err(ConfigError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,29 @@
+use crate::{merge, wgsl, CreateModuleError};
+
+pub fn parse(source: &str, stage: naga::ShaderStage) -> Result<naga::Module, CreateModuleError> {
+    let options = naga::front::glsl::Options::from(stage);
+    naga::front::glsl::Parser::default()
+        .parse(&options, source)
+        .map_err(|errors| {
+            let messages: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+            CreateModuleError::GlslParseError {
+                stage,
+                message: messages.join("\n"),
+                location: errors
+                    .iter()
+                    .find_map(|e| wgsl::source_location(source, e.meta)),
+            }
+        })
+}
+
+// naga has no way to link modules, so the items of each stage are copied into a new module.
+// The GLSL entry points are both named main, so they're renamed to match the WGSL naming convention.
+pub fn merge_stages(
+    vertex: naga::Module,
+    fragment: naga::Module,
+) -> Result<naga::Module, CreateModuleError> {
+    let mut module = naga::Module::default();
+    merge::append_module(&mut module, vertex, |_| "vs_main".to_string())?;
+    merge::append_module(&mut module, fragment, |_| "fs_main".to_string())?;
+    Ok(module)
+}
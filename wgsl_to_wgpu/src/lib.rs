@@ -5,1003 +5,8731 @@
 //! The [create_shader_module] function is intended for use in build scripts.
 //! This facilitates a shader focused workflow where edits to WGSL code are automatically reflected in the corresponding Rust file.
 //! For example, changing the type of a uniform in WGSL will raise a compile error in Rust code using the generated struct to initialize the buffer.
+//! Scalar constants like `let MAX_LIGHTS: u32 = 16u;` are exported as Rust constants with the same name, so CPU code can size arrays and buffers to match.
+//! Generated structs, bind groups, bindings, and functions have doc comments with the WGSL declarations they come from,
+//! so hovering over them in an editor shows the shader side like `var<uniform> camera: Camera`.
+//! Names that are valid in WGSL but reserved in Rust like `ref` use raw identifiers like `r#ref` in the generated code.
+//! Generated items are ordered by name for structs and entry points and by index for bind groups and bindings,
+//! so the output only changes when the shader changes and not with the declaration order or naga version.
+//! The [create_shader_modules] function generates a module for each of several shaders at once.
+//! Setting [WriterOptions::module_name] wraps the output in a named module, so several outputs can be included in one file.
+//! The code for large shaders can be split into separate files for the structs, bind groups, and vertex module with [create_shader_module_files].
+//! Shaders with identical groups like a global camera group can share the bind group types with [WriterOptions::shared_bind_groups].
+//! Projects that only need part of the output like the structs can disable the other sections with [WriterOptions].
+//! Shaders that were already parsed or modified with naga can use [create_shader_module_from_naga] instead.
+//! Shaders that share code with `#include` directives or toggle code with `#ifdef` blocks can be combined with [preprocess] first.
+//! Build scripts can generate bindings into `OUT_DIR` with a single call to [build].
+//! Tools outside of Rust can use the same reflection data as JSON from [create_reflection_json].
+//! Projects with many shaders can list them in a configuration file for [generate_from_config].
+//! The `watch` function enabled by the `watch` feature regenerates shaders whenever their WGSL files change.
 //!
 //! ## Limitations
-//! This project currently supports a small subset of WGSL types and doesn't enforce certain key properties such as field alignment.
+//! This project currently supports a small subset of WGSL types.
+//! Structs used in uniform, storage, or push constant buffers and vertex input structs have explicit padding fields
+//! to match the WGSL memory layout, but other structs don't enforce certain key properties such as field alignment.
 //! It may be necessary to disable running this function for shaders with unsupported types or features.
-//! The current implementation assumes all shader stages are part of a single WGSL source file.
+//! The current implementation assumes all shader stages are part of a single WGSL source file,
+//! so shaders using imports should be composed into a single WGSL source before calling [create_shader_module].
+//! GLSL vertex and fragment shaders can be used with `create_shader_module_glsl` enabled by the `glsl` feature,
+//! and compiled SPIR-V can be used with `create_shader_module_spirv` enabled by the `spirv` feature.
+//! Both embed WGSL written by naga for the parsed module like [create_shader_module_from_naga].
+//!
+//! Shaders are parsed and validated with naga 0.8, so features that require a newer version of naga aren't supported yet:
+//! - `@group(0) @binding(0)` attributes and commas between struct members.
+//!   Use the `[[group(0), binding(0)]]` syntax instead. Parse errors for `@` attributes include a note explaining this.
+//! - `alias` declarations. Use the `type Color = vec4<f32>;` syntax instead.
+//! - Half precision `f16` types and the `enable f16;` directive.
+//! - Binding arrays like `binding_array<texture_2d<f32>, N>`, so bind group layouts are always generated with `count: None`.
+//! - External textures like `var video: texture_external;`, which are rejected with a [CreateModuleError::ParseError].
+//!   The version of wgpu used here doesn't have a binding type for external textures either.
+//! - Uniform and storage buffers with non-struct types like `var<uniform> time: f32;`, which fail validation.
+//! - [naga_oil](https://github.com/bevyengine/naga_oil) `#import` directives, since naga_oil depends on a newer version of naga.
 use indoc::{formatdoc, writedoc};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write;
 
+mod config;
+#[cfg(feature = "glsl")]
+mod glsl;
+#[cfg(any(feature = "glsl", feature = "spirv"))]
+mod merge;
+mod preprocess;
+mod reflection;
+#[cfg(feature = "watch")]
+mod watch;
 mod wgsl;
 
+pub use config::{
+    build, build_with_options, generate_from_config, read_config, write_if_changed, ConfigError,
+    ShaderConfig,
+};
+pub use preprocess::{preprocess, PreprocessError, PreprocessedSource};
+pub use reflection::create_reflection_json;
+#[cfg(feature = "watch")]
+pub use watch::watch;
+
 // TODO: Simplify these templates and indentation?
 // TODO: Structure the code to make it easier to imagine what the output will look like.
 /// Errors while generating Rust source for a WGSl shader module.
 #[derive(Debug, PartialEq, Eq)]
 pub enum CreateModuleError {
+    /// The WGSL source could not be parsed.
+    /// The `message` contains the formatted error with the relevant source lines.
+    ParseError {
+        error: NagaError<naga::front::wgsl::ParseError>,
+        message: String,
+        location: SourceLocation,
+    },
+
+    /// The parsed module is not valid WGSL.
+    /// The `message` contains the error and all of its causes.
+    ValidationError {
+        error: NagaError<Box<naga::WithSpan<naga::valid::ValidationError>>>,
+        message: String,
+        location: Option<SourceLocation>,
+    },
+
     /// Bind group sets must be consecutive and start from 0.
     /// See `bind_group_layouts` for [wgpu::PipelineLayoutDescriptor].
-    NonConsecutiveBindGroups,
+    /// Enable [WriterOptions::placeholder_bind_groups] to fill the gaps with empty layouts instead.
+    /// The `name` and `location` refer to the first binding in the unexpected `group`.
+    NonConsecutiveBindGroups {
+        group: u32,
+        name: String,
+        location: Option<SourceLocation>,
+    },
 
     /// Each binding resource must be associated with exactly one binding index.
-    DuplicateBinding { binding: u32 },
+    /// The `name` and `location` refer to the repeated binding.
+    DuplicateBinding {
+        group: u32,
+        binding: u32,
+        name: String,
+        location: Option<SourceLocation>,
+    },
+
+    /// The type of the binding resource has no corresponding [wgpu::BindingType].
+    /// The `ty` is a description of the WGSL type for error messages.
+    UnsupportedBinding {
+        group: u32,
+        binding: u32,
+        name: String,
+        ty: String,
+        location: Option<SourceLocation>,
+    },
+
+    /// The struct member has no valid representation when deriving `bytemuck::Pod`.
+    /// WGSL `bool` isn't host-shareable, so generated structs can't contain it.
+    /// The `location` refers to the struct definition.
+    UnsupportedStructMember {
+        name: String,
+        member: String,
+        ty: String,
+        location: Option<SourceLocation>,
+    },
+
+    /// The vertex input field has no corresponding [wgpu::VertexFormat].
+    /// Use [WriterOptions::vertex_format_overrides] to choose a format for the `field`.
+    /// The `name` is the vertex input struct, and the `location` refers to the struct definition if there is one.
+    UnsupportedVertexInput {
+        name: String,
+        field: String,
+        ty: String,
+        location: Option<SourceLocation>,
+    },
+
+    /// The GLSL source for the `stage` could not be parsed for `create_shader_module_glsl`.
+    /// The `message` contains all of the errors, and the `location` refers to the first error.
+    #[cfg(feature = "glsl")]
+    GlslParseError {
+        stage: naga::ShaderStage,
+        message: String,
+        location: Option<SourceLocation>,
+    },
+
+    /// naga failed to parse the SPIR-V for `create_shader_module_spirv`.
+    #[cfg(feature = "spirv")]
+    SpirvParseError {
+        error: NagaError<naga::front::spv::Error>,
+    },
+
+    /// An error for the shader with the given `path` from [create_shader_modules].
+    Shader {
+        path: String,
+        error: Box<CreateModuleError>,
+    },
+
+    /// The shader at `path` would generate a module with the same `name` as an earlier shader
+    /// passed to [create_shader_modules].
+    DuplicateModuleName { name: String, path: String },
+
+    /// The generated code isn't valid Rust tokens for `create_shader_module_tokens`.
+    /// This can happen for invalid paths or visibility in [WriterOptions].
+    InvalidTokens { message: String },
+
+    /// naga failed to compile the module to SPIR-V for [WriterOptions::embed_spirv].
+    SpirvError {
+        error: NagaError<naga::back::spv::Error>,
+    },
+
+    /// naga failed to write the module as WGSL for [create_shader_module_from_naga].
+    WgslOutError {
+        error: NagaError<naga::back::wgsl::Error>,
+    },
+
+    /// The `first` and `second` items would generate Rust items or fields with the same `name`.
+    /// This happens for names that only differ in case like entry points `vs_main` and `vsMain`
+    /// or names that match generated items like a struct `CameraBuffer` and the buffer type for `Camera`.
+    /// The items are descriptions like "struct `Camera`" for error messages.
+    NameCollision {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    /// The struct has no members and [WriterOptions::empty_structs] is [EmptyStructs::Error].
+    /// The `location` refers to the struct definition.
+    EmptyStruct {
+        name: String,
+        location: Option<SourceLocation>,
+    },
+}
+
+/// An error from naga in [CreateModuleError].
+/// naga errors don't implement [PartialEq], so errors with the same message compare as equal.
+#[derive(Debug)]
+pub struct NagaError<E>(pub E);
+
+impl<E: std::fmt::Display> PartialEq for NagaError<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl<E: std::fmt::Display> Eq for NagaError<E> {}
+
+/// A position in the WGSL source with lines and columns starting from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+// Describe where a named item is defined for error messages.
+fn write_item_location(
+    f: &mut std::fmt::Formatter<'_>,
+    name: &str,
+    location: &Option<SourceLocation>,
+) -> std::fmt::Result {
+    match location {
+        Some(location) => write!(f, " for `{name}` at {location}"),
+        None => write!(f, " for `{name}`"),
+    }
+}
+
+impl std::fmt::Display for CreateModuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreateModuleError::ParseError { message, .. } => write!(f, "{message}"),
+            CreateModuleError::ValidationError {
+                message, location, ..
+            } => match location {
+                Some(location) => write!(f, "{message} at {location}"),
+                None => write!(f, "{message}"),
+            },
+            CreateModuleError::NonConsecutiveBindGroups {
+                group,
+                name,
+                location,
+            } => {
+                write!(
+                    f,
+                    "bind groups must be consecutive and start from 0 but found group {group}"
+                )?;
+                write_item_location(f, name, location)
+            }
+            CreateModuleError::DuplicateBinding {
+                group,
+                binding,
+                name,
+                location,
+            } => {
+                write!(
+                    f,
+                    "binding {binding} in group {group} is used by more than one resource"
+                )?;
+                write_item_location(f, name, location)
+            }
+            CreateModuleError::UnsupportedBinding {
+                group,
+                binding,
+                name,
+                ty,
+                location,
+            } => {
+                write!(
+                    f,
+                    "binding {binding} in group {group} has unsupported type `{ty}`"
+                )?;
+                write_item_location(f, name, location)
+            }
+            CreateModuleError::UnsupportedStructMember {
+                name,
+                member,
+                ty,
+                location,
+            } => {
+                write!(
+                    f,
+                    "member `{member}` has type `{ty}` which can't be used with bytemuck"
+                )?;
+                write_item_location(f, name, location)
+            }
+            CreateModuleError::UnsupportedVertexInput {
+                name,
+                field,
+                ty,
+                location,
+            } => {
+                write!(
+                    f,
+                    "vertex input `{field}` has type `{ty}` which has no vertex format"
+                )?;
+                write_item_location(f, name, location)
+            }
+            #[cfg(feature = "glsl")]
+            CreateModuleError::GlslParseError {
+                stage,
+                message,
+                location,
+            } => {
+                write!(f, "failed to parse {stage:?} GLSL: {message}")?;
+                match location {
+                    Some(location) => write!(f, " at {location}"),
+                    None => Ok(()),
+                }
+            }
+            #[cfg(feature = "spirv")]
+            CreateModuleError::SpirvParseError { error } => {
+                write!(f, "failed to parse SPIR-V: {}", error.0)
+            }
+            CreateModuleError::Shader { path, error } => write!(f, "{path}: {error}"),
+            CreateModuleError::DuplicateModuleName { name, path } => write!(
+                f,
+                "module name `{name}` for `{path}` is already used by another shader"
+            ),
+            CreateModuleError::InvalidTokens { message } => {
+                write!(f, "failed to parse generated code: {message}")
+            }
+            CreateModuleError::SpirvError { error } => {
+                write!(f, "failed to compile SPIR-V: {}", error.0)
+            }
+            CreateModuleError::WgslOutError { error } => {
+                write!(f, "failed to write WGSL: {}", error.0)
+            }
+            CreateModuleError::NameCollision {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "{first} and {second} both generate the Rust name `{name}`"
+            ),
+            CreateModuleError::EmptyStruct { name, location } => {
+                write!(f, "struct has no members")?;
+                write_item_location(f, name, location)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CreateModuleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CreateModuleError::ParseError { error, .. } => Some(&error.0),
+            CreateModuleError::ValidationError { error, .. } => Some(error.0.as_ref()),
+            CreateModuleError::Shader { error, .. } => Some(error.as_ref()),
+            CreateModuleError::SpirvError { error } => Some(&error.0),
+            #[cfg(feature = "spirv")]
+            CreateModuleError::SpirvParseError { error } => Some(&error.0),
+            CreateModuleError::WgslOutError { error } => Some(&error.0),
+            _ => None,
+        }
+    }
+}
+
+/// Options for configuring the generated Rust code.
+///
+/// Start from [WriterOptions::default] and override only the fields that should change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriterOptions {
+    /// Derive [bytemuck::Pod](https://docs.rs/bytemuck/latest/bytemuck/trait.Pod.html)
+    /// and [bytemuck::Zeroable](https://docs.rs/bytemuck/latest/bytemuck/trait.Zeroable.html)
+    /// for the generated structs.
+    pub derive_bytemuck: bool,
+
+    /// The names of float texture bindings that can't be filtered such as `Rg32Float` textures.
+    /// These use `filterable: false` for [wgpu::TextureSampleType::Float].
+    pub non_filterable_textures: Vec<String>,
+
+    /// The names of sampler bindings that should use [wgpu::SamplerBindingType::NonFiltering].
+    /// Samplers used with non filterable textures must not filter,
+    /// so samplers passed directly to texture sampling functions with a texture from [WriterOptions::non_filterable_textures]
+    /// use `NonFiltering` without being listed here.
+    /// Samplers for other textures like depth textures sampled without filtering still need to be listed.
+    pub non_filtering_samplers: Vec<String>,
+
+    /// The names of uniform or storage buffer bindings that use `has_dynamic_offset: true`.
+    /// The `set` functions for bind groups with these bindings take the dynamic offsets
+    /// in order of their binding index.
+    pub dynamic_offset_bindings: Vec<String>,
+
+    /// The names of vertex input structs that contain per instance data.
+    /// These use [wgpu::VertexStepMode::Instance] for their `STEP_MODE` and generated vertex buffer layouts.
+    pub instance_input_structs: Vec<String>,
+
+    /// Vertex formats for vertex input fields by field name like `"color"` for [wgpu::VertexFormat::Unorm8x4].
+    /// This allows packing vertex data more tightly than the types used in the shader.
+    /// The generated fields use arrays of the format's components like `[u8; 4]`.
+    pub vertex_format_overrides: HashMap<String, wgpu::VertexFormat>,
+
+    /// Generate a `BindGroups` struct that owns the bind groups and dynamic offsets instead of borrowing them.
+    /// This avoids the lifetime on `BindGroups`, and `set_bind_groups` takes a reference to it instead.
+    pub owned_bind_groups: bool,
+
+    /// The Rust types used for WGSL vectors and matrices in the generated structs.
+    pub matrix_vector_types: MatrixVectorTypes,
+
+    /// Derive [encase::ShaderType](https://docs.rs/encase/latest/encase/trait.ShaderType.html)
+    /// instead of bytemuck for structs used in uniform or storage buffers.
+    /// Buffer data should then be written with encase to handle WGSL layout rules.
+    /// Vectors in these structs use glam types for [MatrixVectorTypes::Rust] and [MatrixVectorTypes::GlamMatrices],
+    /// since encase doesn't give arrays like `[f32; 3]` the alignment of WGSL vectors.
+    /// Other structs like vertex inputs are unaffected.
+    pub derive_encase: bool,
+
+    /// Derive [crevice](https://docs.rs/crevice) `AsStd140` for structs used in uniform buffers
+    /// and `AsStd430` for structs used in storage buffers instead of bytemuck.
+    /// This also generates `write_buffer_std140` or `write_buffer_std430` methods
+    /// that write the padded representation to a buffer.
+    /// Vectors use glam types in the same way as [WriterOptions::derive_encase].
+    /// [WriterOptions::derive_encase] takes priority if both options are enabled.
+    pub derive_crevice: bool,
+
+    /// Implement [Default] for the generated structs.
+    /// Fields use their own [Default] implementation, so scalars, vectors, and padding are zeroed.
+    pub impl_default: bool,
+
+    /// Derive [serde](https://docs.rs/serde) `Serialize` and `Deserialize` for the generated structs.
+    /// Serde only supports arrays with up to 32 elements,
+    /// so structs with larger arrays or padding fields won't compile with this option.
+    pub derive_serde: bool,
+
+    /// Additional derives like `"PartialOrd"` for structs used in uniform, storage, or push constant buffers.
+    pub buffer_struct_derives: Vec<String>,
+
+    /// Additional derives for structs used as vertex inputs.
+    pub vertex_input_struct_derives: Vec<String>,
+
+    /// Additional derives for any other structs.
+    pub struct_derives: Vec<String>,
+
+    /// The path used for [wgpu] items in the generated code like `"crate::gpu::wgpu"`.
+    pub wgpu_path: String,
+
+    /// The path used for bytemuck items in the generated code.
+    /// Structs deriving bytemuck's traits also get a `#[bytemuck(crate = "...")]` attribute
+    /// for paths other than `"bytemuck"` so the derive macros use the same path.
+    pub bytemuck_path: String,
+
+    /// The path used for glam types in the generated code.
+    pub glam_path: String,
+
+    /// The visibility of top level items like `"pub(crate)"` or `"pub(super)"`.
+    /// Nested items and struct fields are still `pub` but limited by the visibility of their parent.
+    pub visibility: String,
+
+    /// Wrap the generated code in a module with this name like `pub mod pbr { ... }`.
+    /// This allows including or concatenating the code for multiple shaders in a single Rust file.
+    /// The [WriterOptions::visibility] applies to the wrapping module instead of the top level items.
+    pub module_name: Option<String>,
+
+    /// Label descriptors for shader modules, layouts, and bind groups using the WGSL file name,
+    /// group numbers, and binding names. This makes resources easier to identify in debugging tools.
+    pub generate_labels: bool,
+
+    /// Embed SPIR-V compiled by naga at build time using `wgpu::ShaderSource::SpirV`
+    /// instead of including the WGSL file at `wgsl_include_path`.
+    /// This skips parsing WGSL at runtime but requires enabling the `spirv` feature for wgpu.
+    pub embed_spirv: bool,
+
+    /// Embed the WGSL source without comments and unnecessary whitespace instead of including the file.
+    /// The original `wgsl_include_path` is still used for the label and documentation of the embedded source.
+    /// This has no effect with [WriterOptions::embed_spirv] or [create_shader_module_from_naga].
+    pub minify_wgsl: bool,
+
+    /// Generate a `create_shader_module_from_path` function that reads the WGSL file at runtime.
+    /// Development builds can use this to reload modified shaders without recompiling.
+    pub shader_module_from_path: bool,
+
+    /// Generate Rust type aliases like `pub type Color = [f32; 4];` for WGSL aliases like `type Color = vec4<f32>;`.
+    /// Generated struct fields still use the resolved types.
+    pub type_aliases: bool,
+
+    /// How to generate WGSL structs without any members.
+    pub empty_structs: EmptyStructs,
+
+    /// Generate Rust structs for WGSL structs and the typed buffers for uniform and storage buffers.
+    /// Disabling the structs also disables the sections that use them like the `vertex` module
+    /// and the `from_data` functions for bind groups.
+    pub generate_structs: bool,
+
+    /// Generate the `bind_groups` module with the bind group layouts and bind groups.
+    /// Disabling the bind groups also disables the pipeline layout and compute pipeline functions.
+    pub generate_bind_groups: bool,
+
+    /// Generate the `vertex` module with the vertex attributes for vertex input structs.
+    /// Disabling the vertex module also disables the entry point modules for vertex entry points,
+    /// since their vertex states use the vertex buffer layouts.
+    pub generate_vertex_module: bool,
+
+    /// Generate `create_shader_module`, `create_pipeline_layout`, and the compute pipeline functions.
+    pub generate_shader_functions: bool,
+
+    /// Allow bind group indices with gaps like groups 1 and 2 without group 0
+    /// instead of returning [CreateModuleError::NonConsecutiveBindGroups].
+    /// The pipeline layout uses an empty bind group layout for each missing group.
+    /// This matches engines that reserve fixed group slots for their own bind groups.
+    pub placeholder_bind_groups: bool,
+
+    /// Share the bind group types between shaders in [create_shader_modules]
+    /// that declare a group with identical bindings at the same group index.
+    /// The shared groups are generated once in a `layouts` module and reexported by each shader's `bind_groups` module,
+    /// so a bind group like a global camera group can be set for pipelines from different shaders.
+    /// Shared bind groups don't have a `from_data` function since the buffer types are different for each shader.
+    pub shared_bind_groups: bool,
+}
+
+/// The library used for vector and matrix types in generated code.
+///
+/// Matrices with three rows have padded columns in WGSL,
+/// so these always use arrays like `[[f32; 4]; 3]` regardless of the selected library.
+/// Types without a corresponding library type such as non square glam matrices also use arrays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixVectorTypes {
+    /// Arrays like `[f32; 4]` for vectors and [glam](https://docs.rs/glam) types
+    /// like `glam::Mat4` for square matrices.
+    /// This is the default and matches the output of earlier versions.
+    GlamMatrices,
+    /// Arrays like `[f32; 4]` or `[[f32; 4]; 4]` without any additional dependencies.
+    Rust,
+    /// [glam](https://docs.rs/glam) types like `glam::Vec4` or `glam::Mat4`.
+    Glam,
+    /// [nalgebra](https://docs.rs/nalgebra) types like `nalgebra::SVector<f32, 4>` or `nalgebra::SMatrix<f32, 4, 4>`.
+    Nalgebra,
+    /// [cgmath](https://docs.rs/cgmath) types like `cgmath::Vector4<f32>` or `cgmath::Matrix4<f32>`.
+    Cgmath,
+    /// [mint](https://docs.rs/mint) types like `mint::Vector4<f32>` or `mint::ColumnMatrix4<f32>`.
+    Mint,
+}
+
+/// The code generated for WGSL structs without any members.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyStructs {
+    /// Empty structs with the same derives as other structs like `pub struct Empty {}`.
+    Generate,
+    /// Unit structs like `pub struct Empty;` without `#[repr(C)]` or the bytemuck, encase, or crevice derives.
+    Unit,
+    /// Don't generate empty structs.
+    /// Generated code that uses an empty struct in a field or binding won't compile.
+    Skip,
+    /// Return [CreateModuleError::EmptyStruct] for the first empty struct.
+    Error,
+}
+
+impl Default for WriterOptions {
+    fn default() -> Self {
+        Self {
+            derive_bytemuck: true,
+            non_filterable_textures: Vec::new(),
+            non_filtering_samplers: Vec::new(),
+            dynamic_offset_bindings: Vec::new(),
+            instance_input_structs: Vec::new(),
+            vertex_format_overrides: HashMap::new(),
+            owned_bind_groups: false,
+            matrix_vector_types: MatrixVectorTypes::GlamMatrices,
+            derive_encase: false,
+            derive_crevice: false,
+            impl_default: false,
+            derive_serde: false,
+            buffer_struct_derives: Vec::new(),
+            vertex_input_struct_derives: Vec::new(),
+            struct_derives: Vec::new(),
+            wgpu_path: "wgpu".to_string(),
+            bytemuck_path: "bytemuck".to_string(),
+            glam_path: "glam".to_string(),
+            visibility: "pub".to_string(),
+            module_name: None,
+            generate_labels: true,
+            embed_spirv: false,
+            minify_wgsl: false,
+            shader_module_from_path: false,
+            type_aliases: false,
+            empty_structs: EmptyStructs::Generate,
+            generate_structs: true,
+            generate_bind_groups: true,
+            generate_vertex_module: true,
+            generate_shader_functions: true,
+            placeholder_bind_groups: false,
+            shared_bind_groups: false,
+        }
+    }
 }
 
 /// Parses the WGSL shader from `wgsl_source` and returns the generated Rust module's source code.
 ///
 /// The `wgsl_include_path` should be a valid path for the `include_wgsl!` macro used in the generated file.
+/// The `options` control what code is generated. See [WriterOptions] for details.
 ///
 /// # Examples
 /// This function is intended to be called at build time such as in a build script.
 /**
 ```rust no_run
 // build.rs
-fn main() {
-    let wgsl_source = std::fs::read_to_string("src/shader.wgsl").unwrap();
-    let text = wgsl_to_wgpu::create_shader_module(&wgsl_source, "shader.wgsl").unwrap();
-    std::fs::write("src/shader.rs", text.as_bytes()).unwrap();
-}
+let wgsl_source = std::fs::read_to_string("src/shader.wgsl").unwrap();
+let text = wgsl_to_wgpu::create_shader_module(
+    &wgsl_source,
+    "shader.wgsl",
+    wgsl_to_wgpu::WriterOptions::default(),
+)
+.unwrap();
+std::fs::write("src/shader.rs", text.as_bytes()).unwrap();
 ```
  */
 pub fn create_shader_module(
     wgsl_source: &str,
     wgsl_include_path: &str,
+    options: WriterOptions,
 ) -> Result<String, CreateModuleError> {
-    let module = naga::front::wgsl::parse_str(wgsl_source).unwrap();
-
-    let bind_group_data = wgsl::get_bind_group_data(&module)?;
-
-    let mut output = String::new();
-    let shader_stages = wgsl::shader_stages(&module);
+    let module = parse_wgsl(wgsl_source)?;
+    write_shader_module(
+        &module,
+        wgsl_source,
+        wgsl_include_path,
+        ModuleSource::File,
+        options,
+        &BTreeMap::new(),
+    )
+}
 
-    // Write all the structs, including uniforms and entry function inputs.
-    write_structs(&mut output, 0, &module);
+fn parse_wgsl(wgsl_source: &str) -> Result<naga::Module, CreateModuleError> {
+    naga::front::wgsl::parse_str(wgsl_source).map_err(|error| {
+        let (line, column) = error.location(wgsl_source);
+        let mut message = error.emit_to_string(wgsl_source);
+        // The first error for newer WGSL is often unrelated to attributes like commas in structs.
+        if wgsl::uses_at_attributes(wgsl_source) {
+            message += "note: attributes like `@group(0) @binding(0)` require a newer version of naga \
+                        than the one used by wgsl_to_wgpu, so use `[[group(0), binding(0)]]` instead\n";
+        }
+        CreateModuleError::ParseError {
+            message,
+            location: SourceLocation { line, column },
+            error: NagaError(error),
+        }
+    })
+}
 
-    // TODO: Avoid having a dependency on naga here?
-    write_bind_groups_module(&mut output, &bind_group_data, shader_stages);
-    write_vertex_module(&mut output, &module);
+// Catch invalid shaders at build time instead of when creating the shader module.
+fn validate_module(
+    module: &naga::Module,
+    wgsl_source: &str,
+) -> Result<naga::valid::ModuleInfo, CreateModuleError> {
+    wgsl::validate(module).map_err(|error| {
+        let location = error
+            .spans()
+            .find_map(|(span, _)| wgsl::source_location(wgsl_source, *span));
+        CreateModuleError::ValidationError {
+            message: validation_error_message(&error),
+            location,
+            error: NagaError(Box::new(error)),
+        }
+    })
+}
 
-    writedoc!(
-        output,
-        r#"
-            pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {{
-                device.create_shader_module(&wgpu::ShaderModuleDescriptor {{
-                    label: None,
-                    source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!("{wgsl_include_path}")))
-                }})
-            }}
-        "#
+/// Generates the Rust module's source code from an already parsed `module`
+/// such as a module that was modified after parsing with naga.
+///
+/// The WGSL source for the module is optional and only used for the line and column of errors.
+/// The generated code embeds WGSL written by naga for `module` when creating the shader module,
+/// so changes to the module after parsing are also part of the shader.
+/// Enable [WriterOptions::embed_spirv] to embed SPIR-V compiled from `module` instead.
+/// The `wgsl_include_path` is only used for labels and documentation.
+/// See [create_shader_module] for details.
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+let wgsl_source = std::fs::read_to_string("src/shader.wgsl").unwrap();
+let module = naga::front::wgsl::parse_str(&wgsl_source).unwrap();
+let text = wgsl_to_wgpu::create_shader_module_from_naga(
+    &module,
+    Some(&wgsl_source),
+    "shader.wgsl",
+    wgsl_to_wgpu::WriterOptions::default(),
+)
+.unwrap();
+std::fs::write("src/shader.rs", text.as_bytes()).unwrap();
+```
+ */
+pub fn create_shader_module_from_naga(
+    module: &naga::Module,
+    wgsl_source: Option<&str>,
+    wgsl_include_path: &str,
+    options: WriterOptions,
+) -> Result<String, CreateModuleError> {
+    // Spans can't be converted to locations without the source.
+    write_shader_module(
+        module,
+        wgsl_source.unwrap_or_default(),
+        wgsl_include_path,
+        ModuleSource::Naga,
+        options,
+        &BTreeMap::new(),
     )
-    .unwrap();
-
-    // TODO: Find a cleaner way of doing this?
-    let bind_group_layouts = bind_group_data
-        .iter()
-        .map(|(group_no, _)| {
-            format!("&bind_groups::BindGroup{group_no}::get_bind_group_layout(device),")
-        })
-        .collect::<Vec<String>>()
-        .join("\n            ");
+}
 
-    writedoc!(
-        output,
-        r#"
-            pub fn create_pipeline_layout(device: &wgpu::Device) -> wgpu::PipelineLayout {{
-                device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {{
-                    label: None,
-                    bind_group_layouts: &[
-                        {bind_group_layouts}
-                    ],
-                    push_constant_ranges: &[],
-                }})
-            }}
-        "#
-    )
-    .unwrap();
+/// Generates the Rust module's source code for a GLSL vertex shader and fragment shader.
+///
+/// Each stage is parsed with naga's GLSL frontend and combined into a single module
+/// with the entry points `vs_main` and `fs_main`.
+/// The outputs of each stage are generated as the structs `VsMainOutput` and `FsMainOutput`.
+/// Resources declared in both stages must use the same type for their binding.
+/// The generated code embeds WGSL written by naga for the combined module like [create_shader_module_from_naga].
+/// The `shader_path` is only used for labels and documentation.
+/// This requires the `glsl` feature.
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+let vertex_source = std::fs::read_to_string("src/shader.vert").unwrap();
+let fragment_source = std::fs::read_to_string("src/shader.frag").unwrap();
+let text = wgsl_to_wgpu::create_shader_module_glsl(
+    &vertex_source,
+    &fragment_source,
+    "shader",
+    wgsl_to_wgpu::WriterOptions::default(),
+)
+.unwrap();
+std::fs::write("src/shader.rs", text.as_bytes()).unwrap();
+```
+ */
+#[cfg(feature = "glsl")]
+pub fn create_shader_module_glsl(
+    vertex_source: &str,
+    fragment_source: &str,
+    shader_path: &str,
+    options: WriterOptions,
+) -> Result<String, CreateModuleError> {
+    let vertex = glsl::parse(vertex_source, naga::ShaderStage::Vertex)?;
+    let fragment = glsl::parse(fragment_source, naga::ShaderStage::Fragment)?;
+    let module = glsl::merge_stages(vertex, fragment)?;
+    create_shader_module_from_naga(&module, None, shader_path, options)
+}
 
-    Ok(output)
+/// Generates the Rust module's source code for compiled SPIR-V such as the output of glslc or DXC.
+///
+/// The `spirv` is parsed with naga's SPIR-V frontend, and the entry points keep their names.
+/// SPIR-V without debug information has no names for structs, fields, and bindings,
+/// so these use generated names like `Struct1`, `member0`, or `group0_binding0`.
+/// The generated code embeds WGSL written by naga for the module like [create_shader_module_from_naga].
+/// The `shader_path` is only used for labels and documentation.
+/// This requires the `spirv` feature.
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+let spirv = std::fs::read("src/shader.spv").unwrap();
+let text = wgsl_to_wgpu::create_shader_module_spirv(
+    &spirv,
+    "shader.spv",
+    wgsl_to_wgpu::WriterOptions::default(),
+)
+.unwrap();
+std::fs::write("src/shader.rs", text.as_bytes()).unwrap();
+```
+ */
+#[cfg(feature = "spirv")]
+pub fn create_shader_module_spirv(
+    spirv: &[u8],
+    shader_path: &str,
+    options: WriterOptions,
+) -> Result<String, CreateModuleError> {
+    // Parse the same way as wgpu does for SPIR-V shader sources.
+    let spv_options = naga::front::spv::Options {
+        adjust_coordinate_space: false,
+        ..Default::default()
+    };
+    let parsed = naga::front::spv::parse_u8_slice(spirv, &spv_options).map_err(|error| {
+        CreateModuleError::SpirvParseError {
+            error: NagaError(error),
+        }
+    })?;
+    let mut module = naga::Module::default();
+    merge::append_module(&mut module, parsed, |name| name.to_string())?;
+    create_shader_module_from_naga(&module, None, shader_path, options)
 }
 
-// Apply indentation to each level.
-fn indent<S: Into<String>>(str: S, level: usize) -> String {
-    str.into()
-        .lines()
-        .map(|l| " ".repeat(level) + l)
-        .collect::<Vec<String>>()
-        .join("\n")
+fn get_bind_group_data<'a>(
+    module: &'a naga::Module,
+    info: &naga::valid::ModuleInfo,
+    wgsl_source: &str,
+    options: &WriterOptions,
+) -> Result<BTreeMap<u32, wgsl::GroupData<'a>>, CreateModuleError> {
+    if options.placeholder_bind_groups {
+        wgsl::get_sparse_bind_group_data(module, info, wgsl_source)
+    } else {
+        wgsl::get_bind_group_data(module, info, wgsl_source)
+    }
 }
 
-// Assume the input is already unindented with indoc.
-fn write_indented<W: Write, S: Into<String>>(w: &mut W, level: usize, str: S) {
-    writeln!(w, "{}", indent(str, level)).unwrap();
+// The shader module uses the WGSL file unless the module may not match the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModuleSource {
+    File,
+    Naga,
 }
 
-fn write_vertex_module<W: Write>(f: &mut W, module: &naga::Module) {
-    writeln!(f, "pub mod vertex {{").unwrap();
+fn write_shader_module(
+    module: &naga::Module,
+    wgsl_source: &str,
+    wgsl_include_path: &str,
+    module_source: ModuleSource,
+    options: WriterOptions,
+    shared_groups: &BTreeMap<u32, String>,
+) -> Result<String, CreateModuleError> {
+    // Items in a wrapper module are already limited by the visibility of the wrapper.
+    let item_options = match options.module_name {
+        Some(_) => WriterOptions {
+            visibility: "pub".to_string(),
+            ..options.clone()
+        },
+        None => options.clone(),
+    };
+    let sections = write_module_sections(
+        module,
+        wgsl_source,
+        wgsl_include_path,
+        module_source,
+        &item_options,
+        &item_options.visibility,
+        shared_groups,
+    )?;
+    let output = [
+        sections.constants,
+        sections.structs,
+        sections.bind_groups,
+        sections.vertex,
+        sections.items,
+    ]
+    .concat();
 
-    // TODO: This is redundant with above?
-    write_vertex_input_structs(f, module);
+    Ok(wrap_module(output, &options))
+}
 
-    writeln!(f, "}}").unwrap();
+// Each section is written separately to support writing the modules to separate files.
+#[derive(Default)]
+struct ModuleSections {
+    constants: String,
+    structs: String,
+    bind_groups: String,
+    vertex: String,
+    items: String,
 }
 
-// TODO: Test this?
-fn write_vertex_input_structs<W: Write>(f: &mut W, module: &naga::Module) {
-    let vertex_inputs = wgsl::get_vertex_input_structs(module);
-    for input in vertex_inputs {
-        let name = input.name;
+fn write_module_sections(
+    module: &naga::Module,
+    wgsl_source: &str,
+    wgsl_include_path: &str,
+    module_source: ModuleSource,
+    options: &WriterOptions,
+    struct_visibility: &str,
+    shared_groups: &BTreeMap<u32, String>,
+) -> Result<ModuleSections, CreateModuleError> {
+    let options = &with_inferred_samplers(module, options);
+    let info = validate_module(module, wgsl_source)?;
 
-        let count = input.fields.len();
-        let attributes = input
-            .fields
-            .iter()
-            .map(|(location, m)| {
-                let format = wgsl::vertex_format(&module.types[m.ty]);
-                // TODO: Will the debug implementation always work with the macro?
-                format!("{location} => {:?}", format)
-            })
-            .collect::<Vec<_>>()
-            .join(", ");
+    let bind_group_data = get_bind_group_data(module, &info, wgsl_source, options)?;
+    check_name_collisions(module, &bind_group_data, options)?;
 
-        // TODO: Account for alignment/padding?
-        let size_in_bytes: u64 = input
-            .fields
-            .iter()
-            .map(|(_, m)| wgsl::vertex_format(&module.types[m.ty]).size())
-            .sum();
+    if options.derive_bytemuck {
+        wgsl::check_bool_members(module, wgsl_source)?;
+    }
+    wgsl::check_vertex_inputs(module, wgsl_source, &options.vertex_format_overrides)?;
+    if options.empty_structs == EmptyStructs::Error {
+        wgsl::check_empty_structs(module, wgsl_source)?;
+    }
 
-        // The vertex input structs should already be written at this point.
-        // TODO: Support vertex inputs that aren't in a struct.
-        write_indented(
-            f,
-            4,
-            formatdoc!(
-                r#"
-                    impl super::{name} {{
-                        pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; {count}] = wgpu::vertex_attr_array![{attributes}];
-                        /// The total size in bytes of all fields without considering padding or alignment.
-                        pub const SIZE_IN_BYTES: u64 = {size_in_bytes};
-                    }}
-                "#
-            ),
+    let mut sections = ModuleSections::default();
+    let shader_stages = wgsl::shader_stages(module);
+
+    let file_name = shader_file_name(wgsl_include_path);
+    let label_prefix = options.generate_labels.then_some(file_name.as_str());
+    let shader_module_label = quote_label(label_prefix, "");
+    let pipeline_layout_label = quote_label(label_prefix, " pipeline layout");
+
+    write_constants(&mut sections.constants, module, options);
+    if options.type_aliases {
+        write_type_aliases(&mut sections.constants, module, options);
+    }
+
+    // Write all the structs, including uniforms and entry function inputs.
+    if options.generate_structs {
+        let struct_options = WriterOptions {
+            visibility: struct_visibility.to_string(),
+            ..options.clone()
+        };
+        write_structs(&mut sections.structs, 0, module, &struct_options);
+        write_buffer_wrappers(
+            &mut sections.structs,
+            0,
+            module,
+            label_prefix,
+            &struct_options,
         );
     }
-}
 
-// TODO: Take an iterator instead?
-fn write_bind_groups_module<W: Write>(
-    f: &mut W,
-    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
-    shader_stages: wgpu::ShaderStages,
-) {
-    writeln!(f, "pub mod bind_groups {{").unwrap();
+    // TODO: Avoid having a dependency on naga here?
+    if options.generate_bind_groups {
+        write_bind_groups_module(
+            &mut sections.bind_groups,
+            module,
+            &bind_group_data,
+            shared_groups,
+            label_prefix,
+            options,
+        )?;
+    }
+    let vertex_module = options.generate_vertex_module && options.generate_structs;
+    if vertex_module {
+        write_vertex_module(&mut sections.vertex, module, options);
+    }
 
-    for (group_no, group) in bind_group_data {
-        writeln!(f, "    pub struct BindGroup{group_no}(wgpu::BindGroup);").unwrap();
+    let output = &mut sections.items;
+    write_entry_points(output, module, vertex_module, options);
+
+    write_device_requirements(output, module, &bind_group_data, options);
 
-        write_bind_group_layout(f, 4, *group_no, group);
-        write_bind_group_layout_descriptor(f, 4, *group_no, group, shader_stages);
-        impl_bind_group(f, 4, *group_no, group, shader_stages);
+    if options.generate_shader_functions {
+        // The source only matches the module when the module was parsed from the file.
+        let file_source = (module_source == ModuleSource::File).then_some(wgsl_source);
+        write_create_shader_module(
+            output,
+            module,
+            &info,
+            file_source,
+            wgsl_include_path,
+            &shader_module_label,
+            options,
+        )?;
     }
 
-    writeln!(f, "    pub struct BindGroups<'a> {{").unwrap();
-    for group_no in bind_group_data.keys() {
-        writeln!(
-            f,
-            "        pub bind_group{group_no}: &'a BindGroup{group_no},"
-        )
-        .unwrap();
+    let push_constant = wgsl::get_push_constant(module);
+    if let Some(push_constant) = &push_constant {
+        write_push_constants(output, module, push_constant, shader_stages, options);
     }
-    writeln!(f, "    }}").unwrap();
+    let push_constant_ranges = if push_constant.is_some() {
+        "&[PUSH_CONSTANT_RANGE]"
+    } else {
+        "&[]"
+    };
 
-    // TODO: Support compute shader with vertex/fragment in the same module?
-    let is_compute = shader_stages == wgpu::ShaderStages::COMPUTE;
+    // The pipeline layout uses the bind group layouts from the bind groups module.
+    if options.generate_shader_functions && options.generate_bind_groups {
+        write_pipeline_layout(
+            output,
+            &bind_group_data,
+            push_constant_ranges,
+            &pipeline_layout_label,
+            options,
+        );
+        write_compute_pipelines(output, module, label_prefix, options);
+    }
 
-    write_set_bind_groups(f, 4, bind_group_data, is_compute);
+    Ok(sections)
+}
 
-    writeln!(f, "}}").unwrap();
+// Samplers used with non filterable textures must not filter,
+// so these don't need to be configured in addition to the textures.
+fn with_inferred_samplers(module: &naga::Module, options: &WriterOptions) -> WriterOptions {
+    let mut options = options.clone();
+    for name in wgsl::samplers_used_with_textures(module, &options.non_filterable_textures) {
+        if !options.non_filtering_samplers.contains(&name) {
+            options.non_filtering_samplers.push(name);
+        }
+    }
+    options
 }
 
-fn write_set_bind_groups<W: Write>(
-    f: &mut W,
-    indent: usize,
-    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
-    is_compute: bool,
-) {
-    let render_pass = if is_compute {
-        "wgpu::ComputePass<'a>"
-    } else {
-        "wgpu::RenderPass<'a>"
-    };
+// Use the file name to identify resources from this module in debugging tools.
+fn shader_file_name(wgsl_include_path: &str) -> String {
+    std::path::Path::new(wgsl_include_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
 
-    write_indented(
-        f,
-        indent,
-        formatdoc!(
+// The shader source is embedded as SPIR-V, WGSL from naga, minified WGSL, or included from the WGSL file.
+fn write_create_shader_module(
+    f: &mut String,
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+    file_source: Option<&str>,
+    wgsl_include_path: &str,
+    shader_module_label: &str,
+    options: &WriterOptions,
+) -> Result<(), CreateModuleError> {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    if options.embed_spirv {
+        let words = wgsl::spirv(module, info).map_err(|error| CreateModuleError::SpirvError {
+            error: NagaError(error),
+        })?;
+        write_spirv_shader_module(f, &words, wgsl_include_path, shader_module_label, options);
+    } else if file_source.is_none() {
+        let wgsl = wgsl::wgsl(module, info).map_err(|error| CreateModuleError::WgslOutError {
+            error: NagaError(error),
+        })?;
+        writedoc!(
+            f,
             r#"
-            pub fn set_bind_groups<'a>(
-                pass: &mut {render_pass},
-                bind_groups: BindGroups<'a>,
-            ) {{
+                /// Create the shader module for `{wgsl_include_path}`.
+                {visibility} fn create_shader_module(device: &{wgpu}::Device) -> {wgpu}::ShaderModule {{
+                    device.create_shader_module(&{wgpu}::ShaderModuleDescriptor {{
+                        label: {shader_module_label},
+                        source: {wgpu}::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(SHADER_WGSL))
+                    }})
+                }}
+                /// The WGSL for `{wgsl_include_path}` written by naga from the module used to generate this code.
+                const SHADER_WGSL: &str = {wgsl:?};
             "#
-        ),
-    );
-
-    // The set function for each bind group already sets the index.
-    for group_no in bind_group_data.keys() {
-        write_indented(
+        )
+        .unwrap();
+    } else if let Some(wgsl_source) = file_source.filter(|_| options.minify_wgsl) {
+        let minified = wgsl::minify(wgsl_source);
+        writedoc!(
             f,
-            indent + 4,
-            format!("bind_groups.bind_group{group_no}.set(pass);"),
-        );
+            r#"
+                /// Create the shader module for `{wgsl_include_path}`.
+                {visibility} fn create_shader_module(device: &{wgpu}::Device) -> {wgpu}::ShaderModule {{
+                    device.create_shader_module(&{wgpu}::ShaderModuleDescriptor {{
+                        label: {shader_module_label},
+                        source: {wgpu}::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(SHADER_WGSL))
+                    }})
+                }}
+                /// The source of `{wgsl_include_path}` without comments and unnecessary whitespace.
+                const SHADER_WGSL: &str = {minified:?};
+            "#
+        )
+        .unwrap();
+    } else {
+        writedoc!(
+            f,
+            r#"
+                /// Create the shader module for `{wgsl_include_path}`.
+                {visibility} fn create_shader_module(device: &{wgpu}::Device) -> {wgpu}::ShaderModule {{
+                    device.create_shader_module(&{wgpu}::ShaderModuleDescriptor {{
+                        label: {shader_module_label},
+                        source: {wgpu}::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!("{wgsl_include_path}")))
+                    }})
+                }}
+            "#
+        )
+        .unwrap();
     }
-    write_indented(f, indent, "}");
+
+    if options.shader_module_from_path {
+        write_shader_module_from_path(f, shader_module_label, options);
+    }
+    Ok(())
 }
 
-fn write_structs<W: Write>(f: &mut W, indent: usize, module: &naga::Module) {
-    // Create matching Rust structs for WGSL structs.
-    // The goal is to eventually have safe ways to initialize uniform buffers.
+fn write_pipeline_layout(
+    f: &mut String,
+    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
+    push_constant_ranges: &str,
+    pipeline_layout_label: &str,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    // TODO: Find a cleaner way of doing this?
+    let bind_group_layouts = (0..wgsl::bind_group_count(bind_group_data))
+        .map(|group_no| {
+            if bind_group_data.contains_key(&group_no) {
+                format!("&layouts.bind_group_layout{group_no},")
+            } else {
+                "&layouts.empty_bind_group_layout,".to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n            ");
 
-    // TODO: How to provide a convenient way to work with these types.
-    // Users will want to either a) create a new buffer each type or b) reuse an existing buffer.
-    // It might not make sense from a performance perspective to constantly create new resources.
-    // This requires the user to keep track of the buffer separately from the BindGroup itself.
+    writedoc!(
+        f,
+        r#"
+            /// Create the pipeline layout with new layouts for all bind groups in the shader.
+            {visibility} fn create_pipeline_layout(device: &{wgpu}::Device) -> {wgpu}::PipelineLayout {{
+                create_pipeline_layout_with_layouts(device, &bind_groups::BindGroupLayouts::new(device))
+            }}
+            /// Create the pipeline layout with the existing bind group `layouts`.
+            {visibility} fn create_pipeline_layout_with_layouts(
+                device: &{wgpu}::Device,
+                layouts: &bind_groups::BindGroupLayouts,
+            ) -> {wgpu}::PipelineLayout {{
+                device.create_pipeline_layout(&{wgpu}::PipelineLayoutDescriptor {{
+                    label: {pipeline_layout_label},
+                    bind_group_layouts: &[
+                        {bind_group_layouts}
+                    ],
+                    push_constant_ranges: {push_constant_ranges},
+                }})
+            }}
+        "#
+    )
+    .unwrap();
+}
 
-    // This is a UniqueArena, so types will only be defined once.
-    for (_, t) in module.types.iter() {
-        if let naga::TypeInner::Struct { members, .. } = &t.inner {
-            let name = t.name.as_ref().unwrap();
-            // TODO: Enforce std140 with crevice for uniform buffers to be safe?
-            write_indented(
-                f,
-                indent,
-                formatdoc!(
-                    r"
-                        #[repr(C)]
-                        #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
-                        pub struct {name} {{
-                        "
-                ),
-            );
+// Duplicate definitions would fail to compile with errors pointing at the generated code instead of the shader.
+fn check_name_collisions(
+    module: &naga::Module,
+    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
+    options: &WriterOptions,
+) -> Result<(), CreateModuleError> {
+    // Types and modules share a namespace, and so do constants and functions.
+    let mut types = NameSet::default();
+    let mut values = NameSet::default();
+
+    for name in ["bind_groups", "vertex", "VerifyDeviceError"] {
+        types.insert(name.to_string(), format!("generated item `{name}`"))?;
+    }
+    for name in [
+        "REQUIRED_FEATURES",
+        "required_limits",
+        "verify_device",
+        "create_shader_module",
+        "create_pipeline_layout",
+        "create_pipeline_layout_with_layouts",
+    ] {
+        values.insert(name.to_string(), format!("generated item `{name}`"))?;
+    }
+    if !module.entry_points.is_empty() {
+        for name in ["EntryPoint", "entry_points"] {
+            types.insert(name.to_string(), format!("generated item `{name}`"))?;
+        }
+    }
+
+    // Entry point modules are nested in the entry_points module.
+    let mut entry_modules = NameSet::default();
+    for entry in wgsl::sorted_entry_points(module) {
+        let item = format!("entry point `{}`", entry.name);
+        let module_name = rust_ident(&name_words(&entry.name).join("_"));
+        entry_modules.insert(module_name, item.clone())?;
+        values.insert(entry_point_const_name(&entry.name), item)?;
+    }
+
+    let wrapper_structs = buffer_wrapper_structs(module, options);
+    for (handle, ty) in wgsl::sorted_struct_types(module) {
+        if let naga::TypeInner::Struct { members, span } = &ty.inner {
+            if members.is_empty() && options.empty_structs == EmptyStructs::Skip {
+                continue;
+            }
+            let name = ty.name.as_ref().unwrap();
+            let item = format!("struct `{name}`");
+            types.insert(rust_ident(name), item.clone())?;
+            if wrapper_structs.contains_key(&handle) {
+                let buffer_name = format!("{}Buffer", rust_ident(name));
+                types.insert(buffer_name, format!("the buffer type for {item}"))?;
+            }
+
+            // Padding fields use names like "_padding0".
+            let mut fields = NameSet::default();
+            let padded_span = Some(*span);
+            for field in struct_fields(module, members, options, padded_span) {
+                let field_item = format!("field `{}` of {item}", field.name);
+                fields.insert(field.name, field_item)?;
+            }
+        }
+    }
+    for input in wgsl::get_vertex_input_structs(module) {
+        let item = format!("vertex input struct `{}`", input.name);
+        if input.is_synthesized {
+            types.insert(rust_ident(&input.name), item.clone())?;
+        }
+
+        // Vertex input structs have their own padding fields.
+        let mut fields = NameSet::default();
+        for field in vertex_input_fields(module, &input, options) {
+            let field_item = format!("field `{}` of {item}", field.name);
+            fields.insert(field.name, field_item)?;
+        }
+    }
+    if options.type_aliases {
+        for (name, _) in type_aliases(module) {
+            types.insert(name.clone(), format!("type alias `{name}`"))?;
+        }
+    }
+
+    for (_, constant) in module.constants.iter() {
+        if let Some(name) = &constant.name {
+            if wgsl::rust_constant(constant).is_some() {
+                values.insert(name.clone(), format!("constant `{name}`"))?;
+            }
+        }
+    }
 
-            write_struct_members(f, indent + 4, members, module);
-            write_indented(f, indent, formatdoc!("}}"));
+    for (group_no, group) in bind_group_data {
+        let mut fields = NameSet::default();
+        let mut consts = NameSet::default();
+        for binding in &group.bindings {
+            let name = binding.name.as_ref().unwrap();
+            let item = format!("binding `{name}` in group {group_no}");
+            fields.insert(rust_ident(name), item.clone())?;
+            consts.insert(binding_const_name(name), item)?;
         }
     }
+
+    Ok(())
 }
 
-fn write_struct_members<W: Write>(
-    f: &mut W,
-    indent: usize,
-    members: &[naga::StructMember],
-    module: &naga::Module,
-) {
-    for member in members {
-        let member_name = member.name.as_ref().unwrap();
-        let member_type = wgsl::rust_type(module, &module.types[member.ty]);
-        write_indented(f, indent, formatdoc!("pub {member_name}: {member_type},"));
+// Rust names and descriptions of the WGSL items that generate them.
+#[derive(Default)]
+struct NameSet(HashMap<String, String>);
+
+impl NameSet {
+    fn insert(&mut self, name: String, item: String) -> Result<(), CreateModuleError> {
+        match self.0.get(&name) {
+            Some(first) => Err(CreateModuleError::NameCollision {
+                name,
+                first: first.clone(),
+                second: item,
+            }),
+            None => {
+                self.0.insert(name, item);
+                Ok(())
+            }
+        }
     }
 }
 
-fn write_bind_group_layout<W: Write>(
-    f: &mut W,
-    indent: usize,
-    group_no: u32,
-    group: &wgsl::GroupData,
+fn write_shader_module_from_path(
+    f: &mut String,
+    shader_module_label: &str,
+    options: &WriterOptions,
 ) {
-    write_indented(
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    writedoc!(
         f,
-        indent,
-        formatdoc!("pub struct BindGroupLayout{group_no}<'a> {{"),
-    );
-    for binding in &group.bindings {
-        let field_name = binding.name.as_ref().unwrap();
-        // TODO: Support more types.
-        let field_type = match binding.binding_type.inner {
-            // TODO: Is it possible to make structs strongly typed and handle buffer creation automatically?
-            // This could be its own module and associated tests.
-            naga::TypeInner::Struct { .. } => "wgpu::BufferBinding<'a>",
-            naga::TypeInner::Image { .. } => "&'a wgpu::TextureView",
-            naga::TypeInner::Sampler { .. } => "&'a wgpu::Sampler",
-            _ => panic!("Unsupported type for binding fields."),
-        };
-        write_indented(f, indent + 4, formatdoc!("pub {field_name}: {field_type},"));
+        r#"
+            /// Create the shader module from the WGSL file at `path` instead of the source embedded at build time.
+            /// The generated bindings still match the original shader, so only change code that keeps the same interface.
+            {visibility} fn create_shader_module_from_path(
+                device: &{wgpu}::Device,
+                path: impl AsRef<std::path::Path>,
+            ) -> std::io::Result<{wgpu}::ShaderModule> {{
+                let source = std::fs::read_to_string(path)?;
+                Ok(device.create_shader_module(&{wgpu}::ShaderModuleDescriptor {{
+                    label: {shader_module_label},
+                    source: {wgpu}::ShaderSource::Wgsl(std::borrow::Cow::Owned(source))
+                }}))
+            }}
+        "#
+    )
+    .unwrap();
+}
+
+fn write_spirv_shader_module(
+    f: &mut String,
+    words: &[u32],
+    wgsl_include_path: &str,
+    shader_module_label: &str,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    writedoc!(
+        f,
+        r#"
+            /// Create the shader module for `{wgsl_include_path}` from SPIR-V compiled at build time.
+            {visibility} fn create_shader_module(device: &{wgpu}::Device) -> {wgpu}::ShaderModule {{
+                device.create_shader_module(&{wgpu}::ShaderModuleDescriptor {{
+                    label: {shader_module_label},
+                    source: {wgpu}::ShaderSource::SpirV(std::borrow::Cow::Borrowed(SHADER_SPIRV))
+                }})
+            }}
+            const SHADER_SPIRV: &[u32] = &[
+        "#
+    )
+    .unwrap();
+    for line in words.chunks(8) {
+        let line: Vec<_> = line.iter().map(|w| format!("0x{w:08x},")).collect();
+        writeln!(f, "    {}", line.join(" ")).unwrap();
     }
-    write_indented(f, indent, formatdoc!("}}"));
+    writeln!(f, "];").unwrap();
 }
 
-fn write_bind_group_layout_descriptor<W: Write>(
+/// Generates the code with [create_shader_module] and parses the text output into a [proc_macro2::TokenStream]
+/// that can be combined with other generated code using [quote](https://docs.rs/quote).
+///
+/// The tokens aren't built directly but by parsing the generated string,
+/// so they only contain what survives parsing the text.
+/// Regular comments are not part of the tokens, but doc comments are preserved,
+/// and all tokens use [proc_macro2::Span::call_site].
+/// Parsing can fail in addition to the errors from [create_shader_module].
+/// Text that doesn't parse as Rust tokens, like an invalid [WriterOptions::visibility],
+/// is reported as [CreateModuleError::InvalidTokens].
+/// This requires the `tokens` feature.
+#[cfg(feature = "tokens")]
+pub fn create_shader_module_tokens(
+    wgsl_source: &str,
+    wgsl_include_path: &str,
+    options: WriterOptions,
+) -> Result<proc_macro2::TokenStream, CreateModuleError> {
+    let text = create_shader_module(wgsl_source, wgsl_include_path, options)?;
+    text.parse::<proc_macro2::TokenStream>()
+        .map_err(|e| CreateModuleError::InvalidTokens {
+            message: e.to_string(),
+        })
+}
+
+/// Generates the same code as [create_shader_module] split into multiple files
+/// to keep the generated code for large shaders readable.
+///
+/// The returned map contains the contents for each file name like `"mod.rs"`, `"structs.rs"`, `"bind_groups.rs"`, and `"vertex.rs"`.
+/// The `mod.rs` file declares the other modules and reexports the structs,
+/// so the paths for generated items are the same as for a single file.
+/// Files for sections that are empty or disabled in [WriterOptions] are omitted.
+/// The [WriterOptions::module_name] isn't used since the files are already in their own module.
+///
+/// The `wgsl_include_path` is relative to the directory containing the files.
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+let wgsl_source = std::fs::read_to_string("src/shader.wgsl").unwrap();
+let files = wgsl_to_wgpu::create_shader_module_files(
+    &wgsl_source,
+    "../shader.wgsl",
+    wgsl_to_wgpu::WriterOptions::default(),
+)
+.unwrap();
+std::fs::create_dir_all("src/shader").unwrap();
+for (name, text) in files {
+    std::fs::write(format!("src/shader/{name}"), text.as_bytes()).unwrap();
+}
+```
+ */
+pub fn create_shader_module_files(
+    wgsl_source: &str,
+    wgsl_include_path: &str,
+    options: WriterOptions,
+) -> Result<BTreeMap<String, String>, CreateModuleError> {
+    let module = parse_wgsl(wgsl_source)?;
+    // The structs are limited by the visibility of their reexport instead.
+    let sections = write_module_sections(
+        &module,
+        wgsl_source,
+        wgsl_include_path,
+        ModuleSource::File,
+        &options,
+        "pub",
+        &BTreeMap::new(),
+    )?;
+
+    let visibility = &options.visibility;
+    let mut files = BTreeMap::new();
+    let mut mod_rs = sections.constants;
+    // Other modules use the structs like super::Camera, so reexport them from the parent module.
+    if !sections.structs.is_empty() {
+        writeln!(mod_rs, "mod structs;\n{visibility} use structs::*;").unwrap();
+        files.insert("structs.rs".to_string(), sections.structs);
+    }
+    for (name, text) in [
+        ("bind_groups", sections.bind_groups),
+        ("vertex", sections.vertex),
+    ] {
+        // Modules like vertex are still written without any items.
+        let contents = module_file_contents(&text);
+        if !contents.trim().is_empty() {
+            writeln!(mod_rs, "{visibility} mod {name};").unwrap();
+            files.insert(format!("{name}.rs"), contents);
+        }
+    }
+    mod_rs += &sections.items;
+    files.insert("mod.rs".to_string(), mod_rs);
+
+    Ok(files)
+}
+
+// Remove the `pub mod name {` line and closing brace along with the indentation of the items.
+fn module_file_contents(text: &str) -> String {
+    let lines: Vec<_> = text.lines().collect();
+    // Items in the module are indented, so only the module itself starts at the beginning of a line.
+    let start = lines
+        .iter()
+        .position(|line| !line.starts_with(' ') && line.contains("mod ") && line.ends_with('{'))
+        .map_or(0, |i| i + 1);
+    let end = lines
+        .iter()
+        .rposition(|line| *line == "}")
+        .filter(|end| *end >= start)
+        .unwrap_or(lines.len());
+    lines[start..end]
+        .iter()
+        .map(|line| format!("{}\n", line.strip_prefix("    ").unwrap_or(line)))
+        .collect()
+}
+
+/// Generates a Rust module for each WGSL shader in `shaders` using the output of [create_shader_module].
+///
+/// Each shader is a pair of the `wgsl_include_path` and the WGSL source.
+/// The modules are named after the file names like `blit` for `"shaders/blit.wgsl"`,
+/// so a whole directory of shaders can be handled by a single build script call.
+/// The [WriterOptions::visibility] applies to the generated modules.
+/// Enable [WriterOptions::shared_bind_groups] to generate a `layouts` module
+/// with the bind group types for groups that are identical in multiple shaders.
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+let blit = std::fs::read_to_string("src/shaders/blit.wgsl").unwrap();
+let model = std::fs::read_to_string("src/shaders/model.wgsl").unwrap();
+let text = wgsl_to_wgpu::create_shader_modules(
+    &[("shaders/blit.wgsl", &blit), ("shaders/model.wgsl", &model)],
+    wgsl_to_wgpu::WriterOptions::default(),
+)
+.unwrap();
+std::fs::write("src/shaders.rs", text.as_bytes()).unwrap();
+```
+ */
+pub fn create_shader_modules(
+    shaders: &[(&str, &str)],
+    options: WriterOptions,
+) -> Result<String, CreateModuleError> {
+    let mut sources: Vec<ShaderSource> = Vec::new();
+    for (path, source) in shaders {
+        let name = shader_module_name(path);
+        if sources.iter().any(|s| s.name == name) {
+            return Err(CreateModuleError::DuplicateModuleName {
+                name,
+                path: path.to_string(),
+            });
+        }
+        let module = parse_wgsl(source).map_err(|error| shader_error(path, error))?;
+        sources.push(ShaderSource {
+            name,
+            path,
+            source,
+            module,
+        });
+    }
+
+    // Items are already limited by the visibility of their module.
+    let module_options = WriterOptions {
+        visibility: "pub".to_string(),
+        module_name: None,
+        ..options.clone()
+    };
+    // The wrapper module limits the visibility of the shader modules instead.
+    let visibility = match options.module_name {
+        Some(_) => "pub",
+        None => options.visibility.as_str(),
+    };
+
+    let mut output = String::new();
+    let shared_groups = if options.shared_bind_groups {
+        let mut layouts = String::new();
+        let shared_groups =
+            write_layouts_module(&mut layouts, &sources, visibility, &module_options)?;
+        output += &layouts;
+        shared_groups
+    } else {
+        vec![BTreeMap::new(); sources.len()]
+    };
+
+    for (shader, shared_groups) in sources.iter().zip(&shared_groups) {
+        let module = write_shader_module(
+            &shader.module,
+            shader.source,
+            shader.path,
+            ModuleSource::File,
+            module_options.clone(),
+            shared_groups,
+        )
+        .map_err(|error| shader_error(shader.path, error))?;
+
+        writeln!(output, "{visibility} mod {} {{", shader.name).unwrap();
+        write_indented(&mut output, 4, module);
+        writeln!(output, "}}").unwrap();
+    }
+
+    Ok(wrap_module(output, &options))
+}
+
+struct ShaderSource<'a> {
+    name: String,
+    path: &'a str,
+    source: &'a str,
+    module: naga::Module,
+}
+
+fn shader_error(path: &str, error: CreateModuleError) -> CreateModuleError {
+    CreateModuleError::Shader {
+        path: path.to_string(),
+        error: Box::new(error),
+    }
+}
+
+// Groups are shared if the generated code without labels is identical for multiple shaders.
+// This compares the bindings, layout entries, and group index that all affect the generated types.
+// Returns the shared module name for each group that uses the shared types for each shader.
+fn write_layouts_module<W: Write>(
+    f: &mut W,
+    shaders: &[ShaderSource],
+    visibility: &str,
+    options: &WriterOptions,
+) -> Result<Vec<BTreeMap<u32, String>>, CreateModuleError> {
+    struct SharedGroup {
+        name: String,
+        group_no: u32,
+        code: String,
+        labeled_code: String,
+        shaders: Vec<usize>,
+    }
+
+    let mut groups: Vec<SharedGroup> = Vec::new();
+    for (i, shader) in shaders.iter().enumerate() {
+        let options = &with_inferred_samplers(&shader.module, options);
+        let info = validate_module(&shader.module, shader.source)
+            .map_err(|error| shader_error(shader.path, error))?;
+        let bind_group_data = get_bind_group_data(&shader.module, &info, shader.source, options)
+            .map_err(|error| shader_error(shader.path, error))?;
+
+        for (group_no, group) in &bind_group_data {
+            let mut code = String::new();
+            write_shared_bind_group(
+                &mut code,
+                0,
+                &shader.module,
+                *group_no,
+                group,
+                None,
+                options,
+            )
+            .map_err(|error| shader_error(shader.path, error))?;
+            match groups.iter_mut().find(|g| g.code == code) {
+                Some(shared) => shared.shaders.push(i),
+                None => {
+                    let file_name = shader_file_name(shader.path);
+                    let label_prefix = options.generate_labels.then_some(file_name.as_str());
+                    let mut labeled_code = String::new();
+                    write_shared_bind_group(
+                        &mut labeled_code,
+                        8,
+                        &shader.module,
+                        *group_no,
+                        group,
+                        label_prefix,
+                        options,
+                    )
+                    .map_err(|error| shader_error(shader.path, error))?;
+                    groups.push(SharedGroup {
+                        name: format!("{}_group{group_no}", shader.name.trim_start_matches("r#")),
+                        group_no: *group_no,
+                        code,
+                        labeled_code,
+                        shaders: vec![i],
+                    });
+                }
+            }
+        }
+    }
+
+    let mut shared_groups = vec![BTreeMap::new(); shaders.len()];
+    let shared: Vec<_> = groups.iter().filter(|g| g.shaders.len() > 1).collect();
+    if shared.is_empty() {
+        return Ok(shared_groups);
+    }
+
+    writeln!(f, "{visibility} mod layouts {{").unwrap();
+    write_set_bind_group_trait(f, 4, options);
+    for group in shared {
+        let names = group
+            .shaders
+            .iter()
+            .map(|i| format!("`{}`", shaders[*i].name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write_indented(
+            f,
+            4,
+            formatdoc!(
+                r"
+                    /// Bind group {group_no} shared by the {names} shaders.
+                    pub mod {name} {{
+                        use super::SetBindGroup;",
+                group_no = group.group_no,
+                name = group.name
+            ),
+        );
+        write!(f, "{}", group.labeled_code).unwrap();
+        writeln!(f, "    }}").unwrap();
+
+        for i in &group.shaders {
+            shared_groups[*i].insert(group.group_no, group.name.clone());
+        }
+    }
+    writeln!(f, "}}").unwrap();
+
+    Ok(shared_groups)
+}
+
+// The shared types are the same as in the bind groups module except for from_data,
+// which uses the buffer types for a specific shader.
+fn write_shared_bind_group<W: Write>(
     f: &mut W,
     indent: usize,
+    module: &naga::Module,
     group_no: u32,
     group: &wgsl::GroupData,
-    shader_stages: wgpu::ShaderStages,
-) {
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) -> Result<(), CreateModuleError> {
+    let wgpu = &options.wgpu_path;
     write_indented(
         f,
         indent,
         formatdoc!(
-            r#"
-                const LAYOUT_DESCRIPTOR{group_no}: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {{
-                    label: None,
-                    entries: &[
-            "#
+            r"
+                /// The bind group for group {group_no} in the shaders.
+                pub struct BindGroup{group_no}({wgpu}::BindGroup);"
         ),
     );
-    for binding in &group.bindings {
-        write_bind_group_layout_entry(f, binding, indent + 8, shader_stages);
+    write_bind_group_layout(f, indent, module, group_no, group, options);
+    write_bind_group_layout_new(f, indent, group_no, group, options);
+    write_bind_group_layout_descriptor(f, indent, group_no, group, label_prefix, options);
+    impl_bind_group(f, indent, module, group_no, group, label_prefix, options)
+}
+
+fn wrap_module(output: String, options: &WriterOptions) -> String {
+    match &options.module_name {
+        Some(name) => {
+            let mut f = String::new();
+            let visibility = &options.visibility;
+            writeln!(f, "{visibility} mod {} {{", rust_ident(name)).unwrap();
+            write_indented(&mut f, 4, output);
+            writeln!(f, "}}").unwrap();
+            f
+        }
+        None => output,
     }
-    write_indented(
-        f,
-        indent,
-        formatdoc!(
-            r#"
-                    ]
-                }};
-            "#
-        ),
-    );
 }
 
-fn write_bind_group_layout_entry<W: Write>(
-    f: &mut W,
-    binding: &wgsl::GroupBinding,
-    indent: usize,
-    shader_stages: wgpu::ShaderStages,
-) {
-    // TODO: Assume storage is only used for compute?
-    // TODO: Support just vertex or fragment?
-    // TODO: Visible from all stages?
-    let stages = match shader_stages {
-        wgpu::ShaderStages::VERTEX_FRAGMENT => "wgpu::ShaderStages::VERTEX_FRAGMENT",
-        wgpu::ShaderStages::COMPUTE => "wgpu::ShaderStages::COMPUTE",
-        wgpu::ShaderStages::VERTEX => "wgpu::ShaderStages::VERTEX",
-        wgpu::ShaderStages::FRAGMENT => "wgpu::ShaderStages::FRAGMENT",
-        _ => todo!(),
-    };
+// Convert paths like "shaders/blit-pass.wgsl" to valid module names like "blit_pass".
+fn shader_module_name(path: &str) -> String {
+    let stem = std::path::Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem: String = stem
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let name = name_words(&stem).join("_");
+    if !name.starts_with(|c: char| c.is_alphabetic()) || PATH_KEYWORDS.contains(&name.as_str()) {
+        // Leading digits and path keywords are invalid even as raw identifiers.
+        format!("shader_{name}")
+    } else {
+        rust_ident(&name)
+    }
+}
 
-    let binding_index = binding.binding_index;
-    write_indented(
-        f,
-        indent,
-        formatdoc!(
-            r#"
-                wgpu::BindGroupLayoutEntry {{
-                    binding: {binding_index}u32,
-                    visibility: {stages},
-            "#
+// Escape WGSL names like "type" or "ref" that are valid in WGSL but reserved in Rust.
+fn rust_ident(name: &str) -> String {
+    if PATH_KEYWORDS.contains(&name) {
+        // Path keywords can't be raw identifiers.
+        format!("{name}_")
+    } else if RUST_KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+// Generate the Rust expression for the given stages.
+fn quote_shader_stages(shader_stages: wgpu::ShaderStages, wgpu: &str) -> String {
+    match shader_stages {
+        wgpu::ShaderStages::NONE => format!("{wgpu}::ShaderStages::NONE"),
+        wgpu::ShaderStages::VERTEX_FRAGMENT => format!("{wgpu}::ShaderStages::VERTEX_FRAGMENT"),
+        stages => {
+            let names: Vec<_> = [
+                (wgpu::ShaderStages::VERTEX, "VERTEX"),
+                (wgpu::ShaderStages::FRAGMENT, "FRAGMENT"),
+                (wgpu::ShaderStages::COMPUTE, "COMPUTE"),
+            ]
+            .iter()
+            .filter(|(stage, _)| stages.contains(*stage))
+            .map(|(_, name)| format!("{wgpu}::ShaderStages::{name}"))
+            .collect();
+            match names.as_slice() {
+                [name] => name.to_string(),
+                // The BitOr impl isn't const, so combine the bits instead.
+                names => {
+                    let bits = names
+                        .iter()
+                        .map(|name| format!("{name}.bits()"))
+                        .collect::<Vec<_>>()
+                        .join(" | ");
+                    format!("{wgpu}::ShaderStages::from_bits_truncate({bits})")
+                }
+            }
+        }
+    }
+}
+
+// Generate the Rust expression for the given buffer usages.
+fn quote_buffer_usages(usages: wgpu::BufferUsages, wgpu: &str) -> String {
+    [
+        (wgpu::BufferUsages::UNIFORM, "UNIFORM"),
+        (wgpu::BufferUsages::STORAGE, "STORAGE"),
+        (wgpu::BufferUsages::COPY_DST, "COPY_DST"),
+    ]
+    .iter()
+    .filter(|(usage, _)| usages.contains(*usage))
+    .map(|(_, name)| format!("{wgpu}::BufferUsages::{name}"))
+    .collect::<Vec<_>>()
+    .join(" | ")
+}
+
+// Generate the Rust expression for the features detected from the shader.
+fn quote_features(features: wgpu::Features, wgpu: &str) -> String {
+    [
+        (wgpu::Features::PUSH_CONSTANTS, "PUSH_CONSTANTS"),
+        (wgpu::Features::SHADER_FLOAT64, "SHADER_FLOAT64"),
+        (
+            wgpu::Features::SHADER_PRIMITIVE_INDEX,
+            "SHADER_PRIMITIVE_INDEX",
         ),
-    );
-    // TODO: Support more types.
-    match binding.binding_type.inner {
-        naga::TypeInner::Struct { .. } => {
-            let buffer_binding_type = wgsl::buffer_binding_type(binding.storage_class);
-            write_indented(
-                f,
-                indent + 4,
+        (wgpu::Features::MULTIVIEW, "MULTIVIEW"),
+        (
+            wgpu::Features::VERTEX_WRITABLE_STORAGE,
+            "VERTEX_WRITABLE_STORAGE",
+        ),
+        (
+            wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+            "TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES",
+        ),
+    ]
+    .iter()
+    .filter(|(feature, _)| features.contains(*feature))
+    .map(|(_, name)| format!("{wgpu}::Features::{name}"))
+    .reduce(|a, b| format!("{a}.union({b})"))
+    .unwrap_or_else(|| format!("{wgpu}::Features::empty()"))
+}
+
+// Generate the Rust expression for an optional descriptor label.
+fn quote_label(label_prefix: Option<&str>, suffix: &str) -> String {
+    match label_prefix {
+        Some(prefix) => format!("Some({:?})", format!("{prefix}{suffix}")),
+        None => "None".to_string(),
+    }
+}
+
+fn validation_error_message(error: &naga::WithSpan<naga::valid::ValidationError>) -> String {
+    // The top level error is usually too generic to be useful on its own.
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(e) = source {
+        write!(message, ": {e}").unwrap();
+        source = e.source();
+    }
+    message
+}
+
+// Apply indentation to each level.
+// Empty lines stay empty to avoid trailing whitespace in the generated code.
+fn indent<S: Into<String>>(str: S, level: usize) -> String {
+    str.into()
+        .lines()
+        .map(|l| match l {
+            "" => String::new(),
+            _ => " ".repeat(level) + l,
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Assume the input is already unindented with indoc.
+fn write_indented<W: Write, S: Into<String>>(w: &mut W, level: usize, str: S) {
+    writeln!(w, "{}", indent(str, level)).unwrap();
+}
+
+fn write_vertex_module<W: Write>(f: &mut W, module: &naga::Module, options: &WriterOptions) {
+    let visibility = &options.visibility;
+    writeln!(f, "{visibility} mod vertex {{").unwrap();
+
+    // TODO: This is redundant with above?
+    write_vertex_input_structs(f, module, options);
+
+    writeln!(f, "}}").unwrap();
+}
+
+fn write_vertex_input_structs<W: Write>(f: &mut W, module: &naga::Module, options: &WriterOptions) {
+    let wgpu = &options.wgpu_path;
+    let vertex_inputs = wgsl::get_vertex_input_structs(module);
+    for input in vertex_inputs {
+        let step_mode = if options.instance_input_structs.contains(&input.name) {
+            format!("{wgpu}::VertexStepMode::Instance")
+        } else {
+            format!("{wgpu}::VertexStepMode::Vertex")
+        };
+
+        let name = rust_ident(&input.name);
+        let count = input.fields.len();
+        let span = input.span;
+
+        // The vertex input structs are padded to match the WGSL layout of the fields.
+        let attributes = input
+            .fields
+            .iter()
+            .map(|(location, m)| {
+                // Unsupported types are rejected before writing the module.
+                let format =
+                    wgsl::vertex_field_format(module, m, &options.vertex_format_overrides).unwrap();
+                let offset = m.offset;
                 formatdoc!(
                     r#"
-                        ty: wgpu::BindingType::Buffer {{
-                            ty: {buffer_binding_type},
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
+                        {wgpu}::VertexAttribute {{
+                            format: {wgpu}::VertexFormat::{format:?},
+                            offset: {offset},
+                            shader_location: {location},
                         }},
                     "#
-                ),
-            );
-        }
-        naga::TypeInner::Image { dim, class, .. } => {
-            let view_dim = match dim {
-                naga::ImageDimension::D1 => "wgpu::TextureViewDimension::D1",
-                naga::ImageDimension::D2 => "wgpu::TextureViewDimension::D2",
-                naga::ImageDimension::D3 => "wgpu::TextureViewDimension::D3",
-                naga::ImageDimension::Cube => "wgpu::TextureViewDimension::Cube",
-            };
+                )
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        let attributes = indent(attributes, 8);
 
-            let sample_type = match class {
-                naga::ImageClass::Sampled { kind: _, multi: _ } => {
-                    "wgpu::TextureSampleType::Float { filterable: true }"
-                }
-                naga::ImageClass::Depth { multi: _ } => "wgpu::TextureSampleType::Depth",
-                naga::ImageClass::Storage {
-                    format: _,
-                    access: _,
-                } => todo!(),
-            };
+        // The vertex input structs should already be written at this point.
+        write_indented(
+            f,
+            4,
+            formatdoc!(
+                r#"
+                    impl super::{name} {{
+                        pub const VERTEX_ATTRIBUTES: [{wgpu}::VertexAttribute; {count}] = [
+                    {attributes}
+                        ];
+                        /// The size in bytes of the struct including padding, which is also the vertex buffer stride.
+                        pub const SIZE_IN_BYTES: u64 = {span};
+                        /// Whether this struct contains per vertex or per instance data.
+                        pub const STEP_MODE: {wgpu}::VertexStepMode = {step_mode};
+                        /// The layout of a vertex buffer containing an array of this struct.
+                        pub const fn vertex_buffer_layout(step_mode: {wgpu}::VertexStepMode) -> {wgpu}::VertexBufferLayout<'static> {{
+                            {wgpu}::VertexBufferLayout {{
+                                array_stride: Self::SIZE_IN_BYTES,
+                                step_mode,
+                                attributes: &Self::VERTEX_ATTRIBUTES,
+                            }}
+                        }}
+                    }}
+                "#
+            ),
+        );
+    }
+}
 
-            write_indented(
-                f,
-                indent + 4,
-                formatdoc!(
-                    r#"
-                        ty: wgpu::BindingType::Texture {{
-                            multisampled: false,
-                            view_dimension: {view_dim},
-                            sample_type: {sample_type},
-                        }},
+// Generate constants and an enum for entry points to avoid hardcoding names in pipelines.
+// Each entry point also gets a module with helpers for creating pipelines and dispatching work.
+// These modules are nested in an entry_points module to not collide with other generated modules.
+fn write_entry_points<W: Write>(
+    f: &mut W,
+    module: &naga::Module,
+    vertex_module: bool,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    if module.entry_points.is_empty() {
+        return;
+    }
+    let entry_points = wgsl::sorted_entry_points(module);
+
+    for entry in &entry_points {
+        let const_name = entry_point_const_name(&entry.name);
+        let name = &entry.name;
+        writeln!(f, "{visibility} const {const_name}: &str = {name:?};").unwrap();
+    }
+
+    // The vertex states use the vertex buffer layouts from the vertex module.
+    let module_entry_points: Vec<_> = entry_points
+        .iter()
+        .filter(|entry| entry.stage != naga::ShaderStage::Vertex || vertex_module)
+        .collect();
+    if !module_entry_points.is_empty() {
+        writeln!(f, "{visibility} mod entry_points {{").unwrap();
+    }
+    for entry in &module_entry_points {
+        let module_name = rust_ident(&name_words(&entry.name).join("_"));
+        let const_name = entry_point_const_name(&entry.name);
+        let stage = wgsl::shader_stage_name(entry.stage);
+        write_indented(
+            f,
+            4,
+            format!(
+                "/// Generated from the {stage} entry point `{}`.",
+                entry.name
+            ),
+        );
+        match entry.stage {
+            naga::ShaderStage::Vertex => {
+                write_vertex_state(f, module, entry, &module_name, &const_name, options)
+            }
+            naga::ShaderStage::Fragment => {
+                write_fragment_state(f, module, entry, &module_name, &const_name, options)
+            }
+            naga::ShaderStage::Compute => {
+                let [x, y, z] = entry.workgroup_size;
+                write_indented(
+                    f,
+                    4,
+                    formatdoc!(
+                        r#"
+                        pub mod {module_name} {{
+                            /// The workgroup size from the `workgroup_size` attribute.
+                            pub const WORKGROUP_SIZE: [u32; 3] = [{x}, {y}, {z}];
+                            /// The number of workgroups needed to cover `extent` invocations in each dimension.
+                            pub const fn workgroup_count(extent: [u32; 3]) -> [u32; 3] {{
+                                [
+                                    extent[0] / WORKGROUP_SIZE[0] + (extent[0] % WORKGROUP_SIZE[0] != 0) as u32,
+                                    extent[1] / WORKGROUP_SIZE[1] + (extent[1] % WORKGROUP_SIZE[1] != 0) as u32,
+                                    extent[2] / WORKGROUP_SIZE[2] + (extent[2] % WORKGROUP_SIZE[2] != 0) as u32,
+                                ]
+                            }}
+                            /// Dispatch enough workgroups to cover `extent` invocations in each dimension.
+                            pub fn dispatch_for(pass: &mut {wgpu}::ComputePass<'_>, extent: [u32; 3]) {{
+                                let [x, y, z] = workgroup_count(extent);
+                                pass.dispatch(x, y, z);
+                            }}
+                        }}
                     "#
-                ),
-            );
-        }
-        naga::TypeInner::Sampler { comparison } => {
-            let sampler_type = if comparison {
-                "wgpu::SamplerBindingType::Comparison"
-            } else {
-                "wgpu::SamplerBindingType::Filtering"
-            };
-            write_indented(
-                f,
-                indent + 4,
-                format!("ty: wgpu::BindingType::Sampler({sampler_type}),"),
-            );
+                    ),
+                );
+            }
         }
-        // TODO: Better error handling.
-        _ => panic!("Failed to generate BindingType."),
-    };
+    }
+    if !module_entry_points.is_empty() {
+        writeln!(f, "}}").unwrap();
+    }
+
+    let variants: Vec<_> = entry_points
+        .iter()
+        .map(|entry| entry_point_variant_name(&entry.name))
+        .collect();
+
+    let names = entry_points
+        .iter()
+        .zip(&variants)
+        .map(|(entry, variant)| {
+            let const_name = entry_point_const_name(&entry.name);
+            format!("Self::{variant} => {const_name},")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let stages = entry_points
+        .iter()
+        .zip(&variants)
+        .map(|(entry, variant)| {
+            let stage = quote_shader_stages(wgsl::shader_stage(entry.stage), wgpu);
+            format!("Self::{variant} => {stage},")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let variants = variants
+        .iter()
+        .map(|variant| format!("{variant},"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
     write_indented(
         f,
-        indent,
+        0,
         formatdoc!(
             r#"
-                    count: None,
-                }},
-            "#
+                #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+                {visibility} enum EntryPoint {{
+                {variants}
+                }}
+                impl EntryPoint {{
+                    /// The name of the entry point function in the shader.
+                    pub const fn name(self) -> &'static str {{
+                        match self {{
+                {names}
+                        }}
+                    }}
+                    /// The shader stage of the entry point.
+                    pub const fn stage(self) -> {wgpu}::ShaderStages {{
+                        match self {{
+                {stages}
+                        }}
+                    }}
+                }}
+            "#,
+            variants = indent(variants, 4),
+            names = indent(names, 12),
+            stages = indent(stages, 12),
         ),
     );
 }
 
-fn impl_bind_group<W: Write>(
+fn write_vertex_state<W: Write>(
     f: &mut W,
-    indent: usize,
-    group_no: u32,
-    group: &wgsl::GroupData,
-    shader_stages: wgpu::ShaderStages,
+    module: &naga::Module,
+    entry: &naga::EntryPoint,
+    module_name: &str,
+    const_name: &str,
+    options: &WriterOptions,
 ) {
+    let wgpu = &options.wgpu_path;
+    // Vertex input structs are written with their attributes in the vertex module.
+    let buffers: Vec<_> = wgsl::vertex_entry_input_names(module, entry)
+        .iter()
+        .map(|name| rust_ident(name))
+        .map(|name| {
+            format!(
+                "super::super::{name}::vertex_buffer_layout(super::super::{name}::STEP_MODE),\n"
+            )
+        })
+        .collect();
+    let count = buffers.len();
+    let buffers = if buffers.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n    ]", indent(buffers.concat(), 8))
+    };
+
     write_indented(
         f,
-        indent,
+        4,
         formatdoc!(
             r#"
-                impl BindGroup{group_no} {{
-                    pub fn get_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {{
-                        device.create_bind_group_layout(&LAYOUT_DESCRIPTOR{group_no})
+                pub mod {module_name} {{
+                    /// The vertex buffer layouts for the vertex inputs in entry point parameter order.
+                    pub const VERTEX_BUFFERS: [{wgpu}::VertexBufferLayout<'static>; {count}] = {buffers};
+                    /// An owned copy of [VERTEX_BUFFERS] for building pipelines without the generated vertex state.
+                    pub const fn vertex_buffer_layouts() -> [{wgpu}::VertexBufferLayout<'static>; {count}] {{
+                        VERTEX_BUFFERS
                     }}
-
-                    pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout{group_no}) -> Self {{
-                        let bind_group_layout = device.create_bind_group_layout(&LAYOUT_DESCRIPTOR{group_no});
-                        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {{
-                            layout: &bind_group_layout,
-                            entries: &[
+                    /// The vertex stage with the entry point and vertex buffer layouts filled in.
+                    pub fn vertex_state(module: &{wgpu}::ShaderModule) -> {wgpu}::VertexState<'_> {{
+                        {wgpu}::VertexState {{
+                            module,
+                            entry_point: super::super::{const_name},
+                            buffers: &VERTEX_BUFFERS,
+                        }}
+                    }}
+                }}
             "#
         ),
     );
+}
 
-    for binding in &group.bindings {
-        let binding_index = binding.binding_index;
-        let binding_name = binding.name.as_ref().unwrap();
-        let resource_type = match binding.binding_type.inner {
-            naga::TypeInner::Struct { .. } => {
-                format!("wgpu::BindingResource::Buffer(bindings.{binding_name})")
-            }
-            naga::TypeInner::Image { .. } => {
-                format!("wgpu::BindingResource::TextureView(bindings.{binding_name})")
-            }
-            naga::TypeInner::Sampler { .. } => {
-                format!("wgpu::BindingResource::Sampler(bindings.{binding_name})")
-            }
-            // TODO: Better error handling.
-            _ => panic!("Failed to generate BindingType."),
-        };
+fn write_fragment_state<W: Write>(
+    f: &mut W,
+    module: &naga::Module,
+    entry: &naga::EntryPoint,
+    module_name: &str,
+    const_name: &str,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let outputs = wgsl::fragment_outputs(module, entry);
+
+    // The targets need an entry for every location up to the last output.
+    let target_count = outputs.last().map(|o| o.location + 1).unwrap_or_default();
+    let count = outputs.len();
+    let locations = outputs
+        .iter()
+        .map(|o| o.location.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sample_types = outputs
+        .iter()
+        .map(|o| match o.kind {
+            naga::ScalarKind::Sint => format!("{wgpu}::TextureSampleType::Sint"),
+            naga::ScalarKind::Uint => format!("{wgpu}::TextureSampleType::Uint"),
+            _ => format!("{wgpu}::TextureSampleType::Float {{ filterable: false }}"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let components = outputs
+        .iter()
+        .map(|o| o.components.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
 
-        write_indented(
-            f,
-            indent + 16,
-            formatdoc!(
-                r#"
-                    wgpu::BindGroupEntry {{
-                        binding: {binding_index}u32,
-                        resource: {resource_type},
-                    }},
-                "#
-            ),
-        );
-    }
     write_indented(
         f,
-        indent + 4,
+        4,
         formatdoc!(
             r#"
-                        ],
-                        label: None,
-                    }});
-                    Self(bind_group)
+                pub mod {module_name} {{
+                    /// The number of color targets required by the outputs of the entry point.
+                    pub const TARGET_COUNT: usize = {target_count};
+                    /// The locations of the color outputs in increasing order.
+                    pub const OUTPUT_LOCATIONS: [u32; {count}] = [{locations}];
+                    /// The component type of each color output.
+                    /// Float outputs are always `filterable: false`, so compare variants with `matches!`.
+                    pub const OUTPUT_SAMPLE_TYPES: [{wgpu}::TextureSampleType; {count}] = [{sample_types}];
+                    /// The number of components of each color output.
+                    pub const OUTPUT_COMPONENTS: [u32; {count}] = [{components}];
+                    /// The fragment stage with the entry point filled in.
+                    pub fn fragment_state<'a>(
+                        module: &'a {wgpu}::ShaderModule,
+                        targets: &'a [{wgpu}::ColorTargetState],
+                    ) -> {wgpu}::FragmentState<'a> {{
+                        debug_assert!(
+                            targets.len() >= TARGET_COUNT,
+                            "expected at least {{TARGET_COUNT}} color targets but found {{}}",
+                            targets.len()
+                        );
+                        {wgpu}::FragmentState {{
+                            module,
+                            entry_point: super::super::{const_name},
+                            targets,
+                        }}
+                    }}
                 }}
             "#
         ),
     );
+}
 
-    // TODO: Support compute shader with vertex/fragment in the same module?
-    let is_compute = shader_stages == wgpu::ShaderStages::COMPUTE;
+// Simple compute setups only need the module's layout and a single entry point.
+fn write_device_requirements<W: Write>(
+    f: &mut W,
+    module: &naga::Module,
+    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    let required_features = quote_features(wgsl::required_features(module, bind_group_data), wgpu);
+    writedoc!(
+        f,
+        r#"
+            /// The device features required by the shader such as [{wgpu}::Features::PUSH_CONSTANTS] for push constants.
+            {visibility} const REQUIRED_FEATURES: {wgpu}::Features = {required_features};
+        "#
+    )
+    .unwrap();
 
-    let render_pass = if is_compute {
-        "wgpu::ComputePass<'a>"
-    } else {
-        "wgpu::RenderPass<'a>"
+    // Dynamic offsets are configured in the options instead of the shader.
+    let dynamic_offsets = |is_uniform: bool| {
+        bind_group_data
+            .values()
+            .flat_map(|group| &group.bindings)
+            .filter(|b| {
+                wgsl::is_buffer_binding(b)
+                    && options
+                        .dynamic_offset_bindings
+                        .contains(b.name.as_ref().unwrap())
+                    && (b.storage_class == naga::StorageClass::Uniform) == is_uniform
+            })
+            .count() as u32
     };
+    let mut limits = wgsl::required_limits(module, bind_group_data);
+    limits.extend(
+        [
+            (
+                "max_dynamic_uniform_buffers_per_pipeline_layout",
+                dynamic_offsets(true),
+            ),
+            (
+                "max_dynamic_storage_buffers_per_pipeline_layout",
+                dynamic_offsets(false),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, limit)| *limit > 0),
+    );
 
-    write_indented(
-        f,
-        indent,
-        formatdoc!(
+    // Only raise the limits to avoid requesting lower limits than the base.
+    if limits.is_empty() {
+        writedoc!(
+            f,
             r#"
-
-                pub fn set<'a>(&'a self, render_pass: &mut {render_pass}) {{
-                    render_pass.set_bind_group({group_no}u32, &self.0, &[]);
+                /// Raise the limits in `base` to at least the limits required by the shader.
+                {visibility} fn required_limits(base: {wgpu}::Limits) -> {wgpu}::Limits {{
+                    base
                 }}
-            }}"#
-        ),
-    );
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-    use indoc::indoc;
-    use pretty_assertions::assert_eq;
+            "#
+        )
+        .unwrap();
+    } else {
+        let fields = limits
+            .iter()
+            .map(|(name, limit)| format!("        {name}: base.{name}.max({limit}),\n"))
+            .collect::<String>();
+        writedoc!(
+            f,
+            r#"
+                /// Raise the limits in `base` to at least the limits required by the shader.
+                {visibility} fn required_limits(base: {wgpu}::Limits) -> {wgpu}::Limits {{
+                    {wgpu}::Limits {{
+                {fields}        ..base
+                    }}
+                }}
+            "#
+        )
+        .unwrap();
+    }
 
-    #[test]
-    fn write_all_structs() {
-        let source = indoc! {r#"
-            struct VectorsF32 {
-                a: vec2<f32>;
+    writedoc!(
+        f,
+        r#"
+            /// An error from [verify_device] for a device that can't use the shader.
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            {visibility} enum VerifyDeviceError {{
+                /// The device doesn't support these features from [REQUIRED_FEATURES].
+                MissingFeatures({wgpu}::Features),
+                /// The device limit with the name from [{wgpu}::Limits] is lower than required by the shader.
+                InsufficientLimit {{
+                    name: &'static str,
+                    required: u32,
+                    supported: u32,
+                }},
+            }}
+            impl std::fmt::Display for VerifyDeviceError {{
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+                    match self {{
+                        Self::MissingFeatures(features) => {{
+                            write!(f, "device is missing features {{features:?}} required by the shader")
+                        }}
+                        Self::InsufficientLimit {{
+                            name,
+                            required,
+                            supported,
+                        }} => write!(
+                            f,
+                            "device limit {{name}} is {{supported}} but the shader requires at least {{required}}"
+                        ),
+                    }}
+                }}
+            }}
+            impl std::error::Error for VerifyDeviceError {{}}
+            /// Check that the device supports the features and limits required by the shader.
+            {visibility} fn verify_device(device: &{wgpu}::Device) -> Result<(), VerifyDeviceError> {{
+                let missing_features = REQUIRED_FEATURES.difference(device.features());
+                if !missing_features.is_empty() {{
+                    return Err(VerifyDeviceError::MissingFeatures(missing_features));
+                }}
+        "#
+    )
+    .unwrap();
+    if !limits.is_empty() {
+        let checks = limits
+            .iter()
+            .map(|(name, limit)| format!("        (\"{name}\", {limit}, limits.{name}),\n"))
+            .collect::<String>();
+        writedoc!(
+            f,
+            r#"
+
+                    let limits = device.limits();
+                    for (name, required, supported) in [
+                {checks}    ] {{
+                        if supported < required {{
+                            return Err(VerifyDeviceError::InsufficientLimit {{
+                                name,
+                                required,
+                                supported,
+                            }});
+                        }}
+                    }}
+            "#
+        )
+        .unwrap();
+    }
+    writedoc!(
+        f,
+        r#"
+                Ok(())
+            }}
+        "#
+    )
+    .unwrap();
+}
+
+fn write_compute_pipelines<W: Write>(
+    f: &mut W,
+    module: &naga::Module,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    for entry in wgsl::sorted_entry_points(module) {
+        if entry.stage == naga::ShaderStage::Compute {
+            let name = name_words(&entry.name).join("_");
+            let const_name = entry_point_const_name(&entry.name);
+            let label = quote_label(label_prefix, &format!(" {} pipeline", entry.name));
+            write_indented(
+                f,
+                0,
+                formatdoc!(
+                    r#"
+                        {visibility} fn create_{name}_pipeline(device: &{wgpu}::Device) -> {wgpu}::ComputePipeline {{
+                            let module = create_shader_module(device);
+                            let layout = create_pipeline_layout(device);
+                            device.create_compute_pipeline(&{wgpu}::ComputePipelineDescriptor {{
+                                label: {label},
+                                layout: Some(&layout),
+                                module: &module,
+                                entry_point: {const_name},
+                            }})
+                        }}
+                    "#
+                ),
+            );
+        }
+    }
+}
+
+// Convert names like "vs_main" or "vsMain" to "ENTRY_VS_MAIN".
+// Convert binding names like "color_texture" or "colorTexture" to "COLOR_TEXTURE_BINDING".
+fn binding_const_name(name: &str) -> String {
+    format!("{}_BINDING", name_words(name).join("_").to_uppercase())
+}
+
+fn entry_point_const_name(name: &str) -> String {
+    format!("ENTRY_{}", name_words(name).join("_").to_uppercase())
+}
+
+// Convert names like "vs_main" or "vsMain" to "VsMain".
+fn entry_point_variant_name(name: &str) -> String {
+    name_words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+// Split snake_case and camelCase identifiers into lowercase words.
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if c == '_' {
+            words.push(std::mem::take(&mut word));
+            previous_lowercase = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lowercase {
+            words.push(std::mem::take(&mut word));
+        }
+        previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        word.extend(c.to_lowercase());
+    }
+    words.push(word);
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+fn write_push_constants<W: Write>(
+    f: &mut W,
+    module: &naga::Module,
+    push_constant: &wgsl::PushConstant,
+    shader_stages: wgpu::ShaderStages,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    let bytemuck = &options.bytemuck_path;
+    let stages = quote_shader_stages(shader_stages, wgpu);
+    let size = push_constant.size;
+
+    write_indented(
+        f,
+        0,
+        formatdoc!(
+            r#"
+                {visibility} const PUSH_CONSTANT_RANGE: {wgpu}::PushConstantRange = {wgpu}::PushConstantRange {{
+                    stages: {stages},
+                    range: 0..{size},
+                }};
+            "#
+        ),
+    );
+
+    // Converting the data to bytes requires bytemuck and the generated structs.
+    if options.derive_bytemuck && options.generate_structs {
+        let ty = wgsl::rust_type(
+            module,
+            push_constant.ty,
+            options.matrix_vector_types,
+            &options.glam_path,
+        );
+        for (suffix, pass) in pass_types(shader_stages) {
+            let set = if pass == COMPUTE_PASS {
+                "pass.set_push_constants(0".to_string()
+            } else {
+                format!("pass.set_push_constants({stages}, 0")
+            };
+            write_indented(
+                f,
+                0,
+                formatdoc!(
+                    r#"
+                        {visibility} fn set_push_constants{suffix}<'a>(pass: &mut {wgpu}::{pass}, push_constants: {ty}) {{
+                            {set}, {bytemuck}::bytes_of(&push_constants));
+                        }}
+                    "#
+                ),
+            );
+        }
+    }
+}
+
+const RENDER_PASS: &str = "RenderPass<'a>";
+const COMPUTE_PASS: &str = "ComputePass<'a>";
+
+// Modules with both compute and render entry points get a second set of
+// functions with a "_compute" suffix for use with compute passes.
+fn pass_types(shader_stages: wgpu::ShaderStages) -> Vec<(&'static str, &'static str)> {
+    if shader_stages == wgpu::ShaderStages::COMPUTE {
+        vec![("", COMPUTE_PASS)]
+    } else if shader_stages.contains(wgpu::ShaderStages::COMPUTE) {
+        vec![("", RENDER_PASS), ("_compute", COMPUTE_PASS)]
+    } else {
+        vec![("", RENDER_PASS)]
+    }
+}
+
+// TODO: Take an iterator instead?
+fn write_bind_groups_module<W: Write>(
+    f: &mut W,
+    module: &naga::Module,
+    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
+    shared_groups: &BTreeMap<u32, String>,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) -> Result<(), CreateModuleError> {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    writeln!(f, "{visibility} mod bind_groups {{").unwrap();
+
+    // Bind groups from the layouts module only accept passes implementing the shared trait.
+    if shared_groups.is_empty() {
+        write_set_bind_group_trait(f, 4, options);
+    } else {
+        writeln!(f, "    pub use super::super::layouts::SetBindGroup;").unwrap();
+    }
+
+    let shared_layouts = shared_layout_descriptors(bind_group_data, options);
+    for (group_no, group) in bind_group_data {
+        if let Some(name) = shared_groups.get(group_no) {
+            write_indented(
+                f,
+                4,
+                formatdoc!(
+                    r"
+                        /// Group {group_no} has the same bindings as other shaders and uses the types from the layouts module.
+                        pub use super::super::layouts::{name}::{{BindGroup{group_no}, BindGroupLayout{group_no}}};"
+                ),
+            );
+            write_group_layout_descriptor(
+                f,
+                *group_no,
+                group,
+                &shared_layouts,
+                label_prefix,
+                options,
+            );
+            continue;
+        }
+
+        writeln!(
+            f,
+            "    /// The bind group for group {group_no} in the shader."
+        )
+        .unwrap();
+        writeln!(f, "    pub struct BindGroup{group_no}({wgpu}::BindGroup);").unwrap();
+
+        write_bind_group_layout(f, 4, module, *group_no, group, options);
+        write_bind_group_layout_new(f, 4, *group_no, group, options);
+        write_group_layout_descriptor(f, *group_no, group, &shared_layouts, label_prefix, options);
+        impl_bind_group(f, 4, module, *group_no, group, label_prefix, options)?;
+        write_bind_group_from_data(f, 4, module, *group_no, group, options);
+    }
+
+    write_bind_group_layouts(f, 4, bind_group_data, label_prefix, options);
+    write_binding_info(f, 4, bind_group_data, options);
+
+    if options.owned_bind_groups {
+        writeln!(f, "    pub struct BindGroups {{").unwrap();
+    } else {
+        writeln!(f, "    pub struct BindGroups<'a> {{").unwrap();
+    }
+    for (group_no, group) in bind_group_data {
+        let has_offsets = !dynamic_offset_bindings(group, options).is_empty();
+        if options.owned_bind_groups {
+            writeln!(f, "        pub bind_group{group_no}: BindGroup{group_no},").unwrap();
+            if has_offsets {
+                writeln!(
+                    f,
+                    "        pub bind_group{group_no}_offsets: Vec<{wgpu}::DynamicOffset>,"
+                )
+                .unwrap();
+            }
+        } else {
+            writeln!(
+                f,
+                "        pub bind_group{group_no}: &'a BindGroup{group_no},"
+            )
+            .unwrap();
+            if has_offsets {
+                writeln!(
+                    f,
+                    "        pub bind_group{group_no}_offsets: &'a [{wgpu}::DynamicOffset],"
+                )
+                .unwrap();
+            }
+        }
+    }
+    writeln!(f, "    }}").unwrap();
+
+    write_set_bind_groups(f, 4, bind_group_data, options);
+
+    writeln!(f, "}}").unwrap();
+    Ok(())
+}
+
+// Dynamic offsets are applied in order of the binding index.
+fn dynamic_offset_bindings<'a>(
+    group: &'a wgsl::GroupData,
+    options: &WriterOptions,
+) -> Vec<&'a str> {
+    let mut bindings: Vec<_> = group
+        .bindings
+        .iter()
+        .filter(|b| wgsl::is_buffer_binding(b))
+        .filter(|b| {
+            options
+                .dynamic_offset_bindings
+                .contains(b.name.as_ref().unwrap())
+        })
+        .collect();
+    bindings.sort_by_key(|b| b.binding_index);
+    bindings
+        .iter()
+        .map(|b| b.name.as_ref().unwrap().as_str())
+        .collect()
+}
+
+// Render passes, compute passes, and render bundles all support setting bind groups.
+fn write_set_bind_group_trait<W: Write>(f: &mut W, indent: usize, options: &WriterOptions) {
+    let wgpu = &options.wgpu_path;
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                /// Types like render or compute passes that can set bind groups.
+                pub trait SetBindGroup<'a> {{
+                    fn set_bind_group(&mut self, index: u32, bind_group: &'a {wgpu}::BindGroup, offsets: &[{wgpu}::DynamicOffset]);
+                }}
+            "#
+        ),
+    );
+    for ty in ["RenderPass", "ComputePass", "RenderBundleEncoder"] {
+        write_indented(
+            f,
+            indent,
+            formatdoc!(
+                r#"
+                    impl<'a> SetBindGroup<'a> for {wgpu}::{ty}<'a> {{
+                        fn set_bind_group(&mut self, index: u32, bind_group: &'a {wgpu}::BindGroup, offsets: &[{wgpu}::DynamicOffset]) {{
+                            {wgpu}::{ty}::set_bind_group(self, index, bind_group, offsets);
+                        }}
+                    }}
+                "#
+            ),
+        );
+    }
+}
+
+fn write_set_bind_groups<W: Write>(
+    f: &mut W,
+    indent: usize,
+    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let bind_groups_type = if options.owned_bind_groups {
+        "&'a BindGroups"
+    } else {
+        "BindGroups<'a>"
+    };
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+            pub fn set_bind_groups<'a>(
+                pass: &mut impl SetBindGroup<'a>,
+                bind_groups: {bind_groups_type},
+            ) {{
+            "#
+        ),
+    );
+
+    // The set function for each bind group already sets the index.
+    for (group_no, group) in bind_group_data {
+        let offsets = if dynamic_offset_bindings(group, options).is_empty() {
+            String::new()
+        } else if options.owned_bind_groups {
+            format!(", &bind_groups.bind_group{group_no}_offsets")
+        } else {
+            format!(", bind_groups.bind_group{group_no}_offsets")
+        };
+        write_indented(
+            f,
+            indent + 4,
+            format!("bind_groups.bind_group{group_no}.set(pass{offsets});"),
+        );
+    }
+    write_indented(f, indent, "}");
+
+    // Allow rebinding a single group without requiring all other groups.
+    for (group_no, group) in bind_group_data {
+        let (offsets_param, offsets) = if dynamic_offset_bindings(group, options).is_empty() {
+            (String::new(), "")
+        } else {
+            (
+                format!("\n    offsets: &[{wgpu}::DynamicOffset],"),
+                ", offsets",
+            )
+        };
+        write_indented(
+            f,
+            indent,
+            formatdoc!(
+                r#"
+                /// Set only bind group {group_no} and leave any other bind groups unchanged.
+                pub fn set_bind_group{group_no}<'a>(
+                    pass: &mut impl SetBindGroup<'a>,
+                    bind_group: &'a BindGroup{group_no},{offsets_param}
+                ) {{
+                    bind_group.set(pass{offsets});
+                }}
+                "#
+            ),
+        );
+    }
+}
+
+// Export named constants so CPU code like array sizes stays in sync with the shader.
+fn write_constants<W: Write>(f: &mut W, module: &naga::Module, options: &WriterOptions) {
+    let visibility = &options.visibility;
+    for (_, constant) in module.constants.iter() {
+        // Literals in expressions are also constants but don't have a name.
+        if let Some(name) = &constant.name {
+            if let Some((ty, value)) = wgsl::rust_constant(constant) {
+                if name.chars().any(|c| c.is_lowercase()) {
+                    writeln!(f, "#[allow(non_upper_case_globals)]").unwrap();
+                }
+                writeln!(f, "{visibility} const {name}: {ty} = {value};").unwrap();
+            }
+        }
+    }
+}
+
+fn write_type_aliases<W: Write>(f: &mut W, module: &naga::Module, options: &WriterOptions) {
+    let visibility = &options.visibility;
+    for (name, ty) in type_aliases(module) {
+        let rust_type =
+            wgsl::rust_type(module, ty, options.matrix_vector_types, &options.glam_path);
+        writeln!(f, "{visibility} type {name} = {rust_type};").unwrap();
+    }
+}
+
+fn type_aliases(module: &naga::Module) -> Vec<(String, &naga::Type)> {
+    module
+        .types
+        .iter()
+        .filter_map(|(_, ty)| {
+            // Aliases are the only named types other than structs.
+            let is_alias = match &ty.inner {
+                naga::TypeInner::Scalar { .. }
+                | naga::TypeInner::Vector { .. }
+                | naga::TypeInner::Matrix { .. }
+                | naga::TypeInner::Atomic { .. } => true,
+                naga::TypeInner::Array { size, .. } => {
+                    matches!(size, naga::ArraySize::Constant(_))
+                }
+                _ => false,
+            };
+            let name = ty.name.as_ref().filter(|_| is_alias)?;
+            Some((rust_ident(name), ty))
+        })
+        .collect()
+}
+
+fn write_structs<W: Write>(
+    f: &mut W,
+    indent: usize,
+    module: &naga::Module,
+    options: &WriterOptions,
+) {
+    // Create matching Rust structs for WGSL structs.
+    // The goal is to eventually have safe ways to initialize uniform buffers.
+
+    // TODO: How to provide a convenient way to work with these types.
+    // Users will want to either a) create a new buffer each type or b) reuse an existing buffer.
+    // It might not make sense from a performance perspective to constantly create new resources.
+    // This requires the user to keep track of the buffer separately from the BindGroup itself.
+
+    // This is a UniqueArena, so types will only be defined once.
+    // Rust doesn't require defining nested structs first, so the structs can be sorted by name.
+    let buffer_struct_types = wgsl::buffer_struct_types(module);
+    let uniform_struct_types = wgsl::uniform_struct_types(module);
+    let storage_struct_types = wgsl::storage_struct_types(module);
+    let host_shareable_struct_types = wgsl::host_shareable_struct_types(module);
+    let vertex_inputs = wgsl::get_vertex_input_structs(module);
+    let vertex_input_names: Vec<_> = vertex_inputs.iter().map(|input| &input.name).collect();
+    let bytemuck_derives = bytemuck_derives(options);
+    let layout = buffer_layout(options);
+    let layout_options = WriterOptions {
+        matrix_vector_types: layout_matrix_vector_types(options.matrix_vector_types),
+        ..options.clone()
+    };
+    for (handle, t) in wgsl::sorted_struct_types(module) {
+        if let naga::TypeInner::Struct { members, span } = &t.inner {
+            let name = t.name.as_ref().unwrap();
+
+            if members.is_empty() && options.empty_structs == EmptyStructs::Skip {
+                continue;
+            }
+            write_indented(
+                f,
+                indent,
+                format!("/// Generated from the WGSL struct `{name}`."),
+            );
+            if members.is_empty() && options.empty_structs == EmptyStructs::Unit {
+                write_unit_struct(f, indent, &rust_ident(name), options);
+                continue;
+            }
+
+            let is_encase = layout == BufferLayout::Encase && buffer_struct_types.contains(&handle);
+            let is_std140 =
+                layout == BufferLayout::Crevice && uniform_struct_types.contains(&handle);
+            let is_std430 =
+                layout == BufferLayout::Crevice && storage_struct_types.contains(&handle);
+
+            let mut derives = vec!["Debug", "Copy", "Clone", "PartialEq"];
+            if is_encase {
+                derives.push("encase::ShaderType");
+            } else if is_std140 || is_std430 {
+                if is_std140 {
+                    derives.push("crevice::std140::AsStd140");
+                }
+                if is_std430 {
+                    derives.push("crevice::std430::AsStd430");
+                }
+            } else if options.derive_bytemuck {
+                derives.extend(bytemuck_derives.iter().map(String::as_str));
+            }
+            if options.derive_serde {
+                derives.extend(["serde::Serialize", "serde::Deserialize"]);
+            }
+
+            let extra_derives = if host_shareable_struct_types.contains(&handle) {
+                &options.buffer_struct_derives
+            } else if vertex_input_names.contains(&name) {
+                &options.vertex_input_struct_derives
+            } else {
+                &options.struct_derives
+            };
+            derives.extend(extra_derives.iter().map(String::as_str));
+
+            // Encase and crevice already handle the layout.
+            let is_padded = host_shareable_struct_types.contains(&handle)
+                && !(is_encase || is_std140 || is_std430);
+            let fields = match vertex_inputs.iter().find(|input| &input.name == name) {
+                // Builtins aren't part of the vertex buffer, so only include fields with locations.
+                Some(input) => vertex_input_fields(module, input, options),
+                None if is_encase || is_std140 || is_std430 => {
+                    struct_fields(module, members, &layout_options, None)
+                }
+                None => struct_fields(module, members, options, is_padded.then_some(*span)),
+            };
+            let name = &rust_ident(name);
+            write_struct(f, indent, name, &derives, &fields, options);
+
+            if is_std140 {
+                write_crevice_write_buffer(f, indent, name, "std140", options);
+            }
+            if is_std430 {
+                write_crevice_write_buffer(f, indent, name, "std430", options);
+            }
+        }
+    }
+
+    // Vertex entry point arguments with locations don't have a corresponding WGSL struct.
+    for input in vertex_inputs.iter().filter(|input| input.is_synthesized) {
+        let mut derives = vec!["Debug", "Copy", "Clone", "PartialEq"];
+        if options.derive_bytemuck {
+            derives.extend(bytemuck_derives.iter().map(String::as_str));
+        }
+        if options.derive_serde {
+            derives.extend(["serde::Serialize", "serde::Deserialize"]);
+        }
+        derives.extend(
+            options
+                .vertex_input_struct_derives
+                .iter()
+                .map(String::as_str),
+        );
+
+        let name = rust_ident(&input.name);
+        let fields = vertex_input_fields(module, input, options);
+        write_indented(
+            f,
+            indent,
+            "/// Generated from the vertex entry point arguments with locations.",
+        );
+        write_struct(f, indent, &name, &derives, &fields, options);
+    }
+}
+
+fn buffer_wrapper_structs(
+    module: &naga::Module,
+    options: &WriterOptions,
+) -> BTreeMap<naga::Handle<naga::Type>, wgpu::BufferUsages> {
+    // The typed buffers use the generated structs.
+    // Encase and crevice structs don't have the same layout as the buffer contents.
+    if !options.generate_structs
+        || !options.derive_bytemuck
+        || buffer_layout(options) != BufferLayout::Padded
+    {
+        return BTreeMap::new();
+    }
+    wgsl::buffer_binding_structs(module)
+}
+
+// How the structs used in uniform and storage buffers match the WGSL memory layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BufferLayout {
+    Padded,
+    Encase,
+    Crevice,
+}
+
+// Encase takes priority over crevice if both options are enabled.
+fn buffer_layout(options: &WriterOptions) -> BufferLayout {
+    if options.derive_encase {
+        BufferLayout::Encase
+    } else if options.derive_crevice {
+        BufferLayout::Crevice
+    } else {
+        BufferLayout::Padded
+    }
+}
+
+// Encase and crevice align arrays like [f32; 3] as WGSL arrays instead of vectors,
+// so vectors in structs with their derives use glam types instead.
+fn layout_matrix_vector_types(types: MatrixVectorTypes) -> MatrixVectorTypes {
+    match types {
+        MatrixVectorTypes::Rust | MatrixVectorTypes::GlamMatrices => MatrixVectorTypes::Glam,
+        types => types,
+    }
+}
+
+// Typed buffers ensure the contents match the struct expected by the shader.
+fn write_buffer_wrappers<W: Write>(
+    f: &mut W,
+    indent: usize,
+    module: &naga::Module,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let visibility = &options.visibility;
+    let bytemuck = &options.bytemuck_path;
+    let mut wrapper_structs: Vec<_> = buffer_wrapper_structs(module, options)
+        .into_iter()
+        .collect();
+    wrapper_structs.sort_by(|(a, _), (b, _)| module.types[*a].name.cmp(&module.types[*b].name));
+    for (handle, usage) in wrapper_structs {
+        let name = &rust_ident(module.types[handle].name.as_ref().unwrap());
+        write_create_buffer_fns(f, indent, name, usage, label_prefix, options);
+
+        let usage = quote_buffer_usages(usage | wgpu::BufferUsages::COPY_DST, wgpu);
+        let label = quote_label(label_prefix, &format!(" {name} buffer"));
+        write_indented(
+            f,
+            indent,
+            formatdoc!(
+                r#"
+                    /// A buffer containing a [{name}] with the usages required by the shader.
+                    {visibility} struct {name}Buffer({wgpu}::Buffer);
+                    impl {name}Buffer {{
+                        pub fn new(device: &{wgpu}::Device, data: &{name}) -> Self {{
+                            Self({wgpu}::util::DeviceExt::create_buffer_init(
+                                device,
+                                &{wgpu}::util::BufferInitDescriptor {{
+                                    label: {label},
+                                    contents: {bytemuck}::bytes_of(data),
+                                    usage: {usage},
+                                }},
+                            ))
+                        }}
+
+                        pub fn write(&self, queue: &{wgpu}::Queue, data: &{name}) {{
+                            data.write_buffer(queue, &self.0);
+                        }}
+
+                        pub fn as_binding(&self) -> {wgpu}::BufferBinding<'_> {{
+                            self.0.as_entire_buffer_binding()
+                        }}
+                    }}
+                "#
+            ),
+        );
+    }
+}
+
+// Each binding type needs a different usage, so add a function for each way the shader uses the struct.
+fn write_create_buffer_fns<W: Write>(
+    f: &mut W,
+    indent: usize,
+    name: &str,
+    usage: wgpu::BufferUsages,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let bytemuck = &options.bytemuck_path;
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                impl {name} {{
+                    /// Write the contents to the start of `buffer` like when updating uniforms each frame.
+                    pub fn write_buffer(&self, queue: &{wgpu}::Queue, buffer: &{wgpu}::Buffer) {{
+                        queue.write_buffer(buffer, 0, {bytemuck}::bytes_of(self));
+                    }}
+            "#
+        ),
+    );
+    for (binding_usage, binding) in [
+        (wgpu::BufferUsages::UNIFORM, "uniform"),
+        (wgpu::BufferUsages::STORAGE, "storage"),
+    ] {
+        if !usage.contains(binding_usage) {
+            continue;
+        }
+        let buffer_usage = quote_buffer_usages(binding_usage | wgpu::BufferUsages::COPY_DST, wgpu);
+        let label = quote_label(label_prefix, &format!(" {name} {binding} buffer"));
+        writeln!(f).unwrap();
+        write_indented(
+            f,
+            indent + 4,
+            formatdoc!(
+                r#"
+                    pub fn create_{binding}_buffer(&self, device: &{wgpu}::Device) -> {wgpu}::Buffer {{
+                        {wgpu}::util::DeviceExt::create_buffer_init(
+                            device,
+                            &{wgpu}::util::BufferInitDescriptor {{
+                                label: {label},
+                                contents: {bytemuck}::bytes_of(self),
+                                usage: {buffer_usage},
+                            }},
+                        )
+                    }}
+                "#
+            ),
+        );
+    }
+    write_indented(f, indent, "}");
+}
+
+fn vertex_input_fields(
+    module: &naga::Module,
+    input: &wgsl::VertexInput,
+    options: &WriterOptions,
+) -> Vec<StructField> {
+    // Fill any gaps between fields and at the end of the struct with bytes.
+    // The attribute offsets and stride are literals, so the Rust layout must not depend on the field types.
+    let mut fields = Vec::new();
+    let mut padding_index = 0;
+    let mut add_padding = |fields: &mut Vec<StructField>, size: u32| {
+        fields.push(padding_field(padding_index, size));
+        padding_index += 1;
+    };
+
+    let mut end = 0;
+    for (_, member) in &input.fields {
+        if member.offset > end {
+            add_padding(&mut fields, member.offset - end);
+        }
+
+        let ty = &module.types[member.ty];
+        let mut field = StructField {
+            name: rust_ident(member.name.as_ref().unwrap()),
+            ty: wgsl::rust_type(module, ty, options.matrix_vector_types, &options.glam_path),
+            default: wgsl::rust_default_value(module, ty),
+        };
+        let mut size = ty.inner.span(&module.constants);
+
+        // Packed formats use a different Rust type than the corresponding WGSL type.
+        if let Some(format) = member
+            .name
+            .as_ref()
+            .and_then(|name| options.vertex_format_overrides.get(name))
+        {
+            field.ty = wgsl::vertex_format_rust_type(*format);
+            size = format.size() as u32;
+        }
+        fields.push(field);
+
+        end = member.offset + size;
+    }
+
+    if input.span > end {
+        add_padding(&mut fields, input.span - end);
+    }
+
+    fields
+}
+
+fn write_struct<W: Write>(
+    f: &mut W,
+    indent: usize,
+    name: &str,
+    derives: &[&str],
+    fields: &[StructField],
+    options: &WriterOptions,
+) {
+    let visibility = &options.visibility;
+    // The derive macros refer to bytemuck by name unless told otherwise.
+    let bytemuck = &options.bytemuck_path;
+    let [pod, _] = bytemuck_derives(options);
+    let bytemuck_attribute = if bytemuck != "bytemuck" && derives.contains(&pod.as_str()) {
+        format!("\n#[bytemuck(crate = \"{bytemuck}\")]")
+    } else {
+        String::new()
+    };
+    let derives = derives.join(", ");
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r"
+                #[repr(C)]
+                #[derive({derives})]{bytemuck_attribute}
+                {visibility} struct {name} {{
+                "
+        ),
+    );
+    write_struct_members(f, indent + 4, fields);
+    write_indented(f, indent, formatdoc!("}}"));
+
+    if options.impl_default {
+        write_default_impl(f, indent, name, fields);
+    }
+}
+
+fn bytemuck_derives(options: &WriterOptions) -> [String; 2] {
+    let bytemuck = &options.bytemuck_path;
+    [format!("{bytemuck}::Pod"), format!("{bytemuck}::Zeroable")]
+}
+
+// Unit structs have no layout, so they only need the derives that don't depend on the fields.
+fn write_unit_struct<W: Write>(f: &mut W, indent: usize, name: &str, options: &WriterOptions) {
+    let visibility = &options.visibility;
+    let mut derives = vec!["Debug", "Copy", "Clone", "PartialEq"];
+    if options.impl_default {
+        derives.push("Default");
+    }
+    if options.derive_serde {
+        derives.extend(["serde::Serialize", "serde::Deserialize"]);
+    }
+    derives.extend(options.struct_derives.iter().map(String::as_str));
+    let derives = derives.join(", ");
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r"
+                #[derive({derives})]
+                {visibility} struct {name};
+            "
+        ),
+    );
+}
+
+// Convert to the padded crevice representation before writing the bytes.
+fn write_crevice_write_buffer<W: Write>(
+    f: &mut W,
+    indent: usize,
+    name: &str,
+    layout: &str,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let trait_name = layout.replace("std", "Std");
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r"
+                impl {name} {{
+                    pub fn write_buffer_{layout}(&self, queue: &{wgpu}::Queue, buffer: &{wgpu}::Buffer) {{
+                        let data = crevice::{layout}::As{trait_name}::as_{layout}(self);
+                        queue.write_buffer(buffer, 0, crevice::{layout}::{trait_name}::as_bytes(&data));
+                    }}
+                }}
+            "
+        ),
+    );
+}
+
+struct StructField {
+    name: String,
+    ty: String,
+    default: String,
+}
+
+fn struct_fields(
+    module: &naga::Module,
+    members: &[naga::StructMember],
+    options: &WriterOptions,
+    padded_span: Option<u32>,
+) -> Vec<StructField> {
+    // Fill any gaps between members and at the end of the struct with bytes.
+    // This makes the Rust layout match the WGSL layout without relying on implicit padding.
+    let mut fields = Vec::new();
+    let mut padding_index = 0;
+    let mut add_padding = |fields: &mut Vec<StructField>, size: u32| {
+        fields.push(padding_field(padding_index, size));
+        padding_index += 1;
+    };
+
+    let mut end = 0;
+    for member in members {
+        if padded_span.is_some() && member.offset > end {
+            add_padding(&mut fields, member.offset - end);
+        }
+
+        let ty = &module.types[member.ty];
+        fields.push(StructField {
+            name: rust_ident(member.name.as_ref().unwrap()),
+            ty: wgsl::rust_type(module, ty, options.matrix_vector_types, &options.glam_path),
+            default: wgsl::rust_default_value(module, ty),
+        });
+
+        end = member.offset + ty.inner.span(&module.constants);
+    }
+
+    if let Some(span) = padded_span {
+        if span > end {
+            add_padding(&mut fields, span - end);
+        }
+    }
+
+    fields
+}
+
+fn padding_field(index: usize, size: u32) -> StructField {
+    StructField {
+        name: format!("_padding{index}"),
+        ty: format!("[u8; {size}]"),
+        default: format!("[0; {size}]"),
+    }
+}
+
+fn write_struct_members<W: Write>(f: &mut W, indent: usize, fields: &[StructField]) {
+    for StructField { name, ty, .. } in fields {
+        write_indented(f, indent, formatdoc!("pub {name}: {ty},"));
+    }
+}
+
+fn write_default_impl<W: Write>(f: &mut W, indent: usize, name: &str, fields: &[StructField]) {
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r"
+                impl Default for {name} {{
+                    fn default() -> Self {{
+                        Self {{
+            "
+        ),
+    );
+    for field in fields {
+        let StructField { name, default, .. } = field;
+        write_indented(f, indent + 12, format!("{name}: {default},"));
+    }
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r"
+                        }}
+                    }}
+                }}
+            "
+        ),
+    );
+}
+
+fn write_bind_group_layout<W: Write>(
+    f: &mut W,
+    indent: usize,
+    module: &naga::Module,
+    group_no: u32,
+    group: &wgsl::GroupData,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r"
+                /// The resources for the bindings in group {group_no}.
+                pub struct BindGroupLayout{group_no}<'a> {{"
+        ),
+    );
+    for binding in &group.bindings {
+        let field_name = rust_ident(binding.name.as_ref().unwrap());
+        // TODO: Support more types.
+        let field_type = match binding.binding_type.inner {
+            // TODO: Is it possible to make structs strongly typed and handle buffer creation automatically?
+            // This could be its own module and associated tests.
+            _ if wgsl::is_buffer_binding(binding) => format!("{wgpu}::BufferBinding<'a>"),
+            naga::TypeInner::Image { .. } => format!("&'a {wgpu}::TextureView"),
+            naga::TypeInner::Sampler { .. } => format!("&'a {wgpu}::Sampler"),
+            // Unsupported types are rejected when collecting the bind group data.
+            _ => unreachable!(),
+        };
+        let binding_index = binding.binding_index;
+        let declaration = wgsl::wgsl_binding_declaration(module, binding);
+        write_indented(
+            f,
+            indent + 4,
+            formatdoc!(
+                r"
+                    /// Group {group_no} binding {binding_index} for `{declaration}`.
+                    pub {field_name}: {field_type},"
+            ),
+        );
+    }
+    write_indented(f, indent, formatdoc!("}}"));
+}
+
+// Most buffers are bound in their entirety, so accept the buffers directly.
+fn write_bind_group_layout_new<W: Write>(
+    f: &mut W,
+    indent: usize,
+    group_no: u32,
+    group: &wgsl::GroupData,
+    options: &WriterOptions,
+) {
+    if !group.bindings.iter().any(wgsl::is_buffer_binding) {
+        return;
+    }
+
+    let wgpu = &options.wgpu_path;
+    let params = group
+        .bindings
+        .iter()
+        .map(|binding| {
+            let name = rust_ident(binding.name.as_ref().unwrap());
+            let ty = match binding.binding_type.inner {
+                _ if wgsl::is_buffer_binding(binding) => format!("&'a {wgpu}::Buffer"),
+                naga::TypeInner::Image { .. } => format!("&'a {wgpu}::TextureView"),
+                naga::TypeInner::Sampler { .. } => format!("&'a {wgpu}::Sampler"),
+                _ => unreachable!(),
+            };
+            format!("        {name}: {ty},\n")
+        })
+        .collect::<String>();
+    let fields = group
+        .bindings
+        .iter()
+        .map(|binding| {
+            let name = rust_ident(binding.name.as_ref().unwrap());
+            if wgsl::is_buffer_binding(binding) {
+                format!("            {name}: {name}.as_entire_buffer_binding(),\n")
+            } else {
+                format!("            {name},\n")
+            }
+        })
+        .collect::<String>();
+    // The parameters mirror the bindings, so large groups have many parameters.
+    let allow = if group.bindings.len() > 7 {
+        "#[allow(clippy::too_many_arguments)]\n    "
+    } else {
+        ""
+    };
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                impl<'a> BindGroupLayout{group_no}<'a> {{
+                    /// Bind the entire buffer for each buffer binding.
+                    /// Set the fields directly to bind only part of a buffer.
+                    {allow}pub fn new(
+                {params}    ) -> Self {{
+                        Self {{
+                {fields}        }}
+                    }}
+                }}
+            "#
+        ),
+    );
+}
+
+fn write_bind_group_layout_descriptor<W: Write>(
+    f: &mut W,
+    indent: usize,
+    group_no: u32,
+    group: &wgsl::GroupData,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let label = quote_label(label_prefix, &format!(" bind group layout {group_no}"));
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                const LAYOUT_DESCRIPTOR{group_no}: {wgpu}::BindGroupLayoutDescriptor = {wgpu}::BindGroupLayoutDescriptor {{
+                    label: {label},
+                    entries: &[
+            "#
+        ),
+    );
+    for binding in &group.bindings {
+        write_bind_group_layout_entry(f, binding, indent + 8, options);
+    }
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                    ]
+                }};
+            "#
+        ),
+    );
+}
+
+// Map groups to an earlier group with identical layout entries to reuse its descriptor.
+fn shared_layout_descriptors(
+    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
+    options: &WriterOptions,
+) -> BTreeMap<u32, u32> {
+    let mut entries: Vec<(u32, String)> = Vec::new();
+    let mut shared = BTreeMap::new();
+    for (group_no, group) in bind_group_data {
+        let mut group_entries = String::new();
+        for binding in &group.bindings {
+            write_bind_group_layout_entry(&mut group_entries, binding, 0, options);
+        }
+        match entries.iter().find(|(_, e)| *e == group_entries) {
+            Some((first_group_no, _)) => {
+                shared.insert(*group_no, *first_group_no);
+            }
+            None => entries.push((*group_no, group_entries)),
+        }
+    }
+    shared
+}
+
+fn write_group_layout_descriptor<W: Write>(
+    f: &mut W,
+    group_no: u32,
+    group: &wgsl::GroupData,
+    shared_layouts: &BTreeMap<u32, u32>,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) {
+    let first_group_no = match shared_layouts.get(&group_no) {
+        Some(first_group_no) => Some(*first_group_no),
+        None => shared_layouts
+            .values()
+            .any(|first| *first == group_no)
+            .then_some(group_no),
+    };
+    match first_group_no {
+        Some(first_group_no) => write_shared_layout_descriptor(
+            f,
+            4,
+            group_no,
+            group,
+            first_group_no,
+            label_prefix,
+            options,
+        ),
+        None => write_bind_group_layout_descriptor(f, 4, group_no, group, label_prefix, options),
+    }
+}
+
+// Groups with identical entries share the entries but keep their own label.
+fn write_shared_layout_descriptor<W: Write>(
+    f: &mut W,
+    indent: usize,
+    group_no: u32,
+    group: &wgsl::GroupData,
+    first_group_no: u32,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    if group_no == first_group_no {
+        write_indented(
+            f,
+            indent,
+            format!("const LAYOUT_ENTRIES{group_no}: &[{wgpu}::BindGroupLayoutEntry] = &["),
+        );
+        for binding in &group.bindings {
+            write_bind_group_layout_entry(f, binding, indent + 4, options);
+        }
+        write_indented(f, indent, "];");
+    } else {
+        write_indented(
+            f,
+            indent,
+            format!("/// Group {group_no} has the same layout entries as group {first_group_no}."),
+        );
+    }
+
+    let label = quote_label(label_prefix, &format!(" bind group layout {group_no}"));
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                const LAYOUT_DESCRIPTOR{group_no}: {wgpu}::BindGroupLayoutDescriptor = {wgpu}::BindGroupLayoutDescriptor {{
+                    label: {label},
+                    entries: LAYOUT_ENTRIES{first_group_no},
+                }};
+            "#
+        ),
+    );
+}
+
+fn write_bind_group_layout_entry<W: Write>(
+    f: &mut W,
+    binding: &wgsl::GroupBinding,
+    indent: usize,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let stages = quote_shader_stages(binding.visibility, wgpu);
+
+    let binding_index = binding.binding_index;
+    let is_configured = |names: &[String]| {
+        binding
+            .name
+            .as_ref()
+            .map(|name| names.contains(name))
+            .unwrap_or_default()
+    };
+
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                {wgpu}::BindGroupLayoutEntry {{
+                    binding: {binding_index}u32,
+                    visibility: {stages},
+            "#
+        ),
+    );
+    // TODO: Support more types.
+    match binding.binding_type.inner {
+        _ if wgsl::is_buffer_binding(binding) => {
+            let buffer_binding_type = wgsl::buffer_binding_type(binding.storage_class, wgpu);
+            let has_dynamic_offset = options
+                .dynamic_offset_bindings
+                .contains(binding.name.as_ref().unwrap());
+            let min_binding_size = match binding.min_binding_size {
+                Some(size) => format!("std::num::NonZeroU64::new({size})"),
+                None => "None".to_string(),
+            };
+            write_indented(
+                f,
+                indent + 4,
+                formatdoc!(
+                    r#"
+                        ty: {wgpu}::BindingType::Buffer {{
+                            ty: {buffer_binding_type},
+                            has_dynamic_offset: {has_dynamic_offset},
+                            min_binding_size: {min_binding_size},
+                        }},
+                    "#
+                ),
+            );
+        }
+        naga::TypeInner::Image {
+            dim,
+            arrayed,
+            class,
+        } => {
+            let view_dim = format!(
+                "{wgpu}::TextureViewDimension::{:?}",
+                wgsl::texture_view_dimension(dim, arrayed)
+            );
+
+            match class {
+                naga::ImageClass::Storage { format, access } => {
+                    let format = wgsl::storage_texture_format(format);
+                    let access = wgsl::storage_texture_access(access);
+                    write_indented(
+                        f,
+                        indent + 4,
+                        formatdoc!(
+                            r#"
+                                ty: {wgpu}::BindingType::StorageTexture {{
+                                    access: {wgpu}::StorageTextureAccess::{access:?},
+                                    format: {wgpu}::TextureFormat::{format:?},
+                                    view_dimension: {view_dim},
+                                }},
+                            "#
+                        ),
+                    );
+                }
+                _ => {
+                    let sample_type = match class {
+                        naga::ImageClass::Sampled { kind, multi: _ } => match kind {
+                            naga::ScalarKind::Sint => "Sint",
+                            naga::ScalarKind::Uint => "Uint",
+                            _ if is_configured(&options.non_filterable_textures) => {
+                                "Float { filterable: false }"
+                            }
+                            _ => "Float { filterable: true }",
+                        },
+                        naga::ImageClass::Depth { multi: _ } => "Depth",
+                        naga::ImageClass::Storage { .. } => unreachable!(),
+                    };
+                    let multisampled = wgsl::is_multisampled(class);
+
+                    write_indented(
+                        f,
+                        indent + 4,
+                        formatdoc!(
+                            r#"
+                                ty: {wgpu}::BindingType::Texture {{
+                                    multisampled: {multisampled},
+                                    view_dimension: {view_dim},
+                                    sample_type: {wgpu}::TextureSampleType::{sample_type},
+                                }},
+                            "#
+                        ),
+                    );
+                }
+            }
+        }
+        naga::TypeInner::Sampler { comparison } => {
+            let sampler_type = if comparison {
+                "Comparison"
+            } else if is_configured(&options.non_filtering_samplers) {
+                "NonFiltering"
+            } else {
+                "Filtering"
+            };
+            write_indented(
+                f,
+                indent + 4,
+                format!(
+                    "ty: {wgpu}::BindingType::Sampler({wgpu}::SamplerBindingType::{sampler_type}),"
+                ),
+            );
+        }
+        _ => unreachable!(),
+    };
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                    count: None,
+                }},
+            "#
+        ),
+    );
+}
+
+// Layouts can be created once and shared by pipelines and bind groups.
+fn write_bind_group_layouts<W: Write>(
+    f: &mut W,
+    indent_level: usize,
+    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let mut fields: Vec<_> = bind_group_data
+        .keys()
+        .map(|group_no| format!("pub bind_group_layout{group_no}: {wgpu}::BindGroupLayout,"))
+        .collect();
+    let mut layouts: Vec<_> = bind_group_data
+        .keys()
+        .map(|group_no| {
+            format!(
+                "bind_group_layout{group_no}: BindGroup{group_no}::get_bind_group_layout(device),"
+            )
+        })
+        .collect();
+    // Groups that the shader doesn't use share an empty layout in the pipeline layout.
+    if wgsl::has_bind_group_gaps(bind_group_data) {
+        let label = quote_label(label_prefix, " empty bind group layout");
+        fields.push(format!(
+            "/// The layout for groups without bindings in the shader.\n    \
+             pub empty_bind_group_layout: {wgpu}::BindGroupLayout,"
+        ));
+        layouts.push(format!(
+            "empty_bind_group_layout: device.create_bind_group_layout(\
+             &{wgpu}::BindGroupLayoutDescriptor {{\n                \
+             label: {label},\n                \
+             entries: &[],\n            \
+             }}),"
+        ));
+    }
+    let fields = fields.join("\n    ");
+    let layouts = layouts.join("\n            ");
+    let create_bind_groups = bind_group_data
+        .keys()
+        .map(|group_no| {
+            formatdoc!(
+                r#"
+                    /// Create bind group {group_no} with the layout in `bind_group_layout{group_no}`.
+                    pub fn create_bind_group{group_no}(
+                        &self,
+                        device: &{wgpu}::Device,
+                        bindings: BindGroupLayout{group_no},
+                    ) -> BindGroup{group_no} {{
+                        BindGroup{group_no}::from_bindings_with_layout(device, &self.bind_group_layout{group_no}, bindings)
+                    }}
+                "#
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    // Avoid an unused variable warning for modules without bind groups.
+    let device = if bind_group_data.is_empty() {
+        "_device"
+    } else {
+        "device"
+    };
+
+    write_indented(
+        f,
+        indent_level,
+        formatdoc!(
+            r#"
+                /// The layouts for all bind groups to avoid creating duplicate layouts.
+                /// Use the same layouts for the pipeline layout and bind groups.
+                pub struct BindGroupLayouts {{
+                    {fields}
+                }}
+                impl BindGroupLayouts {{
+                    /// Create the layouts for all bind groups in the shader.
+                    pub fn new({device}: &{wgpu}::Device) -> Self {{
+                        Self {{
+                            {layouts}
+                        }}
+                    }}
+                {create_bind_groups}
+                }}
+            "#,
+            create_bind_groups = indent(create_bind_groups, 4)
+        ),
+    );
+}
+
+fn write_binding_info<W: Write>(
+    f: &mut W,
+    indent_level: usize,
+    bind_group_data: &BTreeMap<u32, wgsl::GroupData>,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    // Reuse the layout entries to keep the types consistent with the bind group layouts.
+    let bindings = bind_group_data
+        .iter()
+        .flat_map(|(group_no, group)| {
+            group.bindings.iter().enumerate().map(move |(i, binding)| {
+                let binding_index = binding.binding_index;
+                let name = binding.name.as_deref().unwrap_or_default();
+                formatdoc!(
+                    r#"
+                        BindingInfo {{
+                            group: {group_no},
+                            binding: {binding_index},
+                            name: "{name}",
+                            visibility: LAYOUT_DESCRIPTOR{group_no}.entries[{i}].visibility,
+                            ty: LAYOUT_DESCRIPTOR{group_no}.entries[{i}].ty,
+                        }},
+                    "#
+                )
+            })
+        })
+        .collect::<String>();
+    let bindings = if bindings.is_empty() {
+        "&[]".to_string()
+    } else {
+        format!("&[\n{}\n]", indent(bindings, 4))
+    };
+
+    write_indented(
+        f,
+        indent_level,
+        formatdoc!(
+            r#"
+                /// A description of a binding in the shader for inspecting the bind groups at runtime.
+                #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                pub struct BindingInfo {{
+                    pub group: u32,
+                    pub binding: u32,
+                    /// The name of the global variable in the shader.
+                    pub name: &'static str,
+                    pub visibility: {wgpu}::ShaderStages,
+                    pub ty: {wgpu}::BindingType,
+                }}
+                /// All bindings in the shader ordered by group and binding index.
+                pub const BINDINGS: &[BindingInfo] = {bindings};
+            "#
+        ),
+    );
+}
+
+fn impl_bind_group<W: Write>(
+    f: &mut W,
+    indent: usize,
+    module: &naga::Module,
+    group_no: u32,
+    group: &wgsl::GroupData,
+    label_prefix: Option<&str>,
+    options: &WriterOptions,
+) -> Result<(), CreateModuleError> {
+    let wgpu = &options.wgpu_path;
+    let binding_consts = group
+        .bindings
+        .iter()
+        .map(|binding| {
+            let name = binding_const_name(binding.name.as_ref().unwrap());
+            let binding_index = binding.binding_index;
+            let declaration = wgsl::wgsl_binding_declaration(module, binding);
+            format!(
+                "    /// The binding index for `{declaration}`.\n    \
+                 pub const {name}: u32 = {binding_index};\n"
+            )
+        })
+        .collect::<String>();
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                impl BindGroup{group_no} {{
+                    /// The index of the group in the shader.
+                    pub const GROUP_INDEX: u32 = {group_no};
+                {binding_consts}
+                    /// Create a new layout for the bindings in this group.
+                    pub fn get_bind_group_layout(device: &{wgpu}::Device) -> {wgpu}::BindGroupLayout {{
+                        device.create_bind_group_layout(&LAYOUT_DESCRIPTOR{group_no})
+                    }}
+
+                    /// Create the bind group with a new layout from [Self::get_bind_group_layout].
+                    pub fn from_bindings(device: &{wgpu}::Device, bindings: BindGroupLayout{group_no}) -> Self {{
+                        let bind_group_layout = Self::get_bind_group_layout(device);
+                        Self::from_bindings_with_layout(device, &bind_group_layout, bindings)
+                    }}
+
+                    /// Create the bind group with an existing layout like one from [BindGroupLayouts].
+                    pub fn from_bindings_with_layout(
+                        device: &{wgpu}::Device,
+                        bind_group_layout: &{wgpu}::BindGroupLayout,
+                        bindings: BindGroupLayout{group_no},
+                    ) -> Self {{
+                        let bind_group = device.create_bind_group(&{wgpu}::BindGroupDescriptor {{
+                            layout: bind_group_layout,
+                            entries: &[
+            "#
+        ),
+    );
+
+    for binding in &group.bindings {
+        let binding_index = binding.binding_index;
+        let binding_name = rust_ident(binding.name.as_ref().unwrap());
+        let resource_type = match binding.binding_type.inner {
+            _ if wgsl::is_buffer_binding(binding) => {
+                format!("{wgpu}::BindingResource::Buffer(bindings.{binding_name})")
+            }
+            naga::TypeInner::Image { .. } => {
+                format!("{wgpu}::BindingResource::TextureView(bindings.{binding_name})")
+            }
+            naga::TypeInner::Sampler { .. } => {
+                format!("{wgpu}::BindingResource::Sampler(bindings.{binding_name})")
+            }
+            _ => unreachable!(),
+        };
+
+        write_indented(
+            f,
+            indent + 16,
+            formatdoc!(
+                r#"
+                    {wgpu}::BindGroupEntry {{
+                        binding: {binding_index}u32,
+                        resource: {resource_type},
+                    }},
+                "#
+            ),
+        );
+    }
+    let binding_names = group
+        .bindings
+        .iter()
+        .map(|b| b.name.as_ref().unwrap().as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let label = quote_label(
+        label_prefix,
+        &format!(" bind group {group_no} ({binding_names})"),
+    );
+    write_indented(
+        f,
+        indent + 4,
+        formatdoc!(
+            r#"
+                        ],
+                        label: {label},
+                    }});
+                    Self(bind_group)
+                }}
+            "#
+        ),
+    );
+
+    writeln!(f).unwrap();
+    write_dummy_bind_group(f, indent + 4, module, group_no, group, options)?;
+
+    let dynamic_offset_bindings = dynamic_offset_bindings(group, options);
+    writeln!(f).unwrap();
+    if dynamic_offset_bindings.is_empty() {
+        write_indented(
+            f,
+            indent + 4,
+            formatdoc!(
+                r#"
+                    /// Set the bind group at [Self::GROUP_INDEX] for the `pass`.
+                    pub fn set<'a>(&'a self, pass: &mut impl SetBindGroup<'a>) {{
+                        pass.set_bind_group({group_no}u32, &self.0, &[]);
+                    }}
+                "#
+            ),
+        );
+    } else {
+        let names = dynamic_offset_bindings.join(", ");
+        write_indented(
+            f,
+            indent + 4,
+            formatdoc!(
+                r#"
+                    /// Set the bind group at [Self::GROUP_INDEX] for the `pass`.
+                    /// The dynamic offsets are for {names} in that order.
+                    pub fn set<'a>(&'a self, pass: &mut impl SetBindGroup<'a>, offsets: &[{wgpu}::DynamicOffset]) {{
+                        pass.set_bind_group({group_no}u32, &self.0, offsets);
+                    }}
+                "#
+            ),
+        );
+    }
+    write_indented(f, indent, "}");
+    Ok(())
+}
+
+// Quick prototypes can skip creating buffers if every buffer binding has a generated buffer type.
+fn write_bind_group_from_data<W: Write>(
+    f: &mut W,
+    indent: usize,
+    module: &naga::Module,
+    group_no: u32,
+    group: &wgsl::GroupData,
+    options: &WriterOptions,
+) {
+    let wgpu = &options.wgpu_path;
+    let wrapper_structs = buffer_wrapper_structs(module, options);
+    let mut buffer_structs = Vec::new();
+    for binding in group.bindings.iter().filter(|b| wgsl::is_buffer_binding(b)) {
+        match module.types.get(binding.binding_type) {
+            Some(handle) if wrapper_structs.contains_key(&handle) => {
+                let struct_name = rust_ident(binding.binding_type.name.as_ref().unwrap());
+                buffer_structs.push((binding, struct_name))
+            }
+            // Buffers without a generated buffer type still need to be created manually.
+            _ => return,
+        }
+    }
+    if buffer_structs.is_empty() {
+        return;
+    }
+
+    let data_fields = group
+        .bindings
+        .iter()
+        .map(|binding| {
+            let name = rust_ident(binding.name.as_ref().unwrap());
+            let ty = match buffer_structs.iter().find(|(b, _)| b.name == binding.name) {
+                Some((_, struct_name)) => format!("&'a super::{struct_name}"),
+                None => match binding.binding_type.inner {
+                    naga::TypeInner::Image { .. } => format!("&'a {wgpu}::TextureView"),
+                    naga::TypeInner::Sampler { .. } => format!("&'a {wgpu}::Sampler"),
+                    _ => unreachable!(),
+                },
+            };
+            format!("    pub {name}: {ty},\n")
+        })
+        .collect::<String>();
+    let buffer_fields = buffer_structs
+        .iter()
+        .map(|(binding, struct_name)| {
+            let name = rust_ident(binding.name.as_ref().unwrap());
+            format!("    pub {name}: super::{struct_name}Buffer,\n")
+        })
+        .collect::<String>();
+    let create_buffers = buffer_structs
+        .iter()
+        .map(|(binding, struct_name)| {
+            let name = rust_ident(binding.name.as_ref().unwrap());
+            format!("            {name}: super::{struct_name}Buffer::new(device, data.{name}),\n")
+        })
+        .collect::<String>();
+    let bindings = group
+        .bindings
+        .iter()
+        .map(|binding| {
+            let name = rust_ident(binding.name.as_ref().unwrap());
+            if wgsl::is_buffer_binding(binding) {
+                format!("                {name}: buffers.{name}.as_binding(),\n")
+            } else {
+                format!("                {name}: data.{name},\n")
+            }
+        })
+        .collect::<String>();
+
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            r#"
+                pub struct BindGroupData{group_no}<'a> {{
+                {data_fields}}}
+                /// The buffers created by [BindGroup{group_no}::from_data].
+                pub struct BindGroupBuffers{group_no} {{
+                {buffer_fields}}}
+                impl BindGroup{group_no} {{
+                    /// Create the bind group and new buffers initialized with the data for each buffer binding.
+                    /// The returned buffers can be used to update the data later.
+                    pub fn from_data(device: &{wgpu}::Device, data: BindGroupData{group_no}) -> (Self, BindGroupBuffers{group_no}) {{
+                        let buffers = BindGroupBuffers{group_no} {{
+                {create_buffers}        }};
+                        let bind_group = Self::from_bindings(
+                            device,
+                            BindGroupLayout{group_no} {{
+                {bindings}            }},
+                        );
+                        (bind_group, buffers)
+                    }}
+                }}
+            "#
+        ),
+    );
+}
+
+// Placeholder resources are useful for tests that only need a valid pipeline.
+fn write_dummy_bind_group<W: Write>(
+    f: &mut W,
+    indent: usize,
+    module: &naga::Module,
+    group_no: u32,
+    group: &wgsl::GroupData,
+    options: &WriterOptions,
+) -> Result<(), CreateModuleError> {
+    let wgpu = &options.wgpu_path;
+    write_indented(
+        f,
+        indent,
+        formatdoc!(
+            "
+                /// Create the bind group with placeholder resources for tests that only need a valid pipeline.
+                pub fn dummy(device: &{wgpu}::Device) -> Self {{"
+        ),
+    );
+
+    for binding in &group.bindings {
+        let binding_name = rust_ident(binding.name.as_ref().unwrap());
+        let resource = match binding.binding_type.inner {
+            _ if wgsl::is_buffer_binding(binding) => {
+                // Zero sized buffers can't be bound, so always allocate at least a few bytes.
+                // Runtime sized arrays get space for a single element.
+                let size = binding.binding_type.inner.span(&module.constants).max(4);
+                let usage = match binding.storage_class {
+                    naga::StorageClass::Uniform => "UNIFORM",
+                    _ => "STORAGE",
+                };
+                formatdoc!(
+                    r#"
+                        device.create_buffer(&{wgpu}::BufferDescriptor {{
+                            label: None,
+                            size: {size},
+                            usage: {wgpu}::BufferUsages::{usage},
+                            mapped_at_creation: false,
+                        }})
+                    "#
+                )
+            }
+            naga::TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => {
+                // A single layer or cube is enough for arrayed views.
+                let (dimension, layers) = match dim {
+                    naga::ImageDimension::D1 => ("D1", 1),
+                    naga::ImageDimension::D2 => ("D2", 1),
+                    naga::ImageDimension::D3 => ("D3", 1),
+                    naga::ImageDimension::Cube => ("D2", 6),
+                };
+                let view_dim = format!("{:?}", wgsl::texture_view_dimension(dim, arrayed));
+                let format = match class {
+                    naga::ImageClass::Sampled { kind, .. } => match kind {
+                        naga::ScalarKind::Sint => "Rgba8Sint".to_string(),
+                        naga::ScalarKind::Uint => "Rgba8Uint".to_string(),
+                        _ => "Rgba8Unorm".to_string(),
+                    },
+                    naga::ImageClass::Depth { .. } => "Depth32Float".to_string(),
+                    naga::ImageClass::Storage { format, .. } => {
+                        format!("{:?}", wgsl::storage_texture_format(format))
+                    }
+                };
+                // Multisampled textures must be render attachments and support 4 samples for all formats here.
+                let (sample_count, usage) = match class {
+                    naga::ImageClass::Storage { .. } => (1, "STORAGE_BINDING".to_string()),
+                    _ if wgsl::is_multisampled(class) => (
+                        4,
+                        format!("TEXTURE_BINDING | {wgpu}::TextureUsages::RENDER_ATTACHMENT"),
+                    ),
+                    _ => (1, "TEXTURE_BINDING".to_string()),
+                };
+                formatdoc!(
+                    r#"
+                        device
+                            .create_texture(&{wgpu}::TextureDescriptor {{
+                                label: None,
+                                size: {wgpu}::Extent3d {{
+                                    width: 1,
+                                    height: 1,
+                                    depth_or_array_layers: {layers},
+                                }},
+                                mip_level_count: 1,
+                                sample_count: {sample_count},
+                                dimension: {wgpu}::TextureDimension::{dimension},
+                                format: {wgpu}::TextureFormat::{format},
+                                usage: {wgpu}::TextureUsages::{usage},
+                            }})
+                            .create_view(&{wgpu}::TextureViewDescriptor {{
+                                dimension: Some({wgpu}::TextureViewDimension::{view_dim}),
+                                ..Default::default()
+                            }})
+                    "#
+                )
+            }
+            naga::TypeInner::Sampler { comparison } => {
+                let compare = if comparison {
+                    format!("Some({wgpu}::CompareFunction::LessEqual)")
+                } else {
+                    "None".to_string()
+                };
+                formatdoc!(
+                    r#"
+                        device.create_sampler(&{wgpu}::SamplerDescriptor {{
+                            compare: {compare},
+                            ..Default::default()
+                        }})
+                    "#
+                )
+            }
+            // There is no placeholder resource for other types.
+            _ => {
+                return Err(CreateModuleError::UnsupportedBinding {
+                    group: group_no,
+                    binding: binding.binding_index,
+                    name: binding.name.clone().unwrap_or_default(),
+                    ty: wgsl::wgsl_type_name(module, binding.binding_type),
+                    location: binding.location,
+                })
+            }
+        };
+
+        // Continuation lines already line up with the start of the let statement.
+        let resource = resource.trim_end();
+        write_indented(f, indent + 4, format!("let {binding_name} = {resource};"));
+    }
+
+    write_indented(
+        f,
+        indent + 4,
+        format!("Self::from_bindings(device, BindGroupLayout{group_no} {{"),
+    );
+    for binding in &group.bindings {
+        let binding_name = rust_ident(binding.name.as_ref().unwrap());
+        let field = if wgsl::is_buffer_binding(binding) {
+            format!("{binding_name}: {binding_name}.as_entire_buffer_binding(),")
+        } else {
+            format!("{binding_name}: &{binding_name},")
+        };
+        write_indented(f, indent + 8, field);
+    }
+    write_indented(f, indent + 4, "})");
+    write_indented(f, indent, "}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn write_all_structs() {
+        let source = indoc! {r#"
+            struct VectorsF32 {
+                a: vec2<f32>;
                 b: vec3<f32>;
                 c: vec4<f32>;
             };
 
-            struct VectorsU32 {
-                a: vec2<u32>;
-                b: vec3<u32>;
-                c: vec4<u32>;
-            };
+            struct VectorsU32 {
+                a: vec2<u32>;
+                b: vec3<u32>;
+                c: vec4<u32>;
+            };
+
+            struct MatricesF32 {
+                a: mat4x4<f32>;
+                b: mat4x3<f32>;
+                c: mat4x2<f32>;
+                d: mat3x4<f32>;
+                e: mat3x3<f32>;
+                f: mat3x2<f32>;
+                g: mat2x4<f32>;
+                h: mat2x3<f32>;
+                i: mat2x2<f32>;
+            };
+            
+            struct StaticArrays {
+                a: array<u32, 5>;
+                b: array<f32, 3>;
+                c: array<mat4x4<f32>, 512>;
+                d: array<vec3<f32>, 4>;
+            };
+
+            struct Atomics {
+                num: atomic<u32>;
+                numi: atomic<i32>;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_structs(&mut actual, 0, &module, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `Atomics`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct Atomics {
+                    pub num: u32,
+                    pub numi: i32,
+                }
+                /// Generated from the WGSL struct `MatricesF32`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct MatricesF32 {
+                    pub a: glam::Mat4,
+                    pub b: [[f32; 4]; 4],
+                    pub c: [[f32; 2]; 4],
+                    pub d: [[f32; 4]; 3],
+                    pub e: [[f32; 4]; 3],
+                    pub f: [[f32; 2]; 3],
+                    pub g: [[f32; 4]; 2],
+                    pub h: [[f32; 4]; 2],
+                    pub i: glam::Mat2,
+                }
+                /// Generated from the WGSL struct `StaticArrays`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct StaticArrays {
+                    pub a: [u32; 5],
+                    pub b: [f32; 3],
+                    pub c: [glam::Mat4; 512],
+                    pub d: [[f32; 4]; 4],
+                }
+                /// Generated from the WGSL struct `VectorsF32`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct VectorsF32 {
+                    pub a: [f32; 2],
+                    pub b: [f32; 3],
+                    pub c: [f32; 4],
+                }
+                /// Generated from the WGSL struct `VectorsU32`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct VectorsU32 {
+                    pub a: [u32; 2],
+                    pub b: [u32; 3],
+                    pub c: [u32; 4],
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_f64() {
+        let source = indoc! {r#"
+            struct VectorsF64 {
+                a: f64;
+                b: vec2<f64>;
+                c: vec4<f64>;
+            };
+
+            struct MatricesF64 {
+                a: mat4x4<f64>;
+                b: mat3x3<f64>;
+                c: mat2x2<f64>;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_structs(&mut actual, 0, &module, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `MatricesF64`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct MatricesF64 {
+                    pub a: glam::DMat4,
+                    pub b: [[f64; 4]; 3],
+                    pub c: glam::DMat2,
+                }
+                /// Generated from the WGSL struct `VectorsF64`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct VectorsF64 {
+                    pub a: f64,
+                    pub b: [f64; 2],
+                    pub c: [f64; 4],
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_matrix_vector_types() {
+        let source = indoc! {r#"
+            struct A {
+                a: vec2<f32>;
+                b: vec4<u32>;
+                c: mat4x4<f32>;
+                d: mat2x4<f32>;
+                e: mat3x3<f32>;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let write_members = |matrix_vector_types| {
+            let mut actual = String::new();
+            let members = module
+                .types
+                .iter()
+                .find_map(|(_, t)| match &t.inner {
+                    naga::TypeInner::Struct { members, .. } => Some(members),
+                    _ => None,
+                })
+                .unwrap();
+            let options = WriterOptions {
+                matrix_vector_types,
+                ..Default::default()
+            };
+            let fields = struct_fields(&module, members, &options, None);
+            write_struct_members(&mut actual, 0, &fields);
+            actual
+        };
+
+        assert_eq!(
+            indoc! {
+                r"
+                pub a: glam::Vec2,
+                pub b: glam::UVec4,
+                pub c: glam::Mat4,
+                pub d: [[f32; 4]; 2],
+                pub e: [[f32; 4]; 3],
+                "
+            },
+            write_members(MatrixVectorTypes::Glam)
+        );
+        assert_eq!(
+            indoc! {
+                r"
+                pub a: nalgebra::SVector<f32, 2>,
+                pub b: nalgebra::SVector<u32, 4>,
+                pub c: nalgebra::SMatrix<f32, 4, 4>,
+                pub d: nalgebra::SMatrix<f32, 4, 2>,
+                pub e: [[f32; 4]; 3],
+                "
+            },
+            write_members(MatrixVectorTypes::Nalgebra)
+        );
+        assert_eq!(
+            indoc! {
+                r"
+                pub a: cgmath::Vector2<f32>,
+                pub b: cgmath::Vector4<u32>,
+                pub c: cgmath::Matrix4<f32>,
+                pub d: [[f32; 4]; 2],
+                pub e: [[f32; 4]; 3],
+                "
+            },
+            write_members(MatrixVectorTypes::Cgmath)
+        );
+        assert_eq!(
+            indoc! {
+                r"
+                pub a: mint::Vector2<f32>,
+                pub b: mint::Vector4<u32>,
+                pub c: mint::ColumnMatrix4<f32>,
+                pub d: mint::ColumnMatrix4x2<f32>,
+                pub e: [[f32; 4]; 3],
+                "
+            },
+            write_members(MatrixVectorTypes::Mint)
+        );
+    }
+
+    #[test]
+    fn write_structs_encase() {
+        let source = indoc! {r#"
+            struct PointLight {
+                position: vec4<f32>;
+            };
+
+            struct Lights {
+                lights: array<PointLight, 4>;
+            };
+
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> lights: Lights;
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return lights.lights[0].position;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        let options = WriterOptions {
+            derive_encase: true,
+            ..Default::default()
+        };
+        write_structs(&mut actual, 0, &module, &options);
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `Lights`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, encase::ShaderType)]
+                pub struct Lights {
+                    pub lights: [PointLight; 4],
+                }
+                /// Generated from the WGSL struct `PointLight`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, encase::ShaderType)]
+                pub struct PointLight {
+                    pub position: glam::Vec4,
+                }
+                /// Generated from the WGSL struct `VertexInput`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct VertexInput {
+                    pub position: [f32; 3],
+                    pub _padding0: [u8; 4],
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_encase_vector_types() {
+        let source = indoc! {r#"
+            struct Light {
+                direction: vec3<f32>;
+                intensity: f32;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> light: Light;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(light.direction, light.intensity);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let write_members = |matrix_vector_types| {
+            let mut actual = String::new();
+            let options = WriterOptions {
+                derive_encase: true,
+                matrix_vector_types,
+                ..Default::default()
+            };
+            write_structs(&mut actual, 0, &module, &options);
+            actual
+        };
+
+        // Arrays don't have the alignment of WGSL vectors with encase.
+        let glam_members = indoc! {r"
+            pub direction: glam::Vec3,
+            pub intensity: f32,
+        "};
+        assert!(write_members(MatrixVectorTypes::Rust).contains(&indent(glam_members, 4)));
+        assert!(write_members(MatrixVectorTypes::GlamMatrices).contains(&indent(glam_members, 4)));
+        assert!(write_members(MatrixVectorTypes::Mint)
+            .contains("    pub direction: mint::Vector3<f32>,\n"));
+    }
+
+    #[test]
+    fn write_structs_crevice() {
+        let source = indoc! {r#"
+            struct Camera {
+                position: vec4<f32>;
+            };
+
+            struct Particles {
+                positions: array<vec4<f32>, 64>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+            [[group(0), binding(1)]]
+            var<storage, read_write> particles: Particles;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {
+                particles.positions[0] = camera.position;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        let options = WriterOptions {
+            derive_crevice: true,
+            ..Default::default()
+        };
+        write_structs(&mut actual, 0, &module, &options);
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `Camera`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, crevice::std140::AsStd140)]
+                pub struct Camera {
+                    pub position: glam::Vec4,
+                }
+                impl Camera {
+                    pub fn write_buffer_std140(&self, queue: &wgpu::Queue, buffer: &wgpu::Buffer) {
+                        let data = crevice::std140::AsStd140::as_std140(self);
+                        queue.write_buffer(buffer, 0, crevice::std140::Std140::as_bytes(&data));
+                    }
+                }
+                /// Generated from the WGSL struct `Particles`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, crevice::std430::AsStd430)]
+                pub struct Particles {
+                    pub positions: [glam::Vec4; 64],
+                }
+                impl Particles {
+                    pub fn write_buffer_std430(&self, queue: &wgpu::Queue, buffer: &wgpu::Buffer) {
+                        let data = crevice::std430::AsStd430::as_std430(self);
+                        queue.write_buffer(buffer, 0, crevice::std430::Std430::as_bytes(&data));
+                    }
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_buffer_wrappers_uniform_storage() {
+        let source = indoc! {r#"
+            struct Camera {
+                position: vec4<f32>;
+            };
+
+            struct Particles {
+                positions: array<vec4<f32>>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+            [[group(0), binding(1)]]
+            var<storage, read_write> particles: Particles;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {
+                particles.positions[0] = camera.position;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_buffer_wrappers(
+            &mut actual,
+            0,
+            &module,
+            Some("shader.wgsl"),
+            &WriterOptions::default(),
+        );
+
+        // Runtime sized structs don't have a fixed size to initialize from.
+        assert_eq!(
+            indoc! {
+                r#"
+                impl Camera {
+                    /// Write the contents to the start of `buffer` like when updating uniforms each frame.
+                    pub fn write_buffer(&self, queue: &wgpu::Queue, buffer: &wgpu::Buffer) {
+                        queue.write_buffer(buffer, 0, bytemuck::bytes_of(self));
+                    }
+
+                    pub fn create_uniform_buffer(&self, device: &wgpu::Device) -> wgpu::Buffer {
+                        wgpu::util::DeviceExt::create_buffer_init(
+                            device,
+                            &wgpu::util::BufferInitDescriptor {
+                                label: Some("shader.wgsl Camera uniform buffer"),
+                                contents: bytemuck::bytes_of(self),
+                                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                            },
+                        )
+                    }
+                }
+                /// A buffer containing a [Camera] with the usages required by the shader.
+                pub struct CameraBuffer(wgpu::Buffer);
+                impl CameraBuffer {
+                    pub fn new(device: &wgpu::Device, data: &Camera) -> Self {
+                        Self(wgpu::util::DeviceExt::create_buffer_init(
+                            device,
+                            &wgpu::util::BufferInitDescriptor {
+                                label: Some("shader.wgsl Camera buffer"),
+                                contents: bytemuck::bytes_of(data),
+                                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                            },
+                        ))
+                    }
+
+                    pub fn write(&self, queue: &wgpu::Queue, data: &Camera) {
+                        data.write_buffer(queue, &self.0);
+                    }
+
+                    pub fn as_binding(&self) -> wgpu::BufferBinding<'_> {
+                        self.0.as_entire_buffer_binding()
+                    }
+                }
+                "#
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_buffer_wrappers_multiple_usages() {
+        let source = indoc! {r#"
+            struct Camera {
+                position: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+            [[group(0), binding(1)]]
+            var<storage, read> cameras: Camera;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {
+                let position = camera.position + cameras.position;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_buffer_wrappers(&mut actual, 0, &module, None, &WriterOptions::default());
+
+        assert!(actual.contains("                label: None,\n"));
+        assert!(actual.contains("pub fn create_uniform_buffer(&self, device: &wgpu::Device)"));
+        assert!(actual.contains("pub fn create_storage_buffer(&self, device: &wgpu::Device)"));
+        assert!(actual.contains(
+            "usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,"
+        ));
+    }
+
+    #[test]
+    fn write_buffer_wrappers_encase() {
+        let source = indoc! {r#"
+            struct Camera {
+                position: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {
+                let position = camera.position;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        let options = WriterOptions {
+            derive_encase: true,
+            ..Default::default()
+        };
+        write_buffer_wrappers(&mut actual, 0, &module, None, &options);
+
+        // The encase representation isn't the same as the struct bytes.
+        assert_eq!("", actual);
+    }
+
+    #[test]
+    fn write_structs_padding() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: f32;
+                b: vec4<f32>;
+                c: vec3<f32>;
+                d: f32;
+                e: vec2<f32>;
+            };
+
+            struct VertexInput {
+                [[location(0)]] a: f32;
+                [[location(1)]] b: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return uniforms.b;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_structs(&mut actual, 0, &module, &WriterOptions::default());
+
+        // Vertex inputs aren't host-shareable, so only the uniform struct has padding.
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `Uniforms`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct Uniforms {
+                    pub a: f32,
+                    pub _padding0: [u8; 12],
+                    pub b: [f32; 4],
+                    pub c: [f32; 3],
+                    pub d: f32,
+                    pub e: [f32; 2],
+                    pub _padding1: [u8; 8],
+                }
+                /// Generated from the WGSL struct `VertexInput`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct VertexInput {
+                    pub a: f32,
+                    pub _padding0: [u8; 12],
+                    pub b: [f32; 4],
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_default() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: f32;
+                b: vec4<f32>;
+                c: array<vec4<f32>, 64>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+
+            [[stage(fragment)]]
+            fn main() {
+                let x = uniforms.a;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        let options = WriterOptions {
+            impl_default: true,
+            ..Default::default()
+        };
+        write_structs(&mut actual, 0, &module, &options);
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `Uniforms`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct Uniforms {
+                    pub a: f32,
+                    pub _padding0: [u8; 12],
+                    pub b: [f32; 4],
+                    pub c: [[f32; 4]; 64],
+                }
+                impl Default for Uniforms {
+                    fn default() -> Self {
+                        Self {
+                            a: Default::default(),
+                            _padding0: [0; 12],
+                            b: Default::default(),
+                            c: [Default::default(); 64],
+                        }
+                    }
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_serde() {
+        let source = indoc! {r#"
+            struct Material {
+                color: vec4<f32>;
+                roughness: f32;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        let options = WriterOptions {
+            derive_serde: true,
+            ..Default::default()
+        };
+        write_structs(&mut actual, 0, &module, &options);
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `Material`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable, serde::Serialize, serde::Deserialize)]
+                pub struct Material {
+                    pub color: [f32; 4],
+                    pub roughness: f32,
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_extra_derives() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: vec4<f32>;
+            };
+
+            struct VertexInput {
+                [[location(0)]] position: vec4<f32>;
+            };
+
+            struct Other {
+                a: f32;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return uniforms.a;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        let options = WriterOptions {
+            derive_bytemuck: false,
+            buffer_struct_derives: vec!["PartialOrd".to_string()],
+            vertex_input_struct_derives: vec!["Default".to_string(), "Hash".to_string()],
+            struct_derives: vec!["bevy_reflect::Reflect".to_string()],
+            ..Default::default()
+        };
+        write_structs(&mut actual, 0, &module, &options);
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `Other`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bevy_reflect::Reflect)]
+                pub struct Other {
+                    pub a: f32,
+                }
+                /// Generated from the WGSL struct `Uniforms`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+                pub struct Uniforms {
+                    pub a: [f32; 4],
+                }
+                /// Generated from the WGSL struct `VertexInput`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, Default, Hash)]
+                pub struct VertexInput {
+                    pub position: [f32; 4],
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_nested() {
+        let source = indoc! {r#"
+            struct PointLight {
+                position: vec4<f32>;
+            };
+
+            struct Lights {
+                light: PointLight;
+                lights: array<PointLight, 4>;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_structs(&mut actual, 0, &module, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `Lights`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct Lights {
+                    pub light: PointLight,
+                    pub lights: [PointLight; 4],
+                }
+                /// Generated from the WGSL struct `PointLight`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct PointLight {
+                    pub position: [f32; 4],
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_vertex_arguments() {
+        let source = indoc! {r#"
+            [[stage(vertex)]]
+            fn vs_main(
+                [[builtin(vertex_index)]] index: u32,
+                [[location(0)]] position: vec3<f32>,
+                [[location(1)]] uv: vec2<f32>
+            ) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(position, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_structs(&mut actual, 0, &module, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {r"
+                /// Generated from the vertex entry point arguments with locations.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct VsMainInput {
+                    pub position: [f32; 3],
+                    pub _padding0: [u8; 4],
+                    pub uv: [f32; 2],
+                    pub _padding1: [u8; 8],
+                }
+            "},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_without_bytemuck() {
+        let source = indoc! {r#"
+            struct A {
+                a: vec4<f32>;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_structs(
+            &mut actual,
+            0,
+            &module,
+            &WriterOptions {
+                derive_bytemuck: false,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Generated from the WGSL struct `A`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq)]
+                pub struct A {
+                    pub a: [f32; 4],
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_compute() {
+        // The actual content of the structs doesn't matter.
+        // We only care about the groups and bindings.
+        let source = indoc! {r#"
+            struct VertexInput0 {};
+            struct VertexWeight {};
+            struct Vertices {};
+            struct VertexWeights {};
+            struct Transforms {};
+
+            [[group(0), binding(0)]] var<storage, read> src : Vertices;
+            [[group(0), binding(1)]] var<storage, read> vertex_weights : VertexWeights;
+            [[group(0), binding(2)]] var<storage, read_write> dst : Vertices;
+
+            [[group(1), binding(0)]] var<uniform> transforms: Transforms;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in bind_group_data {
+            write_bind_group_layout(
+                &mut actual,
+                0,
+                &module,
+                group_no,
+                &group,
+                &WriterOptions::default(),
+            );
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                group_no,
+                &group,
+                None,
+                &WriterOptions::default(),
+            );
+        }
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// The resources for the bindings in group 0.
+                pub struct BindGroupLayout0<'a> {
+                    /// Group 0 binding 0 for `var<storage, read> src: Vertices`.
+                    pub src: wgpu::BufferBinding<'a>,
+                    /// Group 0 binding 1 for `var<storage, read> vertex_weights: VertexWeights`.
+                    pub vertex_weights: wgpu::BufferBinding<'a>,
+                    /// Group 0 binding 2 for `var<storage, read_write> dst: Vertices`.
+                    pub dst: wgpu::BufferBinding<'a>,
+                }
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1u32,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 2u32,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ]
+                };
+                /// The resources for the bindings in group 1.
+                pub struct BindGroupLayout1<'a> {
+                    /// Group 1 binding 0 for `var<uniform> transforms: Transforms`.
+                    pub transforms: wgpu::BufferBinding<'a>,
+                }
+                const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_vertex_fragment() {
+        // The actual content of the structs doesn't matter.
+        // We only care about the groups and bindings.
+        // Test different texture and sampler types.
+        let source = indoc! {r#"
+            struct Transforms {};
+
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(0), binding(1)]]
+            var color_sampler: sampler;
+            [[group(0), binding(2)]]
+            var depth_texture: texture_depth_2d;
+            [[group(0), binding(3)]]
+            var comparison_sampler: sampler_comparison;
+
+            [[group(1), binding(0)]] var<uniform> transforms: Transforms;
+
+            [[stage(vertex)]]
+            fn vs_main() {}
+
+            [[stage(fragment)]]
+            fn fs_main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in bind_group_data {
+            write_bind_group_layout(
+                &mut actual,
+                0,
+                &module,
+                group_no,
+                &group,
+                &WriterOptions::default(),
+            );
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                group_no,
+                &group,
+                None,
+                &WriterOptions::default(),
+            );
+        }
+
+        // TODO: Are storage buffers valid for vertex/fragment?
+        assert_eq!(
+            indoc! {
+                r"
+                /// The resources for the bindings in group 0.
+                pub struct BindGroupLayout0<'a> {
+                    /// Group 0 binding 0 for `var color_texture: texture_2d<f32>`.
+                    pub color_texture: &'a wgpu::TextureView,
+                    /// Group 0 binding 1 for `var color_sampler: sampler`.
+                    pub color_sampler: &'a wgpu::Sampler,
+                    /// Group 0 binding 2 for `var depth_texture: texture_depth_2d`.
+                    pub depth_texture: &'a wgpu::TextureView,
+                    /// Group 0 binding 3 for `var comparison_sampler: sampler_comparison`.
+                    pub comparison_sampler: &'a wgpu::Sampler,
+                }
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1u32,
+                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 2u32,
+                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Depth,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 3u32,
+                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                            count: None,
+                        },
+                    ]
+                };
+                /// The resources for the bindings in group 1.
+                pub struct BindGroupLayout1<'a> {
+                    /// Group 1 binding 0 for `var<uniform> transforms: Transforms`.
+                    pub transforms: wgpu::BufferBinding<'a>,
+                }
+                const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_storage_textures() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var write_texture: texture_storage_2d<rgba8unorm, write>;
+            [[group(0), binding(1)]]
+            var read_texture: texture_storage_3d<r32float, read>;
+
+            [[stage(compute), workgroup_size(1)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in bind_group_data {
+            write_bind_group_layout(
+                &mut actual,
+                0,
+                &module,
+                group_no,
+                &group,
+                &WriterOptions::default(),
+            );
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                group_no,
+                &group,
+                None,
+                &WriterOptions::default(),
+            );
+        }
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// The resources for the bindings in group 0.
+                pub struct BindGroupLayout0<'a> {
+                    /// Group 0 binding 0 for `var write_texture: texture_storage_2d<rgba8unorm, write>`.
+                    pub write_texture: &'a wgpu::TextureView,
+                    /// Group 0 binding 1 for `var read_texture: texture_storage_3d<r32float, read>`.
+                    pub read_texture: &'a wgpu::TextureView,
+                }
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::StorageTexture {
+                                access: wgpu::StorageTextureAccess::WriteOnly,
+                                format: wgpu::TextureFormat::Rgba8Unorm,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1u32,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::StorageTexture {
+                                access: wgpu::StorageTextureAccess::ReadOnly,
+                                format: wgpu::TextureFormat::R32Float,
+                                view_dimension: wgpu::TextureViewDimension::D3,
+                            },
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_integer_textures() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var id_texture: texture_2d<u32>;
+            [[group(0), binding(1)]]
+            var offset_texture: texture_2d<i32>;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in bind_group_data {
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                group_no,
+                &group,
+                None,
+                &WriterOptions::default(),
+            );
+        }
+
+        assert_eq!(
+            indoc! {
+                r"
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Uint,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Sint,
+                            },
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_non_filterable() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var data_texture: texture_2d<f32>;
+            [[group(0), binding(1)]]
+            var data_sampler: sampler;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in bind_group_data {
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                group_no,
+                &group,
+                None,
+                &WriterOptions {
+                    non_filterable_textures: vec!["data_texture".to_string()],
+                    non_filtering_samplers: vec!["data_sampler".to_string()],
+                    ..Default::default()
+                },
+            );
+        }
+
+        assert_eq!(
+            indoc! {
+                r"
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::NonFiltering),
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_depth_textures() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]] var shadow_map: texture_depth_2d;
+            [[group(0), binding(1)]] var shadow_maps: texture_depth_2d_array;
+            [[group(0), binding(2)]] var point_shadow: texture_depth_cube;
+            [[group(0), binding(3)]] var point_shadows: texture_depth_cube_array;
+            [[group(0), binding(4)]] var shadow_ms: texture_depth_multisampled_2d;
+            [[group(0), binding(5)]] var shadow_sampler: sampler_comparison;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                let a = textureSampleCompare(shadow_map, shadow_sampler, vec2<f32>(0.0), 0.5);
+                let b = textureSampleCompare(shadow_maps, shadow_sampler, vec2<f32>(0.0), 1, 0.5);
+                let c = textureSampleCompare(point_shadow, shadow_sampler, vec3<f32>(0.0), 0.5);
+                let d = textureSampleCompare(point_shadows, shadow_sampler, vec3<f32>(0.0), 1, 0.5);
+                let e = textureLoad(shadow_ms, vec2<i32>(0), 0);
+                return vec4<f32>(a + b + c + d + e);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in &bind_group_data {
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                *group_no,
+                group,
+                None,
+                &WriterOptions::default(),
+            );
+        }
+
+        assert_eq!(
+            indoc! {
+                r"
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Depth,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2Array,
+                                sample_type: wgpu::TextureSampleType::Depth,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 2u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::Cube,
+                                sample_type: wgpu::TextureSampleType::Depth,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 3u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::CubeArray,
+                                sample_type: wgpu::TextureSampleType::Depth,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 4u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: true,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Depth,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 5u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+
+        // Dummy multisampled textures need multiple samples to match the layout.
+        let mut actual = String::new();
+        write_dummy_bind_group(
+            &mut actual,
+            0,
+            &module,
+            0,
+            &bind_group_data[&0],
+            &WriterOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(4, actual.matches("sample_count: 1,").count());
+        assert_eq!(1, actual.matches("sample_count: 4,").count());
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_min_binding_size() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: vec4<f32>;
+                b: f32;
+            };
+
+            struct Particles {
+                count: u32;
+                positions: array<vec4<f32>>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+            [[group(0), binding(1)]]
+            var<storage, read_write> particles: Particles;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {
+                particles.positions[0] = uniforms.a;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in bind_group_data {
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                group_no,
+                &group,
+                None,
+                &WriterOptions::default(),
+            );
+        }
+
+        // Runtime sized arrays can't be validated ahead of time.
+        assert_eq!(
+            indoc! {
+                r"
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: std::num::NonZeroU64::new(32),
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1u32,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_vertex() {
+        // The actual content of the structs doesn't matter.
+        // We only care about the groups and bindings.
+        let source = indoc! {r#"
+            struct Transforms {};
+
+            [[group(0), binding(0)]] var<uniform> transforms: Transforms;
+
+            [[stage(vertex)]]
+            fn vs_main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in bind_group_data {
+            write_bind_group_layout(
+                &mut actual,
+                0,
+                &module,
+                group_no,
+                &group,
+                &WriterOptions::default(),
+            );
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                group_no,
+                &group,
+                None,
+                &WriterOptions::default(),
+            );
+        }
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// The resources for the bindings in group 0.
+                pub struct BindGroupLayout0<'a> {
+                    /// Group 0 binding 0 for `var<uniform> transforms: Transforms`.
+                    pub transforms: wgpu::BufferBinding<'a>,
+                }
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::VERTEX,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn bind_group_layouts_descriptors_fragment() {
+        // The actual content of the structs doesn't matter.
+        // We only care about the groups and bindings.
+        let source = indoc! {r#"
+            struct Transforms {};
+
+            [[group(0), binding(0)]] var<uniform> transforms: Transforms;
+
+            [[stage(fragment)]]
+            fn fs_main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        for (group_no, group) in bind_group_data {
+            write_bind_group_layout(
+                &mut actual,
+                0,
+                &module,
+                group_no,
+                &group,
+                &WriterOptions::default(),
+            );
+            write_bind_group_layout_descriptor(
+                &mut actual,
+                0,
+                group_no,
+                &group,
+                None,
+                &WriterOptions::default(),
+            );
+        }
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// The resources for the bindings in group 0.
+                pub struct BindGroupLayout0<'a> {
+                    /// Group 0 binding 0 for `var<uniform> transforms: Transforms`.
+                    pub transforms: wgpu::BufferBinding<'a>,
+                }
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0u32,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ]
+                };
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_push_constants_vertex_fragment() {
+        let source = indoc! {r#"
+            struct PushConstants {
+                color: vec4<f32>;
+                scale: f32;
+            };
+
+            var<push_constant> constants: PushConstants;
+
+            [[stage(vertex)]]
+            fn vs_main() {}
+
+            [[stage(fragment)]]
+            fn fs_main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let push_constant = wgsl::get_push_constant(&module).unwrap();
+
+        let mut actual = String::new();
+        write_push_constants(
+            &mut actual,
+            &module,
+            &push_constant,
+            wgpu::ShaderStages::VERTEX_FRAGMENT,
+            &WriterOptions::default(),
+        );
+
+        assert_eq!(
+            indoc! {
+                r"
+                pub const PUSH_CONSTANT_RANGE: wgpu::PushConstantRange = wgpu::PushConstantRange {
+                    stages: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    range: 0..32,
+                };
+                pub fn set_push_constants<'a>(pass: &mut wgpu::RenderPass<'a>, push_constants: PushConstants) {
+                    pass.set_push_constants(wgpu::ShaderStages::VERTEX_FRAGMENT, 0, bytemuck::bytes_of(&push_constants));
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_push_constants_compute() {
+        let source = indoc! {r#"
+            struct PushConstants {
+                offset: u32;
+            };
+
+            var<push_constant> constants: PushConstants;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let push_constant = wgsl::get_push_constant(&module).unwrap();
+
+        let mut actual = String::new();
+        write_push_constants(
+            &mut actual,
+            &module,
+            &push_constant,
+            wgpu::ShaderStages::COMPUTE,
+            &WriterOptions::default(),
+        );
+
+        assert_eq!(
+            indoc! {
+                r"
+                pub const PUSH_CONSTANT_RANGE: wgpu::PushConstantRange = wgpu::PushConstantRange {
+                    stages: wgpu::ShaderStages::COMPUTE,
+                    range: 0..4,
+                };
+                pub fn set_push_constants<'a>(pass: &mut wgpu::ComputePass<'a>, push_constants: PushConstants) {
+                    pass.set_push_constants(0, bytemuck::bytes_of(&push_constants));
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn dummy_bind_group() {
+        let source = indoc! {r#"
+            struct Transforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]] var<uniform> transforms: Transforms;
+            [[group(0), binding(1)]]
+            var depth_texture: texture_depth_cube;
+            [[group(0), binding(2)]]
+            var comparison_sampler: sampler_comparison;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        write_dummy_bind_group(
+            &mut actual,
+            0,
+            &module,
+            0,
+            &bind_group_data[&0],
+            &WriterOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            indoc! {
+                r"
+                /// Create the bind group with placeholder resources for tests that only need a valid pipeline.
+                pub fn dummy(device: &wgpu::Device) -> Self {
+                    let transforms = device.create_buffer(&wgpu::BufferDescriptor {
+                        label: None,
+                        size: 16,
+                        usage: wgpu::BufferUsages::UNIFORM,
+                        mapped_at_creation: false,
+                    });
+                    let depth_texture = device
+                        .create_texture(&wgpu::TextureDescriptor {
+                            label: None,
+                            size: wgpu::Extent3d {
+                                width: 1,
+                                height: 1,
+                                depth_or_array_layers: 6,
+                            },
+                            mip_level_count: 1,
+                            sample_count: 1,
+                            dimension: wgpu::TextureDimension::D2,
+                            format: wgpu::TextureFormat::Depth32Float,
+                            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                        })
+                        .create_view(&wgpu::TextureViewDescriptor {
+                            dimension: Some(wgpu::TextureViewDimension::Cube),
+                            ..Default::default()
+                        });
+                    let comparison_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+                        compare: Some(wgpu::CompareFunction::LessEqual),
+                        ..Default::default()
+                    });
+                    Self::from_bindings(device, BindGroupLayout0 {
+                        transforms: transforms.as_entire_buffer_binding(),
+                        depth_texture: &depth_texture,
+                        comparison_sampler: &comparison_sampler,
+                    })
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn dummy_bind_group_unsupported_binding() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]] var<storage, read_write> a: atomic<u32>;
+        "#};
+
+        // The bind group data normally rejects these types, so construct the group directly.
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let (_, global) = module.global_variables.iter().next().unwrap();
+        let group = wgsl::GroupData {
+            bindings: vec![wgsl::GroupBinding {
+                name: global.name.clone(),
+                binding_index: 0,
+                binding_type: &module.types[global.ty],
+                storage_class: naga::StorageClass::Handle,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                location: None,
+                min_binding_size: None,
+            }],
+        };
+
+        let mut actual = String::new();
+        let result = write_dummy_bind_group(
+            &mut actual,
+            0,
+            &module,
+            0,
+            &group,
+            &WriterOptions::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::UnsupportedBinding { group: 0, binding: 0, name, ty, .. })
+                if name == "a" && ty == "atomic<u32>"
+        ));
+    }
+
+    #[test]
+    fn write_bind_group_layout_new_buffers() {
+        let source = indoc! {r#"
+            struct Transforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(0), binding(1)]]
+            var color_sampler: sampler;
+            [[group(0), binding(2)]]
+            var<uniform> transforms: Transforms;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return transforms.a + textureSample(color_texture, color_sampler, vec2<f32>(0.0));
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        write_bind_group_layout_new(
+            &mut actual,
+            0,
+            0,
+            &bind_group_data[&0],
+            &WriterOptions::default(),
+        );
+
+        assert_eq!(
+            indoc! {
+                r"
+                impl<'a> BindGroupLayout0<'a> {
+                    /// Bind the entire buffer for each buffer binding.
+                    /// Set the fields directly to bind only part of a buffer.
+                    pub fn new(
+                        color_texture: &'a wgpu::TextureView,
+                        color_sampler: &'a wgpu::Sampler,
+                        transforms: &'a wgpu::Buffer,
+                    ) -> Self {
+                        Self {
+                            color_texture,
+                            color_sampler,
+                            transforms: transforms.as_entire_buffer_binding(),
+                        }
+                    }
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_bind_group_layout_new_no_buffers() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(color_texture, vec2<i32>(0), 0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        // The struct literal is just as short without any buffers.
+        let mut actual = String::new();
+        write_bind_group_layout_new(
+            &mut actual,
+            0,
+            0,
+            &bind_group_data[&0],
+            &WriterOptions::default(),
+        );
+        assert_eq!("", actual);
+    }
+
+    #[test]
+    fn write_bind_group_layouts_two_groups() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(1), binding(0)]]
+            var color_sampler: sampler;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureSample(color_texture, color_sampler, vec2<f32>(0.0));
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        write_bind_group_layouts(
+            &mut actual,
+            0,
+            &bind_group_data,
+            None,
+            &WriterOptions::default(),
+        );
+
+        assert_eq!(
+            indoc! {r"
+                /// The layouts for all bind groups to avoid creating duplicate layouts.
+                /// Use the same layouts for the pipeline layout and bind groups.
+                pub struct BindGroupLayouts {
+                    pub bind_group_layout0: wgpu::BindGroupLayout,
+                    pub bind_group_layout1: wgpu::BindGroupLayout,
+                }
+                impl BindGroupLayouts {
+                    /// Create the layouts for all bind groups in the shader.
+                    pub fn new(device: &wgpu::Device) -> Self {
+                        Self {
+                            bind_group_layout0: BindGroup0::get_bind_group_layout(device),
+                            bind_group_layout1: BindGroup1::get_bind_group_layout(device),
+                        }
+                    }
+                    /// Create bind group 0 with the layout in `bind_group_layout0`.
+                    pub fn create_bind_group0(
+                        &self,
+                        device: &wgpu::Device,
+                        bindings: BindGroupLayout0,
+                    ) -> BindGroup0 {
+                        BindGroup0::from_bindings_with_layout(device, &self.bind_group_layout0, bindings)
+                    }
+                    /// Create bind group 1 with the layout in `bind_group_layout1`.
+                    pub fn create_bind_group1(
+                        &self,
+                        device: &wgpu::Device,
+                        bindings: BindGroupLayout1,
+                    ) -> BindGroup1 {
+                        BindGroup1::from_bindings_with_layout(device, &self.bind_group_layout1, bindings)
+                    }
+                }
+            "},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_bind_group_layouts_placeholder() {
+        let source = indoc! {r#"
+            [[group(1), binding(0)]]
+            var color_texture: texture_2d<f32>;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(color_texture, vec2<i32>(0), 0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_sparse_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        write_bind_group_layouts(
+            &mut actual,
+            0,
+            &bind_group_data,
+            Some("shader.wgsl"),
+            &WriterOptions::default(),
+        );
+
+        assert_eq!(
+            indoc! {r#"
+                /// The layouts for all bind groups to avoid creating duplicate layouts.
+                /// Use the same layouts for the pipeline layout and bind groups.
+                pub struct BindGroupLayouts {
+                    pub bind_group_layout1: wgpu::BindGroupLayout,
+                    /// The layout for groups without bindings in the shader.
+                    pub empty_bind_group_layout: wgpu::BindGroupLayout,
+                }
+                impl BindGroupLayouts {
+                    /// Create the layouts for all bind groups in the shader.
+                    pub fn new(device: &wgpu::Device) -> Self {
+                        Self {
+                            bind_group_layout1: BindGroup1::get_bind_group_layout(device),
+                            empty_bind_group_layout: device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                                label: Some("shader.wgsl empty bind group layout"),
+                                entries: &[],
+                            }),
+                        }
+                    }
+                    /// Create bind group 1 with the layout in `bind_group_layout1`.
+                    pub fn create_bind_group1(
+                        &self,
+                        device: &wgpu::Device,
+                        bindings: BindGroupLayout1,
+                    ) -> BindGroup1 {
+                        BindGroup1::from_bindings_with_layout(device, &self.bind_group_layout1, bindings)
+                    }
+                }
+            "#},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_bind_groups_module_shared_layout_descriptors() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]] var color_texture: texture_2d<f32>;
+            [[group(0), binding(1)]] var color_sampler: sampler;
+            [[group(1), binding(0)]] var depth_texture: texture_depth_2d;
+            [[group(2), binding(0)]] var normal_texture: texture_2d<f32>;
+            [[group(2), binding(1)]] var normal_sampler: sampler;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                let depth = textureLoad(depth_texture, vec2<i32>(0), 0);
+                let normal = textureSample(normal_texture, normal_sampler, vec2<f32>(0.0));
+                return textureSample(color_texture, color_sampler, vec2<f32>(0.0)) + normal * depth;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+        let options = WriterOptions::default();
+        assert_eq!(
+            BTreeMap::from([(2, 0)]),
+            shared_layout_descriptors(&bind_group_data, &options)
+        );
+
+        let mut actual = String::new();
+        write_bind_groups_module(
+            &mut actual,
+            &module,
+            &bind_group_data,
+            &BTreeMap::new(),
+            None,
+            &options,
+        )
+        .unwrap();
+        assert!(actual.contains("    const LAYOUT_ENTRIES0: &[wgpu::BindGroupLayoutEntry] = &[\n"));
+        assert!(!actual.contains("LAYOUT_ENTRIES1"));
+        assert!(!actual.contains("LAYOUT_ENTRIES2: "));
+        assert!(actual.contains(&indent(
+            indoc! {r"
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: LAYOUT_ENTRIES0,
+                };
+            "},
+            4
+        )));
+        assert!(actual.contains("    const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {\n        label: None,\n        entries: &[\n"));
+        assert!(actual.contains(&indent(
+            indoc! {r"
+                /// Group 2 has the same layout entries as group 0.
+                const LAYOUT_DESCRIPTOR2: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: LAYOUT_ENTRIES0,
+                };
+            "},
+            4
+        )));
+
+        // Options that change the entries of a group also prevent sharing the descriptor.
+        let options = WriterOptions {
+            non_filterable_textures: vec!["normal_texture".to_string()],
+            non_filtering_samplers: vec!["normal_sampler".to_string()],
+            ..Default::default()
+        };
+        assert!(shared_layout_descriptors(&bind_group_data, &options).is_empty());
+    }
+
+    #[test]
+    fn create_shader_module_shared_layout_descriptor_labels() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]] var color_texture: texture_2d<f32>;
+            [[group(1), binding(0)]] var normal_texture: texture_2d<f32>;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(color_texture, vec2<i32>(0), 0) + textureLoad(normal_texture, vec2<i32>(0), 0);
+            }
+        "#};
+
+        let options = WriterOptions {
+            generate_labels: true,
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        // Each group keeps its own label even though the entries are shared.
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: Some("shader.wgsl bind group layout 0"),
+                    entries: LAYOUT_ENTRIES0,
+                };
+            "#},
+            4
+        )));
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                /// Group 1 has the same layout entries as group 0.
+                const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
+                    label: Some("shader.wgsl bind group layout 1"),
+                    entries: LAYOUT_ENTRIES0,
+                };
+            "#},
+            4
+        )));
+    }
+
+    #[test]
+    fn write_constants_scalars() {
+        let source = indoc! {r#"
+            let MAX_LIGHTS: u32 = 16u;
+            let OFFSET: i32 = -2;
+            let PI: f32 = 3.14159;
+            let SHADOWS: bool = true;
+            let scale: f32 = 0.1;
+            let ORIGIN: vec2<f32> = vec2<f32>(0.0, 0.0);
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(PI * scale, f32(MAX_LIGHTS), 0.0, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_constants(&mut actual, &module, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {r"
+                pub const MAX_LIGHTS: u32 = 16;
+                pub const OFFSET: i32 = -2;
+                pub const PI: f32 = 3.14159;
+                pub const SHADOWS: bool = true;
+                #[allow(non_upper_case_globals)]
+                pub const scale: f32 = 0.1;
+            "},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_structs_type_aliases() {
+        let source = indoc! {r#"
+            type Color = vec4<f32>;
+            type Transform = mat4x4<f32>;
+            type Weights = array<f32, 4>;
+
+            struct Material {
+                color: Color;
+                transform: Transform;
+                weights: Weights;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> material: Material;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return material.transform * material.color * material.weights[0];
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_structs(&mut actual, 0, &module, &WriterOptions::default());
+        assert!(actual.contains(indoc! {r"
+            pub struct Material {
+                pub color: [f32; 4],
+                pub transform: glam::Mat4,
+                pub weights: [f32; 4],
+            }
+        "}));
+
+        let options = WriterOptions {
+            type_aliases: true,
+            matrix_vector_types: MatrixVectorTypes::Glam,
+            ..Default::default()
+        };
+        let mut actual = String::new();
+        write_type_aliases(&mut actual, &module, &options);
+        assert_eq!(
+            indoc! {r"
+                pub type Color = glam::Vec4;
+                pub type Transform = glam::Mat4;
+                pub type Weights = [f32; 4];
+            "},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_binding_info_two_groups() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(1), binding(0)]]
+            var color_sampler: sampler;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureSample(color_texture, color_sampler, vec2<f32>(0.0));
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        write_binding_info(&mut actual, 0, &bind_group_data, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {r#"
+                /// A description of a binding in the shader for inspecting the bind groups at runtime.
+                #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                pub struct BindingInfo {
+                    pub group: u32,
+                    pub binding: u32,
+                    /// The name of the global variable in the shader.
+                    pub name: &'static str,
+                    pub visibility: wgpu::ShaderStages,
+                    pub ty: wgpu::BindingType,
+                }
+                /// All bindings in the shader ordered by group and binding index.
+                pub const BINDINGS: &[BindingInfo] = &[
+                    BindingInfo {
+                        group: 0,
+                        binding: 0,
+                        name: "color_texture",
+                        visibility: LAYOUT_DESCRIPTOR0.entries[0].visibility,
+                        ty: LAYOUT_DESCRIPTOR0.entries[0].ty,
+                    },
+                    BindingInfo {
+                        group: 1,
+                        binding: 0,
+                        name: "color_sampler",
+                        visibility: LAYOUT_DESCRIPTOR1.entries[0].visibility,
+                        ty: LAYOUT_DESCRIPTOR1.entries[0].ty,
+                    },
+                ];
+            "#},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_binding_info_empty() {
+        let mut actual = String::new();
+        write_binding_info(&mut actual, 0, &BTreeMap::new(), &WriterOptions::default());
+        assert!(actual.ends_with("pub const BINDINGS: &[BindingInfo] = &[];\n"));
+    }
+
+    #[test]
+    fn write_vertex_module_single_input() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+                [[location(1)]] uv: vec2<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_vertex_module(&mut actual, &module, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {r#"
+                pub mod vertex {
+                    impl super::VertexInput {
+                        pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 2] = [
+                            wgpu::VertexAttribute {
+                                format: wgpu::VertexFormat::Float32x3,
+                                offset: 0,
+                                shader_location: 0,
+                            },
+                            wgpu::VertexAttribute {
+                                format: wgpu::VertexFormat::Float32x2,
+                                offset: 16,
+                                shader_location: 1,
+                            },
+                        ];
+                        /// The size in bytes of the struct including padding, which is also the vertex buffer stride.
+                        pub const SIZE_IN_BYTES: u64 = 32;
+                        /// Whether this struct contains per vertex or per instance data.
+                        pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+                        /// The layout of a vertex buffer containing an array of this struct.
+                        pub const fn vertex_buffer_layout(step_mode: wgpu::VertexStepMode) -> wgpu::VertexBufferLayout<'static> {
+                            wgpu::VertexBufferLayout {
+                                array_stride: Self::SIZE_IN_BYTES,
+                                step_mode,
+                                attributes: &Self::VERTEX_ATTRIBUTES,
+                            }
+                        }
+                    }
+                }
+            "#},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_vertex_module_non_consecutive_locations() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+                [[location(1)]] normal: vec3<f32>;
+                [[location(4)]] uv: vec2<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_vertex_module(&mut actual, &module, &WriterOptions::default());
+
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 3] = [
+                    wgpu::VertexAttribute {
+                        format: wgpu::VertexFormat::Float32x3,
+                        offset: 0,
+                        shader_location: 0,
+                    },
+                    wgpu::VertexAttribute {
+                        format: wgpu::VertexFormat::Float32x3,
+                        offset: 16,
+                        shader_location: 1,
+                    },
+                    wgpu::VertexAttribute {
+                        format: wgpu::VertexFormat::Float32x2,
+                        offset: 32,
+                        shader_location: 4,
+                    },
+                ];
+            "#},
+            8
+        )));
+    }
+
+    #[test]
+    fn write_vertex_module_format_overrides() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+                [[location(1)]] color: vec4<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let options = WriterOptions {
+            vertex_format_overrides: [("color".to_string(), wgpu::VertexFormat::Unorm8x4)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut actual = String::new();
+        write_structs(&mut actual, 0, &module, &options);
+        assert_eq!(
+            indoc! {r"
+                /// Generated from the WGSL struct `VertexInput`.
+                #[repr(C)]
+                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+                pub struct VertexInput {
+                    pub position: [f32; 3],
+                    pub _padding0: [u8; 4],
+                    pub color: [u8; 4],
+                    pub _padding1: [u8; 12],
+                }
+            "},
+            actual
+        );
+
+        let mut actual = String::new();
+        write_vertex_module(&mut actual, &module, &options);
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                wgpu::VertexAttribute {
+                    format: wgpu::VertexFormat::Unorm8x4,
+                    offset: 16,
+                    shader_location: 1,
+                },
+            "#},
+            12
+        )));
+    }
+
+    #[test]
+    fn write_vertex_module_instance_input() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+            };
+
+            struct InstanceInput {
+                [[location(1)]] offset: vec3<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput, instance: InstanceInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position + instance.offset, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let options = WriterOptions {
+            instance_input_structs: vec!["InstanceInput".to_string()],
+            ..Default::default()
+        };
+        let mut actual = String::new();
+        write_vertex_module(&mut actual, &module, &options);
+
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                impl super::VertexInput {
+                    pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 1] = [
+                        wgpu::VertexAttribute {
+                            format: wgpu::VertexFormat::Float32x3,
+                            offset: 0,
+                            shader_location: 0,
+                        },
+                    ];
+                    /// The size in bytes of the struct including padding, which is also the vertex buffer stride.
+                    pub const SIZE_IN_BYTES: u64 = 16;
+                    /// Whether this struct contains per vertex or per instance data.
+                    pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Vertex;
+        "#},
+            4
+        )));
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                impl super::InstanceInput {
+                    pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 1] = [
+                        wgpu::VertexAttribute {
+                            format: wgpu::VertexFormat::Float32x3,
+                            offset: 0,
+                            shader_location: 1,
+                        },
+                    ];
+                    /// The size in bytes of the struct including padding, which is also the vertex buffer stride.
+                    pub const SIZE_IN_BYTES: u64 = 16;
+                    /// Whether this struct contains per vertex or per instance data.
+                    pub const STEP_MODE: wgpu::VertexStepMode = wgpu::VertexStepMode::Instance;
+        "#},
+            4
+        )));
+    }
+
+    #[test]
+    fn write_entry_points_vertex_fragment_compute() {
+        let source = indoc! {r#"
+            [[stage(vertex)]]
+            fn vs_main() -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+
+            [[stage(fragment)]]
+            fn fsMain() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_entry_points(&mut actual, &module, true, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {r#"
+                pub const ENTRY_FS_MAIN: &str = "fsMain";
+                pub const ENTRY_MAIN: &str = "main";
+                pub const ENTRY_VS_MAIN: &str = "vs_main";
+                pub mod entry_points {
+                    /// Generated from the fragment entry point `fsMain`.
+                    pub mod fs_main {
+                        /// The number of color targets required by the outputs of the entry point.
+                        pub const TARGET_COUNT: usize = 1;
+                        /// The locations of the color outputs in increasing order.
+                        pub const OUTPUT_LOCATIONS: [u32; 1] = [0];
+                        /// The component type of each color output.
+                        /// Float outputs are always `filterable: false`, so compare variants with `matches!`.
+                        pub const OUTPUT_SAMPLE_TYPES: [wgpu::TextureSampleType; 1] = [wgpu::TextureSampleType::Float { filterable: false }];
+                        /// The number of components of each color output.
+                        pub const OUTPUT_COMPONENTS: [u32; 1] = [4];
+                        /// The fragment stage with the entry point filled in.
+                        pub fn fragment_state<'a>(
+                            module: &'a wgpu::ShaderModule,
+                            targets: &'a [wgpu::ColorTargetState],
+                        ) -> wgpu::FragmentState<'a> {
+                            debug_assert!(
+                                targets.len() >= TARGET_COUNT,
+                                "expected at least {TARGET_COUNT} color targets but found {}",
+                                targets.len()
+                            );
+                            wgpu::FragmentState {
+                                module,
+                                entry_point: super::super::ENTRY_FS_MAIN,
+                                targets,
+                            }
+                        }
+                    }
+                    /// Generated from the compute entry point `main`.
+                    pub mod main {
+                        /// The workgroup size from the `workgroup_size` attribute.
+                        pub const WORKGROUP_SIZE: [u32; 3] = [64, 1, 1];
+                        /// The number of workgroups needed to cover `extent` invocations in each dimension.
+                        pub const fn workgroup_count(extent: [u32; 3]) -> [u32; 3] {
+                            [
+                                extent[0] / WORKGROUP_SIZE[0] + (extent[0] % WORKGROUP_SIZE[0] != 0) as u32,
+                                extent[1] / WORKGROUP_SIZE[1] + (extent[1] % WORKGROUP_SIZE[1] != 0) as u32,
+                                extent[2] / WORKGROUP_SIZE[2] + (extent[2] % WORKGROUP_SIZE[2] != 0) as u32,
+                            ]
+                        }
+                        /// Dispatch enough workgroups to cover `extent` invocations in each dimension.
+                        pub fn dispatch_for(pass: &mut wgpu::ComputePass<'_>, extent: [u32; 3]) {
+                            let [x, y, z] = workgroup_count(extent);
+                            pass.dispatch(x, y, z);
+                        }
+                    }
+                    /// Generated from the vertex entry point `vs_main`.
+                    pub mod vs_main {
+                        /// The vertex buffer layouts for the vertex inputs in entry point parameter order.
+                        pub const VERTEX_BUFFERS: [wgpu::VertexBufferLayout<'static>; 0] = [];
+                        /// An owned copy of [VERTEX_BUFFERS] for building pipelines without the generated vertex state.
+                        pub const fn vertex_buffer_layouts() -> [wgpu::VertexBufferLayout<'static>; 0] {
+                            VERTEX_BUFFERS
+                        }
+                        /// The vertex stage with the entry point and vertex buffer layouts filled in.
+                        pub fn vertex_state(module: &wgpu::ShaderModule) -> wgpu::VertexState<'_> {
+                            wgpu::VertexState {
+                                module,
+                                entry_point: super::super::ENTRY_VS_MAIN,
+                                buffers: &VERTEX_BUFFERS,
+                            }
+                        }
+                    }
+                }
+                #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+                pub enum EntryPoint {
+                    FsMain,
+                    Main,
+                    VsMain,
+                }
+                impl EntryPoint {
+                    /// The name of the entry point function in the shader.
+                    pub const fn name(self) -> &'static str {
+                        match self {
+                            Self::FsMain => ENTRY_FS_MAIN,
+                            Self::Main => ENTRY_MAIN,
+                            Self::VsMain => ENTRY_VS_MAIN,
+                        }
+                    }
+                    /// The shader stage of the entry point.
+                    pub const fn stage(self) -> wgpu::ShaderStages {
+                        match self {
+                            Self::FsMain => wgpu::ShaderStages::FRAGMENT,
+                            Self::Main => wgpu::ShaderStages::COMPUTE,
+                            Self::VsMain => wgpu::ShaderStages::VERTEX,
+                        }
+                    }
+                }
+            "#},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_entry_points_vertex_buffers() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+            };
+
+            struct InstanceInput {
+                [[location(1)]] offset: vec3<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn main(
+                [[builtin(vertex_index)]] index: u32,
+                in: VertexInput,
+                instance: InstanceInput
+            ) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position + instance.offset, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_entry_points(&mut actual, &module, true, &WriterOptions::default());
+
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                pub mod main {
+                    /// The vertex buffer layouts for the vertex inputs in entry point parameter order.
+                    pub const VERTEX_BUFFERS: [wgpu::VertexBufferLayout<'static>; 2] = [
+                        super::super::VertexInput::vertex_buffer_layout(super::super::VertexInput::STEP_MODE),
+                        super::super::InstanceInput::vertex_buffer_layout(super::super::InstanceInput::STEP_MODE),
+                    ];
+                    /// An owned copy of [VERTEX_BUFFERS] for building pipelines without the generated vertex state.
+                    pub const fn vertex_buffer_layouts() -> [wgpu::VertexBufferLayout<'static>; 2] {
+                        VERTEX_BUFFERS
+                    }
+                    /// The vertex stage with the entry point and vertex buffer layouts filled in.
+                    pub fn vertex_state(module: &wgpu::ShaderModule) -> wgpu::VertexState<'_> {
+                        wgpu::VertexState {
+                            module,
+                            entry_point: super::super::ENTRY_MAIN,
+                            buffers: &VERTEX_BUFFERS,
+                        }
+                    }
+                }
+            "#},
+            4
+        )));
+    }
+
+    #[test]
+    fn write_entry_points_fragment_outputs() {
+        let source = indoc! {r#"
+            struct FragmentOutput {
+                [[location(0)]] color: vec4<f32>;
+                [[builtin(frag_depth)]] depth: f32;
+                [[location(2)]] id: u32;
+                [[location(1)]] normal: vec2<i32>;
+            };
+
+            [[stage(fragment)]]
+            fn main() -> FragmentOutput {
+                var out: FragmentOutput;
+                return out;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_entry_points(&mut actual, &module, true, &WriterOptions::default());
+
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                pub mod main {
+                    /// The number of color targets required by the outputs of the entry point.
+                    pub const TARGET_COUNT: usize = 3;
+                    /// The locations of the color outputs in increasing order.
+                    pub const OUTPUT_LOCATIONS: [u32; 3] = [0, 1, 2];
+                    /// The component type of each color output.
+                    /// Float outputs are always `filterable: false`, so compare variants with `matches!`.
+                    pub const OUTPUT_SAMPLE_TYPES: [wgpu::TextureSampleType; 3] = [wgpu::TextureSampleType::Float { filterable: false }, wgpu::TextureSampleType::Sint, wgpu::TextureSampleType::Uint];
+                    /// The number of components of each color output.
+                    pub const OUTPUT_COMPONENTS: [u32; 3] = [4, 2, 1];
+            "#},
+            4
+        )));
+    }
+
+    #[test]
+    fn write_entry_points_workgroup_size() {
+        let source = indoc! {r#"
+            [[stage(compute), workgroup_size(8, 8)]]
+            fn blurX() {}
+
+            [[stage(compute), workgroup_size(4, 4, 2)]]
+            fn blur_y() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_entry_points(&mut actual, &module, true, &WriterOptions::default());
+
+        assert!(actual.contains(&indent(
+            indoc! {r#"
+                pub mod blur_x {
+                    /// The workgroup size from the `workgroup_size` attribute.
+                    pub const WORKGROUP_SIZE: [u32; 3] = [8, 8, 1];
+                    /// The number of workgroups needed to cover `extent` invocations in each dimension.
+                    pub const fn workgroup_count(extent: [u32; 3]) -> [u32; 3] {
+                        [
+                            extent[0] / WORKGROUP_SIZE[0] + (extent[0] % WORKGROUP_SIZE[0] != 0) as u32,
+                            extent[1] / WORKGROUP_SIZE[1] + (extent[1] % WORKGROUP_SIZE[1] != 0) as u32,
+                            extent[2] / WORKGROUP_SIZE[2] + (extent[2] % WORKGROUP_SIZE[2] != 0) as u32,
+                        ]
+                    }
+                    /// Dispatch enough workgroups to cover `extent` invocations in each dimension.
+                    pub fn dispatch_for(pass: &mut wgpu::ComputePass<'_>, extent: [u32; 3]) {
+                        let [x, y, z] = workgroup_count(extent);
+                        pass.dispatch(x, y, z);
+                    }
+                }
+                /// Generated from the compute entry point `blur_y`.
+                pub mod blur_y {
+                    /// The workgroup size from the `workgroup_size` attribute.
+                    pub const WORKGROUP_SIZE: [u32; 3] = [4, 4, 2];
+                    /// The number of workgroups needed to cover `extent` invocations in each dimension.
+                    pub const fn workgroup_count(extent: [u32; 3]) -> [u32; 3] {
+                        [
+                            extent[0] / WORKGROUP_SIZE[0] + (extent[0] % WORKGROUP_SIZE[0] != 0) as u32,
+                            extent[1] / WORKGROUP_SIZE[1] + (extent[1] % WORKGROUP_SIZE[1] != 0) as u32,
+                            extent[2] / WORKGROUP_SIZE[2] + (extent[2] % WORKGROUP_SIZE[2] != 0) as u32,
+                        ]
+                    }
+                    /// Dispatch enough workgroups to cover `extent` invocations in each dimension.
+                    pub fn dispatch_for(pass: &mut wgpu::ComputePass<'_>, extent: [u32; 3]) {
+                        let [x, y, z] = workgroup_count(extent);
+                        pass.dispatch(x, y, z);
+                    }
+                }
+            "#},
+            4
+        )));
+    }
+
+    #[test]
+    fn write_compute_pipelines_compute() {
+        let source = indoc! {r#"
+            [[stage(vertex)]]
+            fn vs_main() -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+
+            [[stage(compute), workgroup_size(64)]]
+            fn updateParticles() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let mut actual = String::new();
+        write_compute_pipelines(
+            &mut actual,
+            &module,
+            Some("shader.wgsl"),
+            &WriterOptions::default(),
+        );
+
+        assert_eq!(
+            indoc! {r#"
+                pub fn create_update_particles_pipeline(device: &wgpu::Device) -> wgpu::ComputePipeline {
+                    let module = create_shader_module(device);
+                    let layout = create_pipeline_layout(device);
+                    device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                        label: Some("shader.wgsl updateParticles pipeline"),
+                        layout: Some(&layout),
+                        module: &module,
+                        entry_point: ENTRY_UPDATE_PARTICLES,
+                    })
+                }
+            "#},
+            actual
+        );
+    }
+
+    #[test]
+    fn write_entry_points_empty() {
+        let module = naga::front::wgsl::parse_str("").unwrap();
+
+        let mut actual = String::new();
+        write_entry_points(&mut actual, &module, true, &WriterOptions::default());
+
+        assert_eq!("", actual);
+    }
+
+    #[test]
+    fn create_shader_module_consecutive_bind_groups() {
+        let source = indoc! {r#"
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(0), binding(0)]] var<uniform> a: A;
+            [[group(1), binding(0)]] var<uniform> b: A;
+
+            [[stage(vertex)]]
+            fn vs_main() {}
+
+            [[stage(fragment)]]
+            fn fs_main() {}
+        "#};
+
+        create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+    }
+
+    #[test]
+    fn create_shader_module_non_consecutive_bind_groups() {
+        let source = indoc! {r#"
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(0), binding(0)]] var<uniform> a: A;
+            [[group(1), binding(0)]] var<uniform> b: A;
+            [[group(3), binding(0)]] var<uniform> c: A;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let result = create_shader_module(source, "shader.wgsl", WriterOptions::default());
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::NonConsecutiveBindGroups {
+                group: 3,
+                location: Some(SourceLocation {
+                    line: 6,
+                    column: 29
+                }),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn create_shader_module_placeholder_bind_groups() {
+        let source = indoc! {r#"
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(1), binding(0)]] var<uniform> a: A;
+            [[group(3), binding(0)]] var<uniform> b: A;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let options = WriterOptions {
+            placeholder_bind_groups: true,
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(actual.contains("pub empty_bind_group_layout: wgpu::BindGroupLayout,"));
+        // Missing groups use the empty layout to keep the indices of the other groups.
+        let bind_group_layouts = [
+            "&layouts.empty_bind_group_layout,",
+            "&layouts.bind_group_layout1,",
+            "&layouts.empty_bind_group_layout,",
+            "&layouts.bind_group_layout3,",
+        ];
+        assert!(actual.contains(&bind_group_layouts.join("\n            ")));
+        assert!(actual.contains("max_bind_groups: base.max_bind_groups.max(4),"));
+    }
+
+    #[test]
+    fn create_shader_module_scalar_vertex_arguments() {
+        let source = indoc! {r#"
+            [[stage(vertex)]]
+            fn vs_main(
+                [[location(0)]] position: vec3<f32>,
+                [[location(1)]] weight: f32,
+                [[location(2)]] index: u32,
+                [[location(3)]] offset: vec2<i32>
+            ) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(position * weight, 1.0);
+            }
+        "#};
+
+        let actual = create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(actual.contains("pub weight: f32,"));
+        assert!(actual.contains("pub index: u32,"));
+        assert!(actual.contains("pub offset: [i32; 2],"));
+        assert!(actual.contains("format: wgpu::VertexFormat::Float32,"));
+        assert!(actual.contains("format: wgpu::VertexFormat::Uint32,"));
+        assert!(actual.contains("format: wgpu::VertexFormat::Sint32x2,"));
+    }
+
+    #[test]
+    fn create_shader_module_repeated_bindings() {
+        let source = indoc! {r#"
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(0), binding(2)]] var<uniform> a: A;
+            [[group(0), binding(2)]] var<uniform> b: A;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let result = create_shader_module(source, "shader.wgsl", WriterOptions::default());
+        assert_eq!(
+            Err(CreateModuleError::DuplicateBinding {
+                group: 0,
+                binding: 2,
+                name: "b".to_string(),
+                location: Some(SourceLocation {
+                    line: 5,
+                    column: 29
+                }),
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn create_shader_module_embed_spirv() {
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+        "#};
+
+        let options = WriterOptions {
+            embed_spirv: true,
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(actual.contains(indoc! {r#"
+            pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
+                device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+                    label: Some("shader.wgsl"),
+                    source: wgpu::ShaderSource::SpirV(std::borrow::Cow::Borrowed(SHADER_SPIRV))
+                })
+            }
+            const SHADER_SPIRV: &[u32] = &[
+        "#}));
+        // SPIR-V starts with the magic number and version 1.0.
+        assert!(actual.contains("\n    0x07230203, 0x00010000, "));
+        assert!(!actual.contains("include_str!"));
+    }
+
+    #[test]
+    fn create_shader_module_minify_wgsl() {
+        let source = indoc! {r#"
+            // The output color.
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+        "#};
+
+        let options = WriterOptions {
+            minify_wgsl: true,
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(actual.contains(indoc! {r#"
+            pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {
+                device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+                    label: Some("shader.wgsl"),
+                    source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(SHADER_WGSL))
+                })
+            }
+            /// The source of `shader.wgsl` without comments and unnecessary whitespace.
+            const SHADER_WGSL: &str = "[[stage(fragment)]] fn main()-> [[location(0)]] vec4<f32>{return vec4<f32>(1.0);}";
+        "#}));
+        assert!(!actual.contains("include_str!"));
+    }
+
+    #[test]
+    fn create_shader_module_from_path() {
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+        "#};
+
+        let options = WriterOptions {
+            shader_module_from_path: true,
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(actual.contains(indoc! {r#"
+                source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!("shader.wgsl")))
+                })
+            }
+            /// Create the shader module from the WGSL file at `path` instead of the source embedded at build time.
+            /// The generated bindings still match the original shader, so only change code that keeps the same interface.
+            pub fn create_shader_module_from_path(
+                device: &wgpu::Device,
+                path: impl AsRef<std::path::Path>,
+            ) -> std::io::Result<wgpu::ShaderModule> {
+                let source = std::fs::read_to_string(path)?;
+                Ok(device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+                    label: Some("shader.wgsl"),
+                    source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Owned(source))
+                }))
+            }
+        "#}));
+    }
+
+    #[test]
+    fn create_shader_module_from_naga_same_output() {
+        let source = indoc! {r#"
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(0), binding(0)]] var<uniform> a: A;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        // Only the source of the shader module differs.
+        let options = WriterOptions {
+            generate_shader_functions: false,
+            ..Default::default()
+        };
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        assert_eq!(
+            create_shader_module(source, "shader.wgsl", options.clone()).unwrap(),
+            create_shader_module_from_naga(&module, Some(source), "shader.wgsl", options).unwrap()
+        );
+
+        let options = WriterOptions {
+            embed_spirv: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            create_shader_module(source, "shader.wgsl", options.clone()).unwrap(),
+            create_shader_module_from_naga(&module, Some(source), "shader.wgsl", options).unwrap()
+        );
+    }
+
+    #[test]
+    fn create_shader_module_from_naga_modified_module() {
+        let source = indoc! {r#"
+            let SCALE: f32 = 1.5;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(SCALE);
+            }
+        "#};
+
+        let mut module = naga::front::wgsl::parse_str(source).unwrap();
+        for (_, constant) in module.constants.iter_mut() {
+            if let naga::ConstantInner::Scalar { value, .. } = &mut constant.inner {
+                *value = naga::ScalarValue::Float(2.5);
+            }
+        }
+
+        let actual = create_shader_module_from_naga(
+            &module,
+            Some(source),
+            "shader.wgsl",
+            WriterOptions::default(),
+        )
+        .unwrap();
+
+        // The shader module uses the modified module instead of the WGSL file.
+        let info = wgsl::validate(&module).unwrap();
+        let wgsl = wgsl::wgsl(&module, &info).unwrap();
+        assert!(wgsl.contains("2.5"));
+        assert!(actual.contains(&format!("const SHADER_WGSL: &str = {wgsl:?};")));
+        assert!(actual.contains("std::borrow::Cow::Borrowed(SHADER_WGSL)"));
+        assert!(!actual.contains("include_str!"));
+        assert!(actual.contains("pub const SCALE: f32 = 2.5;"));
+    }
+
+    #[test]
+    fn create_shader_module_from_naga_without_source() {
+        let source = indoc! {r#"
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(0), binding(2)]] var<uniform> a: A;
+            [[group(0), binding(2)]] var<uniform> b: A;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let result =
+            create_shader_module_from_naga(&module, None, "shader.wgsl", WriterOptions::default());
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::DuplicateBinding {
+                group: 0,
+                binding: 2,
+                location: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn create_shader_module_bool_member() {
+        let source = indoc! {r#"
+            struct Flags {
+                enabled: bool;
+                mask: vec2<bool>;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let result = create_shader_module(source, "shader.wgsl", WriterOptions::default());
+        match result {
+            Err(CreateModuleError::UnsupportedStructMember {
+                name,
+                member,
+                ty,
+                location,
+            }) => {
+                assert_eq!("Flags", name);
+                assert_eq!("enabled", member);
+                assert_eq!("bool", ty);
+                assert_eq!(1, location.unwrap().line);
+            }
+            _ => panic!("expected an unsupported struct member error"),
+        }
+
+        // Structs without bytemuck derives don't need a host-shareable layout.
+        let options = WriterOptions {
+            derive_bytemuck: false,
+            ..Default::default()
+        };
+        assert!(create_shader_module(source, "shader.wgsl", options).is_ok());
+    }
+
+    #[test]
+    fn write_structs_empty_structs() {
+        let source = indoc! {r#"
+            struct Empty {};
+            struct Light {
+                color: vec4<f32>;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let structs = |empty_structs| {
+            let options = WriterOptions {
+                empty_structs,
+                impl_default: true,
+                ..Default::default()
+            };
+            let mut actual = String::new();
+            write_structs(&mut actual, 0, &module, &options);
+            actual
+        };
+
+        assert!(structs(EmptyStructs::Generate).contains(indoc! {r"
+            #[repr(C)]
+            #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+            pub struct Empty {
+            }
+        "}));
+        assert!(structs(EmptyStructs::Unit).starts_with(indoc! {r"
+            /// Generated from the WGSL struct `Empty`.
+            #[derive(Debug, Copy, Clone, PartialEq, Default)]
+            pub struct Empty;
+            /// Generated from the WGSL struct `Light`.
+            #[repr(C)]
+        "}));
+        assert!(!structs(EmptyStructs::Skip).contains("Empty"));
+    }
+
+    #[test]
+    fn create_shader_module_empty_struct_error() {
+        let source = indoc! {r#"
+            struct Empty {};
+
+            struct Light {
+                color: vec4<f32>;
+            };
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let options = WriterOptions {
+            empty_structs: EmptyStructs::Error,
+            ..Default::default()
+        };
+        match create_shader_module(source, "shader.wgsl", options) {
+            Err(CreateModuleError::EmptyStruct { name, location }) => {
+                assert_eq!("Empty", name);
+                assert_eq!(1, location.unwrap().line);
+            }
+            _ => panic!("expected an empty struct error"),
+        }
+        assert!(create_shader_module(source, "shader.wgsl", WriterOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn create_shader_module_disabled_sections() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+            };
+
+            struct Camera {
+                view_proj: mat4x4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+
+            [[stage(vertex)]]
+            fn vs_main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return camera.view_proj * vec4<f32>(in.position, 1.0);
+            }
+
+            [[stage(fragment)]]
+            fn fs_main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+        "#};
+
+        let options = WriterOptions {
+            generate_bind_groups: false,
+            generate_vertex_module: false,
+            generate_shader_functions: false,
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(actual.contains("pub struct Camera {"));
+        assert!(actual.contains("pub struct CameraBuffer("));
+        assert!(actual.contains("pub mod fs_main {"));
+        assert!(!actual.contains("pub mod vs_main {"));
+        assert!(!actual.contains("pub mod bind_groups {"));
+        assert!(!actual.contains("pub mod vertex {"));
+        assert!(!actual.contains("pub fn create_shader_module("));
+        assert!(!actual.contains("pub fn create_pipeline_layout("));
+
+        // The sections that use the structs are disabled along with the structs.
+        let options = WriterOptions {
+            generate_structs: false,
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(!actual.contains("pub struct Camera {"));
+        assert!(!actual.contains("pub struct CameraBuffer("));
+        assert!(!actual.contains("pub fn from_data("));
+        assert!(!actual.contains("pub mod vertex {"));
+        assert!(actual.contains("pub mod bind_groups {"));
+        assert!(actual.contains("pub fn create_pipeline_layout("));
+    }
+
+    #[test]
+    fn create_shader_module_crate_paths() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: mat4x4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return uniforms.a[0];
+            }
+        "#};
+
+        let options = WriterOptions {
+            matrix_vector_types: MatrixVectorTypes::Glam,
+            wgpu_path: "crate::gpu::wgpu".to_string(),
+            bytemuck_path: "crate::bytemuck".to_string(),
+            glam_path: "vendored_glam".to_string(),
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+
+        assert!(actual.contains(indoc! {r#"
+            #[derive(Debug, Copy, Clone, PartialEq, crate::bytemuck::Pod, crate::bytemuck::Zeroable)]
+            #[bytemuck(crate = "crate::bytemuck")]
+            pub struct Uniforms {
+        "#}));
+        assert!(actual.contains("pub a: vendored_glam::Mat4,"));
+        assert!(actual.contains("device: &crate::gpu::wgpu::Device"));
+        assert!(actual.contains("crate::gpu::wgpu::ShaderStages::FRAGMENT"));
+
+        // Every path should use the configured crate paths.
+        let remaining = actual
+            .replace("crate::gpu::wgpu::", "")
+            .replace("crate::bytemuck::", "")
+            .replace("vendored_glam::", "");
+        assert!(!remaining.contains("wgpu::"));
+        assert!(!remaining.contains("bytemuck::"));
+        assert!(!remaining.contains("glam::"));
+    }
+
+    #[test]
+    fn create_shader_module_default_bytemuck_path() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return uniforms.a;
+            }
+        "#};
+
+        let actual = create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(actual.contains("PartialEq, bytemuck::Pod, bytemuck::Zeroable)]"));
+        assert!(!actual.contains("#[bytemuck(crate"));
+    }
+
+    #[test]
+    fn create_shader_module_visibility() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return uniforms.a;
+            }
+        "#};
+
+        let options = WriterOptions {
+            visibility: "pub(crate)".to_string(),
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+
+        assert!(actual.contains("\npub(crate) struct Uniforms {\n    pub a: [f32; 4],"));
+        assert!(actual.contains("\npub(crate) mod bind_groups {\n"));
+        assert!(actual.contains("\n    pub struct BindGroup0("));
+        assert!(actual.contains("\npub(crate) fn create_shader_module("));
+        assert!(actual.contains("\npub(crate) fn create_pipeline_layout("));
+        assert!(!actual.lines().any(|l| l.starts_with("pub ")));
+    }
+
+    #[test]
+    fn create_shader_module_module_name() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return uniforms.a;
+            }
+        "#};
+
+        let options = WriterOptions {
+            visibility: "pub(crate)".to_string(),
+            module_name: Some("pbr".to_string()),
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+
+        // Only the wrapping module uses the configured visibility.
+        assert!(actual.starts_with("pub(crate) mod pbr {\n"));
+        assert!(actual.contains("\n    pub struct Uniforms {\n        pub a: [f32; 4],"));
+        assert!(actual.contains("\n    pub mod bind_groups {\n"));
+        assert!(actual.contains("\n    pub fn create_shader_module("));
+        assert!(actual.ends_with("\n}\n"));
+        assert!(actual
+            .lines()
+            .skip(1)
+            .all(|l| l.starts_with(' ') || l.is_empty() || l == "}"));
+
+        // Module names are escaped like other identifiers.
+        let options = WriterOptions {
+            module_name: Some("type".to_string()),
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(actual.starts_with("pub mod r#type {\n"));
+    }
+
+    #[test]
+    fn create_shader_module_no_trailing_whitespace() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+            [[group(0), binding(1)]]
+            var color_texture: texture_2d<f32>;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return uniforms.a;
+            }
+        "#};
+
+        for module_name in [None, Some("shader".to_string())] {
+            let options = WriterOptions {
+                module_name,
+                ..Default::default()
+            };
+            let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+            assert!(actual.lines().all(|l| l == l.trim_end()));
+        }
+    }
+
+    #[test]
+    fn create_shader_module_inferred_non_filtering_samplers() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var data_texture: texture_2d<f32>;
+            [[group(0), binding(1)]]
+            var data_sampler: sampler;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureSampleLevel(data_texture, data_sampler, vec2<f32>(0.0), 0.0);
+            }
+        "#};
+
+        let actual = create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(
+            actual.contains("ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),")
+        );
+
+        // The sampler doesn't need to be configured for non filterable textures.
+        let options = WriterOptions {
+            non_filterable_textures: vec!["data_texture".to_string()],
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(
+            actual.contains("sample_type: wgpu::TextureSampleType::Float { filterable: false },")
+        );
+        assert!(actual
+            .contains("ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::NonFiltering),"));
+    }
+
+    #[test]
+    fn create_shader_module_bind_group_consts() {
+        let source = indoc! {r#"
+            struct Transforms {
+                model: mat4x4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var colorTexture: texture_2d<f32>;
+            [[group(0), binding(2)]]
+            var<uniform> transforms: Transforms;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return transforms.model * textureLoad(colorTexture, vec2<i32>(0), 0);
+            }
+        "#};
+
+        let actual = create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(actual.contains(&indent(
+            indoc! {r"
+                impl BindGroup0 {
+                    /// The index of the group in the shader.
+                    pub const GROUP_INDEX: u32 = 0;
+                    /// The binding index for `var colorTexture: texture_2d<f32>`.
+                    pub const COLOR_TEXTURE_BINDING: u32 = 0;
+                    /// The binding index for `var<uniform> transforms: Transforms`.
+                    pub const TRANSFORMS_BINDING: u32 = 2;
+
+                    /// Create a new layout for the bindings in this group.
+                    pub fn get_bind_group_layout("},
+            4
+        )));
+    }
+
+    #[test]
+    fn create_shader_module_from_bindings_with_layout() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(color_texture, vec2<i32>(0), 0);
+            }
+        "#};
+
+        let actual = create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(actual.contains(&indent(
+            indoc! {r"
+                pub fn from_bindings(device: &wgpu::Device, bindings: BindGroupLayout0) -> Self {
+                    let bind_group_layout = Self::get_bind_group_layout(device);
+                    Self::from_bindings_with_layout(device, &bind_group_layout, bindings)
+                }
+            "},
+            8
+        )));
+        assert!(actual.contains(&indent(
+            indoc! {r"
+                /// Create the bind group with an existing layout like one from [BindGroupLayouts].
+                pub fn from_bindings_with_layout(
+                    device: &wgpu::Device,
+                    bind_group_layout: &wgpu::BindGroupLayout,
+                    bindings: BindGroupLayout0,
+                ) -> Self {
+                    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        layout: bind_group_layout,
+            "},
+            8
+        )));
+    }
+
+    #[test]
+    fn write_bind_group_from_data_texture_buffers() {
+        let source = indoc! {r#"
+            struct Transforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(0), binding(1)]]
+            var<uniform> transforms: Transforms;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return transforms.a + textureLoad(color_texture, vec2<i32>(0), 0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        write_bind_group_from_data(
+            &mut actual,
+            0,
+            &module,
+            0,
+            &bind_group_data[&0],
+            &WriterOptions::default(),
+        );
+
+        assert_eq!(
+            indoc! {
+                r"
+                pub struct BindGroupData0<'a> {
+                    pub color_texture: &'a wgpu::TextureView,
+                    pub transforms: &'a super::Transforms,
+                }
+                /// The buffers created by [BindGroup0::from_data].
+                pub struct BindGroupBuffers0 {
+                    pub transforms: super::TransformsBuffer,
+                }
+                impl BindGroup0 {
+                    /// Create the bind group and new buffers initialized with the data for each buffer binding.
+                    /// The returned buffers can be used to update the data later.
+                    pub fn from_data(device: &wgpu::Device, data: BindGroupData0) -> (Self, BindGroupBuffers0) {
+                        let buffers = BindGroupBuffers0 {
+                            transforms: super::TransformsBuffer::new(device, data.transforms),
+                        };
+                        let bind_group = Self::from_bindings(
+                            device,
+                            BindGroupLayout0 {
+                                color_texture: data.color_texture,
+                                transforms: buffers.transforms.as_binding(),
+                            },
+                        );
+                        (bind_group, buffers)
+                    }
+                }
+                "
+            },
+            actual
+        );
+    }
+
+    #[test]
+    fn write_bind_group_from_data_unsupported_buffers() {
+        let source = indoc! {r#"
+            struct Transforms {
+                a: vec4<f32>;
+            };
+
+            struct Values {
+                values: array<u32>;
+            };
+
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(1), binding(0)]]
+            var<uniform> transforms: Transforms;
+            [[group(1), binding(1)]]
+            var<storage, read> values: Values;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                let value = f32(values.values[0]);
+                return transforms.a + textureLoad(color_texture, vec2<i32>(0), 0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        // Groups without buffers or with buffers that don't have a generated buffer type are skipped.
+        let mut actual = String::new();
+        for (group_no, group) in &bind_group_data {
+            write_bind_group_from_data(
+                &mut actual,
+                0,
+                &module,
+                *group_no,
+                group,
+                &WriterOptions::default(),
+            );
+        }
+        assert_eq!("", actual);
+    }
+
+    #[test]
+    fn create_shader_module_required_features() {
+        let source = indoc! {r#"
+            struct PushConstants {
+                color: vec4<f32>;
+            };
+            var<push_constant> constants: PushConstants;
+
+            [[stage(fragment)]]
+            fn main([[builtin(primitive_index)]] index: u32) -> [[location(0)]] vec4<f32> {
+                return constants.color;
+            }
+        "#};
+
+        let actual = create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(actual.contains(indoc! {r"
+            /// The device features required by the shader such as [wgpu::Features::PUSH_CONSTANTS] for push constants.
+            pub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::PUSH_CONSTANTS.union(wgpu::Features::SHADER_PRIMITIVE_INDEX);
+        "}));
+    }
+
+    #[test]
+    fn create_shader_module_no_required_features() {
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+
+        let actual = create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(actual.contains(
+            "\npub const REQUIRED_FEATURES: wgpu::Features = wgpu::Features::empty();\n"
+        ));
+    }
+
+    #[cfg(feature = "tokens")]
+    #[test]
+    fn create_shader_module_tokens_unterminated_string() {
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+
+        // The path is only checked once the text is parsed.
+        let options = WriterOptions {
+            wgpu_path: "wgpu\"".to_string(),
+            ..Default::default()
+        };
+        assert!(create_shader_module(source, "shader.wgsl", options.clone()).is_ok());
+        let error = create_shader_module_tokens(source, "shader.wgsl", options).unwrap_err();
+        assert!(matches!(error, CreateModuleError::InvalidTokens { .. }));
+        assert!(error
+            .to_string()
+            .starts_with("failed to parse generated code: "));
+    }
+
+    #[cfg(feature = "tokens")]
+    #[test]
+    fn create_shader_module_tokens_invalid_visibility() {
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+
+        let options = WriterOptions {
+            visibility: "pub(".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            create_shader_module_tokens(source, "shader.wgsl", options),
+            Err(CreateModuleError::InvalidTokens { .. })
+        ));
+    }
 
-            struct MatricesF32 {
-                a: mat4x4<f32>;
-            };
-            
-            struct StaticArrays {
-                a: array<u32, 5>;
-                b: array<f32, 3>;
-                c: array<mat4x4<f32>, 512>;
+    #[test]
+    fn write_device_requirements_limits() {
+        let source = indoc! {r#"
+            struct Transforms {
+                a: vec4<f32>;
             };
 
+            [[group(0), binding(0)]]
+            var<uniform> transforms: Transforms;
+
             [[stage(fragment)]]
-            fn main() {}
+            fn main() -> [[location(0)]] vec4<f32> {
+                return transforms.a;
+            }
         "#};
 
         let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
 
+        let options = WriterOptions {
+            dynamic_offset_bindings: vec!["transforms".to_string()],
+            ..Default::default()
+        };
         let mut actual = String::new();
-        write_structs(&mut actual, 0, &module);
+        write_device_requirements(&mut actual, &module, &bind_group_data, &options);
 
-        assert_eq!(
-            indoc! {
-                r"
-                #[repr(C)]
-                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
-                pub struct VectorsF32 {
-                    pub a: [f32; 2],
-                    pub b: [f32; 3],
-                    pub c: [f32; 4],
+        assert!(actual.contains(indoc! {r"
+            /// Raise the limits in `base` to at least the limits required by the shader.
+            pub fn required_limits(base: wgpu::Limits) -> wgpu::Limits {
+                wgpu::Limits {
+                    max_bind_groups: base.max_bind_groups.max(1),
+                    max_uniform_buffers_per_shader_stage: base.max_uniform_buffers_per_shader_stage.max(1),
+                    max_uniform_buffer_binding_size: base.max_uniform_buffer_binding_size.max(16),
+                    max_dynamic_uniform_buffers_per_pipeline_layout: base.max_dynamic_uniform_buffers_per_pipeline_layout.max(1),
+                    ..base
                 }
-                #[repr(C)]
-                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
-                pub struct VectorsU32 {
-                    pub a: [u32; 2],
-                    pub b: [u32; 3],
-                    pub c: [u32; 4],
+            }
+        "}));
+        assert!(actual.ends_with(indoc! {r#"
+            /// Check that the device supports the features and limits required by the shader.
+            pub fn verify_device(device: &wgpu::Device) -> Result<(), VerifyDeviceError> {
+                let missing_features = REQUIRED_FEATURES.difference(device.features());
+                if !missing_features.is_empty() {
+                    return Err(VerifyDeviceError::MissingFeatures(missing_features));
                 }
-                #[repr(C)]
-                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
-                pub struct MatricesF32 {
-                    pub a: glam::Mat4,
+
+                let limits = device.limits();
+                for (name, required, supported) in [
+                    ("max_bind_groups", 1, limits.max_bind_groups),
+                    ("max_uniform_buffers_per_shader_stage", 1, limits.max_uniform_buffers_per_shader_stage),
+                    ("max_uniform_buffer_binding_size", 16, limits.max_uniform_buffer_binding_size),
+                    ("max_dynamic_uniform_buffers_per_pipeline_layout", 1, limits.max_dynamic_uniform_buffers_per_pipeline_layout),
+                ] {
+                    if supported < required {
+                        return Err(VerifyDeviceError::InsufficientLimit {
+                            name,
+                            required,
+                            supported,
+                        });
+                    }
                 }
-                #[repr(C)]
-                #[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
-                pub struct StaticArrays {
-                    pub a: [u32; 5],
-                    pub b: [f32; 3],
-                    pub c: [glam::Mat4; 512],
+                Ok(())
+            }
+        "#}));
+    }
+
+    #[test]
+    fn write_device_requirements_no_limits() {
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let mut actual = String::new();
+        write_device_requirements(
+            &mut actual,
+            &module,
+            &bind_group_data,
+            &WriterOptions::default(),
+        );
+
+        // Avoid struct update syntax without any fields.
+        assert!(actual.contains(indoc! {r"
+            pub fn required_limits(base: wgpu::Limits) -> wgpu::Limits {
+                base
+            }
+        "}));
+        assert!(actual.ends_with(indoc! {r"
+                if !missing_features.is_empty() {
+                    return Err(VerifyDeviceError::MissingFeatures(missing_features));
                 }
-                "
-            },
+                Ok(())
+            }
+        "}));
+    }
+
+    #[test]
+    fn create_shader_module_files_split() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+            };
+
+            struct Camera {
+                view_proj: mat4x4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+
+            [[stage(vertex)]]
+            fn vs_main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return camera.view_proj * vec4<f32>(in.position, 1.0);
+            }
+        "#};
+
+        let options = WriterOptions {
+            visibility: "pub(crate)".to_string(),
+            module_name: Some("unused".to_string()),
+            ..Default::default()
+        };
+        let files = create_shader_module_files(source, "../shader.wgsl", options).unwrap();
+        assert_eq!(
+            vec!["bind_groups.rs", "mod.rs", "structs.rs", "vertex.rs"],
+            files.keys().collect::<Vec<_>>()
+        );
+
+        let mod_rs = &files["mod.rs"];
+        assert!(mod_rs.starts_with(indoc! {r"
+            mod structs;
+            pub(crate) use structs::*;
+            pub(crate) mod bind_groups;
+            pub(crate) mod vertex;
+        "}));
+        assert!(mod_rs.contains("\npub(crate) fn create_shader_module("));
+        assert!(mod_rs.contains(r#"include_str!("../shader.wgsl")"#));
+        assert!(!mod_rs.contains("struct Camera"));
+        assert!(!mod_rs.contains("mod unused"));
+
+        // Module files contain the items without the module or its indentation.
+        assert!(files["structs.rs"].contains("\npub struct Camera {\n    pub view_proj:"));
+        assert!(files["bind_groups.rs"].contains("\npub struct BindGroup0(wgpu::BindGroup);\n"));
+        assert!(files["bind_groups.rs"].ends_with("\n}\n"));
+        assert!(!files["bind_groups.rs"].contains("mod bind_groups"));
+        assert!(files["vertex.rs"].starts_with("impl super::VertexInput {\n"));
+
+        // Sections without any items don't have a file.
+        let options = WriterOptions {
+            generate_bind_groups: false,
+            generate_vertex_module: false,
+            ..Default::default()
+        };
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+        let files = create_shader_module_files(source, "shader.wgsl", options).unwrap();
+        assert_eq!(vec!["mod.rs"], files.keys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn create_shader_module_files_empty_vertex_module() {
+        // The vertex module is enabled but there aren't any vertex inputs.
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+        let files =
+            create_shader_module_files(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(!files.contains_key("vertex.rs"));
+        assert!(!files["mod.rs"].contains("mod vertex;"));
+    }
+
+    #[test]
+    fn module_file_contents_doc_comments() {
+        let text = indoc! {"
+            /// The module.
+            pub(crate) mod name {
+                /// The item.
+                pub struct A {
+                    pub a: f32,
+                }
+            }
+        "};
+        assert_eq!(
+            indoc! {"
+                /// The item.
+                pub struct A {
+                    pub a: f32,
+                }
+            "},
+            module_file_contents(text)
+        );
+        assert_eq!("", module_file_contents("pub mod vertex {\n}\n"));
+    }
+
+    #[test]
+    fn create_shader_modules_two_shaders() {
+        let blit = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+        let model = indoc! {r#"
+            [[stage(vertex)]]
+            fn main() -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+
+        let options = WriterOptions {
+            visibility: "pub(crate)".to_string(),
+            ..Default::default()
+        };
+        let actual = create_shader_modules(
+            &[
+                ("shaders/blit.wgsl", blit),
+                ("shaders/model-pass.wgsl", model),
+            ],
+            options,
+        )
+        .unwrap();
+
+        // Only the modules use the configured visibility.
+        assert!(actual.starts_with("pub(crate) mod blit {\n"));
+        assert!(actual.contains("\n}\npub(crate) mod model_pass {\n"));
+        assert!(actual.contains(
+            "\n    pub fn create_shader_module(device: &wgpu::Device) -> wgpu::ShaderModule {\n"
+        ));
+        assert!(actual.contains(r#"include_str!("shaders/model-pass.wgsl")"#));
+        assert!(actual.ends_with("\n}\n"));
+    }
+
+    #[test]
+    fn create_shader_modules_shared_bind_groups() {
+        let model = indoc! {r#"
+            struct Camera {
+                view_proj: mat4x4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+
+            [[group(1), binding(0)]]
+            var color_texture: texture_2d<f32>;
+
+            [[stage(vertex)]]
+            fn vs_main() -> [[builtin(position)]] vec4<f32> {
+                return camera.view_proj * vec4<f32>(0.0);
+            }
+
+            [[stage(fragment)]]
+            fn fs_main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(color_texture, vec2<i32>(0), 0);
+            }
+        "#};
+        let shadow = indoc! {r#"
+            struct Camera {
+                view_proj: mat4x4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+
+            [[group(1), binding(0)]]
+            var<uniform> light: Camera;
+
+            [[stage(vertex)]]
+            fn vs_main() -> [[builtin(position)]] vec4<f32> {
+                return camera.view_proj * light.view_proj * vec4<f32>(0.0);
+            }
+        "#};
+        let shaders = [("model.wgsl", model), ("shadow.wgsl", shadow)];
+
+        let options = WriterOptions {
+            shared_bind_groups: true,
+            ..Default::default()
+        };
+        let actual = create_shader_modules(&shaders, options.clone()).unwrap();
+
+        // Only group 0 is the same in both shaders.
+        assert!(actual.starts_with(indoc! {r"
+            pub mod layouts {
+                /// Types like render or compute passes that can set bind groups.
+        "}));
+        assert!(actual.contains(
+            &[
+                "    /// Bind group 0 shared by the `model`, `shadow` shaders.",
+                "    pub mod model_group0 {",
+                "        use super::SetBindGroup;",
+                "        /// The bind group for group 0 in the shaders.",
+                "        pub struct BindGroup0(wgpu::BindGroup);",
+            ]
+            .join("\n")
+        ));
+        assert_eq!(1, actual.matches("pub struct BindGroup0(").count());
+        assert_eq!(
+            2,
+            actual
+                .matches(
+                    "        pub use super::super::layouts::model_group0::{BindGroup0, BindGroupLayout0};\n"
+                )
+                .count()
+        );
+        assert_eq!(
+            2,
             actual
+                .matches("        pub use super::super::layouts::SetBindGroup;\n")
+                .count()
+        );
+        assert_eq!(2, actual.matches("pub struct BindGroup1(").count());
+
+        // Shaders without identical groups don't need a layouts module.
+        let actual = create_shader_modules(&shaders[..1], options).unwrap();
+        assert!(actual.starts_with("pub mod model {\n"));
+        assert!(!actual.contains("mod layouts"));
+        assert!(!actual.contains("super::super::layouts"));
+    }
+
+    #[test]
+    fn create_shader_modules_shared_bind_groups_crate_paths() {
+        let source = indoc! {r#"
+            struct Camera {
+                view_proj: mat4x4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+
+            [[stage(vertex)]]
+            fn vs_main() -> [[builtin(position)]] vec4<f32> {
+                return camera.view_proj * vec4<f32>(0.0);
+            }
+        "#};
+        let shaders = [("model.wgsl", source), ("shadow.wgsl", source)];
+
+        let options = WriterOptions {
+            shared_bind_groups: true,
+            wgpu_path: "crate::gpu::wgpu".to_string(),
+            bytemuck_path: "crate::gpu::bytemuck".to_string(),
+            ..Default::default()
+        };
+        let actual = create_shader_modules(&shaders, options).unwrap();
+
+        // The layouts module uses the same paths as the shader modules.
+        let layouts = &actual[..actual.find("pub mod model {").unwrap()];
+        assert!(layouts.contains("pub struct BindGroup0(crate::gpu::wgpu::BindGroup);"));
+        assert!(
+            layouts.contains("impl<'a> SetBindGroup<'a> for crate::gpu::wgpu::RenderPass<'a> {")
+        );
+
+        let remaining = actual
+            .replace("crate::gpu::wgpu::", "")
+            .replace("crate::gpu::bytemuck::", "");
+        assert!(!remaining.contains("wgpu::"));
+        assert!(!remaining.contains("bytemuck::"));
+    }
+
+    #[test]
+    fn create_shader_modules_error_path() {
+        let valid = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+
+        let result = create_shader_modules(
+            &[("blit.wgsl", valid), ("model.wgsl", "fn main(")],
+            WriterOptions::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::Shader { ref path, ref error })
+                if path == "model.wgsl" && matches!(**error, CreateModuleError::ParseError { .. })
+        ));
+        assert!(result.unwrap_err().to_string().starts_with("model.wgsl: "));
+    }
+
+    #[test]
+    fn create_shader_modules_duplicate_name() {
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+        "#};
+
+        let result = create_shader_modules(
+            &[("a/blit.wgsl", source), ("b/blit.wgsl", source)],
+            WriterOptions::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::DuplicateModuleName { ref name, ref path })
+                if name == "blit" && path == "b/blit.wgsl"
+        ));
+    }
+
+    fn name_collision(source: &str, options: WriterOptions) -> (String, String, String) {
+        match create_shader_module(source, "shader.wgsl", options) {
+            Err(CreateModuleError::NameCollision {
+                name,
+                first,
+                second,
+            }) => (name, first, second),
+            result => panic!("expected a name collision but found {result:?}"),
+        }
+    }
+
+    #[test]
+    fn create_shader_module_entry_point_collision() {
+        let source = indoc! {r#"
+            [[stage(fragment)]]
+            fn fs_main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(0.0);
+            }
+
+            [[stage(fragment)]]
+            fn fsMain() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+        "#};
+
+        assert_eq!(
+            (
+                "fs_main".to_string(),
+                "entry point `fsMain`".to_string(),
+                "entry point `fs_main`".to_string()
+            ),
+            name_collision(source, WriterOptions::default())
+        );
+    }
+
+    #[test]
+    fn create_shader_module_entry_points_named_like_modules() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn vertex(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position, 1.0);
+            }
+
+            [[stage(fragment)]]
+            fn fragment() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+
+            [[stage(compute), workgroup_size(64)]]
+            fn bind_groups() {}
+        "#};
+
+        let actual = create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap();
+        assert!(actual.contains("pub mod vertex {\n"));
+        assert!(actual.contains("pub mod entry_points {\n"));
+        for name in ["vertex", "fragment", "bind_groups"] {
+            assert!(actual.contains(&format!("\n    pub mod {name} {{\n")));
+        }
+        assert!(actual.contains(
+            "        super::super::VertexInput::vertex_buffer_layout(super::super::VertexInput::STEP_MODE),"
+        ));
+    }
+
+    #[test]
+    fn create_shader_module_generated_name_collisions() {
+        let source = indoc! {r#"
+            struct Camera {
+                position: vec4<f32>;
+            };
+            struct CameraBuffer {
+                position: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+            [[group(0), binding(1)]]
+            var<uniform> camera_buffer: CameraBuffer;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return camera.position + camera_buffer.position;
+            }
+        "#};
+
+        // Buffer types aren't generated for encase structs.
+        let options = WriterOptions {
+            derive_encase: true,
+            ..Default::default()
+        };
+        assert!(create_shader_module(source, "shader.wgsl", options).is_ok());
+
+        let (name, first, second) = name_collision(source, WriterOptions::default());
+        assert_eq!("CameraBuffer", name);
+        assert_eq!(
+            "the buffer type for struct `Camera` and struct `CameraBuffer` both generate the Rust name `CameraBuffer`",
+            CreateModuleError::NameCollision { name, first, second }.to_string()
+        );
+
+        let source = indoc! {r#"
+            let verify_device: f32 = 1.0;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return vec4<f32>(verify_device);
+            }
+        "#};
+        assert_eq!(
+            (
+                "verify_device".to_string(),
+                "generated item `verify_device`".to_string(),
+                "constant `verify_device`".to_string()
+            ),
+            name_collision(source, WriterOptions::default())
         );
     }
 
     #[test]
-    fn bind_group_layouts_descriptors_compute() {
-        // The actual content of the structs doesn't matter.
-        // We only care about the groups and bindings.
+    fn create_shader_module_padding_field_collision() {
         let source = indoc! {r#"
-            struct VertexInput0 {};
-            struct VertexWeight {};
-            struct Vertices {};
-            struct VertexWeights {};
-            struct Transforms {};
-
-            [[group(0), binding(0)]] var<storage, read> src : Vertices;
-            [[group(0), binding(1)]] var<storage, read> vertex_weights : VertexWeights;
-            [[group(0), binding(2)]] var<storage, read_write> dst : Vertices;
+            struct Uniforms {
+                a: f32;
+                b: vec4<f32>;
+                _padding0: f32;
+            };
 
-            [[group(1), binding(0)]] var<uniform> transforms: Transforms;
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
 
-            [[stage(compute)]]
-            fn main() {}
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return uniforms.b * uniforms.a * uniforms._padding0;
+            }
         "#};
 
-        let module = naga::front::wgsl::parse_str(source).unwrap();
-        let bind_group_data = wgsl::get_bind_group_data(&module).unwrap();
-
-        let mut actual = String::new();
-        for (group_no, group) in bind_group_data {
-            write_bind_group_layout(&mut actual, 0, group_no, &group);
-            write_bind_group_layout_descriptor(
-                &mut actual,
-                0,
-                group_no,
-                &group,
-                wgpu::ShaderStages::COMPUTE,
-            );
-        }
-
         assert_eq!(
-            indoc! {
-                r"
-                pub struct BindGroupLayout0<'a> {
-                    pub src: wgpu::BufferBinding<'a>,
-                    pub vertex_weights: wgpu::BufferBinding<'a>,
-                    pub dst: wgpu::BufferBinding<'a>,
-                }
-                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
-                    label: None,
-                    entries: &[
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0u32,
-                            visibility: wgpu::ShaderStages::COMPUTE,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Storage { read_only: true },
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 1u32,
-                            visibility: wgpu::ShaderStages::COMPUTE,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Storage { read_only: true },
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 2u32,
-                            visibility: wgpu::ShaderStages::COMPUTE,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Storage { read_only: false },
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                    ]
-                };
-                pub struct BindGroupLayout1<'a> {
-                    pub transforms: wgpu::BufferBinding<'a>,
-                }
-                const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
-                    label: None,
-                    entries: &[
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0u32,
-                            visibility: wgpu::ShaderStages::COMPUTE,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                    ]
-                };
-                "
-            },
-            actual
+            (
+                "_padding0".to_string(),
+                "field `_padding0` of struct `Uniforms`".to_string(),
+                "field `_padding0` of struct `Uniforms`".to_string()
+            ),
+            name_collision(source, WriterOptions::default())
         );
     }
 
     #[test]
-    fn bind_group_layouts_descriptors_vertex_fragment() {
-        // The actual content of the structs doesn't matter.
-        // We only care about the groups and bindings.
-        // Test different texture and sampler types.
+    fn create_shader_module_binding_const_collision() {
         let source = indoc! {r#"
-            struct Transforms {};
-
             [[group(0), binding(0)]]
             var color_texture: texture_2d<f32>;
             [[group(0), binding(1)]]
-            var color_sampler: sampler;
-            [[group(0), binding(2)]]
-            var depth_texture: texture_depth_2d;
-            [[group(0), binding(3)]]
-            var comparison_sampler: sampler_comparison;
-
-            [[group(1), binding(0)]] var<uniform> transforms: Transforms;
-
-            [[stage(vertex)]]
-            fn vs_main() {}
+            var colorTexture: texture_2d<f32>;
 
             [[stage(fragment)]]
-            fn fs_main() {}
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(color_texture, vec2<i32>(0), 0) + textureLoad(colorTexture, vec2<i32>(0), 0);
+            }
         "#};
 
-        let module = naga::front::wgsl::parse_str(source).unwrap();
-        let bind_group_data = wgsl::get_bind_group_data(&module).unwrap();
-
-        let mut actual = String::new();
-        for (group_no, group) in bind_group_data {
-            write_bind_group_layout(&mut actual, 0, group_no, &group);
-            write_bind_group_layout_descriptor(
-                &mut actual,
-                0,
-                group_no,
-                &group,
-                wgpu::ShaderStages::VERTEX_FRAGMENT,
-            );
-        }
-
-        // TODO: Are storage buffers valid for vertex/fragment?
         assert_eq!(
-            indoc! {
-                r"
-                pub struct BindGroupLayout0<'a> {
-                    pub color_texture: &'a wgpu::TextureView,
-                    pub color_sampler: &'a wgpu::Sampler,
-                    pub depth_texture: &'a wgpu::TextureView,
-                    pub comparison_sampler: &'a wgpu::Sampler,
-                }
-                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
-                    label: None,
-                    entries: &[
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0u32,
-                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
-                            ty: wgpu::BindingType::Texture {
-                                multisampled: false,
-                                view_dimension: wgpu::TextureViewDimension::D2,
-                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                            },
-                            count: None,
-                        },
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 1u32,
-                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
-                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                            count: None,
-                        },
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 2u32,
-                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
-                            ty: wgpu::BindingType::Texture {
-                                multisampled: false,
-                                view_dimension: wgpu::TextureViewDimension::D2,
-                                sample_type: wgpu::TextureSampleType::Depth,
-                            },
-                            count: None,
-                        },
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 3u32,
-                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
-                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
-                            count: None,
-                        },
-                    ]
-                };
-                pub struct BindGroupLayout1<'a> {
-                    pub transforms: wgpu::BufferBinding<'a>,
-                }
-                const LAYOUT_DESCRIPTOR1: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
-                    label: None,
-                    entries: &[
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0u32,
-                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                    ]
-                };
-                "
-            },
-            actual
+            (
+                "COLOR_TEXTURE_BINDING".to_string(),
+                "binding `color_texture` in group 0".to_string(),
+                "binding `colorTexture` in group 0".to_string()
+            ),
+            name_collision(source, WriterOptions::default())
         );
     }
 
     #[test]
-    fn bind_group_layouts_descriptors_vertex() {
-        // The actual content of the structs doesn't matter.
-        // We only care about the groups and bindings.
+    fn shader_module_names() {
+        assert_eq!("blit", shader_module_name("shaders/blit.wgsl"));
+        assert_eq!("blit_pass", shader_module_name("blit-pass.wgsl"));
+        assert_eq!("blit_pass", shader_module_name("BlitPass.wgsl"));
+        assert_eq!("r#type", shader_module_name("type.wgsl"));
+        assert_eq!("shader_self", shader_module_name("self.wgsl"));
+        assert_eq!("shader_2d", shader_module_name("2d.wgsl"));
+    }
+
+    #[test]
+    fn rust_ident_keywords() {
+        assert_eq!("color", rust_ident("color"));
+        assert_eq!("r#ref", rust_ident("ref"));
+        assert_eq!("r#in", rust_ident("in"));
+        assert_eq!("self_", rust_ident("self"));
+        assert_eq!("Self_", rust_ident("Self"));
+    }
+
+    #[test]
+    fn create_shader_module_keyword_names() {
         let source = indoc! {r#"
-            struct Transforms {};
+            struct match {
+                ref: vec4<f32>;
+            };
 
-            [[group(0), binding(0)]] var<uniform> transforms: Transforms;
+            [[group(0), binding(0)]]
+            var<uniform> mod: match;
 
             [[stage(vertex)]]
-            fn vs_main() {}
+            fn move([[location(0)]] in: vec4<f32>) -> [[builtin(position)]] vec4<f32> {
+                return in * mod.ref;
+            }
         "#};
 
-        let module = naga::front::wgsl::parse_str(source).unwrap();
-        let bind_group_data = wgsl::get_bind_group_data(&module).unwrap();
+        let options = WriterOptions {
+            vertex_format_overrides: [("in".to_string(), wgpu::VertexFormat::Unorm8x4)].into(),
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shader.wgsl", options).unwrap();
+        assert!(actual.contains("pub struct r#match {\n    pub r#ref: [f32; 4],\n}"));
+        assert!(actual.contains(
+            "pub struct MoveInput {\n    pub r#in: [u8; 4],\n    pub _padding0: [u8; 12],\n}"
+        ));
+        assert!(actual.contains("pub r#mod: wgpu::BufferBinding<'a>,"));
+        assert!(actual.contains("wgpu::BindingResource::Buffer(bindings.r#mod)"));
+        assert!(actual.contains("pub mod r#move {"));
+    }
 
-        let mut actual = String::new();
-        for (group_no, group) in bind_group_data {
-            write_bind_group_layout(&mut actual, 0, group_no, &group);
-            write_bind_group_layout_descriptor(
-                &mut actual,
-                0,
-                group_no,
-                &group,
-                wgpu::ShaderStages::VERTEX,
-            );
-        }
+    #[test]
+    fn create_shader_module_labels() {
+        let source = indoc! {r#"
+            struct Uniforms {
+                a: vec4<f32>;
+            };
 
-        assert_eq!(
-            indoc! {
-                r"
-                pub struct BindGroupLayout0<'a> {
-                    pub transforms: wgpu::BufferBinding<'a>,
-                }
-                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
-                    label: None,
-                    entries: &[
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0u32,
-                            visibility: wgpu::ShaderStages::VERTEX,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                    ]
-                };
-                "
-            },
-            actual
+            [[group(0), binding(0)]]
+            var<uniform> uniforms: Uniforms;
+            [[group(0), binding(1)]]
+            var color_texture: texture_2d<f32>;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return uniforms.a;
+            }
+        "#};
+
+        let actual =
+            create_shader_module(source, "shaders/model.wgsl", WriterOptions::default()).unwrap();
+        assert!(actual.contains(r#"label: Some("model.wgsl"),"#));
+        assert!(actual.contains(r#"label: Some("model.wgsl bind group layout 0"),"#));
+        assert!(
+            actual.contains(r#"label: Some("model.wgsl bind group 0 (uniforms, color_texture)"),"#)
         );
+        assert!(actual.contains(r#"label: Some("model.wgsl pipeline layout"),"#));
+
+        let options = WriterOptions {
+            generate_labels: false,
+            ..Default::default()
+        };
+        let actual = create_shader_module(source, "shaders/model.wgsl", options).unwrap();
+        assert!(!actual.contains("label: Some"));
     }
 
     #[test]
-    fn bind_group_layouts_descriptors_fragment() {
-        // The actual content of the structs doesn't matter.
-        // We only care about the groups and bindings.
+    fn create_shader_module_parse_error() {
         let source = indoc! {r#"
-            struct Transforms {};
-
-            [[group(0), binding(0)]] var<uniform> transforms: Transforms;
-
-            [[stage(fragment)]]
-            fn fs_main() {}
+            var<uniform> a: vec4<f32>
         "#};
 
-        let module = naga::front::wgsl::parse_str(source).unwrap();
-        let bind_group_data = wgsl::get_bind_group_data(&module).unwrap();
-
-        let mut actual = String::new();
-        for (group_no, group) in bind_group_data {
-            write_bind_group_layout(&mut actual, 0, group_no, &group);
-            write_bind_group_layout_descriptor(
-                &mut actual,
-                0,
-                group_no,
-                &group,
-                wgpu::ShaderStages::FRAGMENT,
-            );
-        }
+        let result = create_shader_module(source, "shader.wgsl", WriterOptions::default());
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::ParseError {
+                location: SourceLocation { line: 2, .. },
+                ..
+            })
+        ));
+    }
 
-        assert_eq!(
-            indoc! {
-                r"
-                pub struct BindGroupLayout0<'a> {
-                    pub transforms: wgpu::BufferBinding<'a>,
-                }
-                const LAYOUT_DESCRIPTOR0: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
-                    label: None,
-                    entries: &[
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0u32,
-                            visibility: wgpu::ShaderStages::FRAGMENT,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                    ]
-                };
-                "
-            },
-            actual
-        );
+    #[test]
+    fn create_shader_module_parse_error_eq() {
+        let parse_error = |source| {
+            create_shader_module(source, "shader.wgsl", WriterOptions::default()).unwrap_err()
+        };
+        assert_eq!(parse_error("fn main("), parse_error("fn main("));
+        assert_ne!(parse_error("fn main("), parse_error("fn main() {"));
     }
 
     #[test]
-    fn create_shader_module_consecutive_bind_groups() {
+    fn create_shader_module_at_attributes_error() {
         let source = indoc! {r#"
-            struct A {
-                f: vec4<f32>;
+            struct Camera {
+                view_proj: mat4x4<f32>,
             };
-            [[group(0), binding(0)]] var<uniform> a: A;
-            [[group(1), binding(0)]] var<uniform> b: A;
 
-            [[stage(vertex)]]
-            fn vs_main() {}
-
-            [[stage(fragment)]]
-            fn fs_main() {}
+            @group(0) @binding(0)
+            var<uniform> camera: Camera;
         "#};
 
-        create_shader_module(source, "shader.wgsl").unwrap();
+        let result = create_shader_module(source, "shader.wgsl", WriterOptions::default());
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::ParseError {
+                location: SourceLocation { line: 2, .. },
+                ..
+            })
+        ));
+        assert!(result.unwrap_err().to_string().contains(
+            "note: attributes like `@group(0) @binding(0)` require a newer version of naga"
+        ));
+
+        // Errors for the older syntax don't need the note.
+        let result = create_shader_module("fn main(", "shader.wgsl", WriterOptions::default());
+        assert!(!result.unwrap_err().to_string().contains("note: attributes"));
     }
 
     #[test]
-    fn create_shader_module_non_consecutive_bind_groups() {
+    fn create_shader_module_texture_external_error() {
+        // The current version of naga doesn't support external textures.
         let source = indoc! {r#"
-            [[group(0), binding(0)]] var<uniform> a: vec4<f32>;
-            [[group(1), binding(0)]] var<uniform> b: vec4<f32>;
-            [[group(3), binding(0)]] var<uniform> c: vec4<f32>;
+            [[group(0), binding(0)]]
+            var video: texture_external;
 
             [[stage(fragment)]]
-            fn main() {}
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(video, vec2<i32>(0));
+            }
         "#};
 
-        let result = create_shader_module(source, "shader.wgsl");
+        let result = create_shader_module(source, "shader.wgsl", WriterOptions::default());
         assert!(matches!(
             result,
-            Err(CreateModuleError::NonConsecutiveBindGroups)
+            Err(CreateModuleError::ParseError {
+                location: SourceLocation {
+                    line: 2,
+                    column: 12
+                },
+                ..
+            })
         ));
     }
 
     #[test]
-    fn create_shader_module_repeated_bindings() {
+    fn create_shader_module_validation_error() {
+        // Uniform buffers must use a struct type.
         let source = indoc! {r#"
-            struct A {
-                f: vec4<f32>;
-            };
-            [[group(0), binding(2)]] var<uniform> a: A;
-            [[group(0), binding(2)]] var<uniform> b: A;
+            [[group(0), binding(0)]] var<uniform> a: vec4<f32>;
 
             [[stage(fragment)]]
             fn main() {}
         "#};
 
-        let result = create_shader_module(source, "shader.wgsl");
+        let result = create_shader_module(source, "shader.wgsl", WriterOptions::default());
         assert!(matches!(
             result,
-            Err(CreateModuleError::DuplicateBinding { binding: 2 })
+            Err(CreateModuleError::ValidationError {
+                location: Some(SourceLocation { line: 1, .. }),
+                ..
+            })
         ));
     }
 
+    #[test]
+    fn write_set_bind_group_trait_passes() {
+        let mut actual = String::new();
+        write_set_bind_group_trait(&mut actual, 0, &WriterOptions::default());
+
+        assert_eq!(
+            indoc! {
+                r"
+            /// Types like render or compute passes that can set bind groups.
+            pub trait SetBindGroup<'a> {
+                fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup, offsets: &[wgpu::DynamicOffset]);
+            }
+            impl<'a> SetBindGroup<'a> for wgpu::RenderPass<'a> {
+                fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup, offsets: &[wgpu::DynamicOffset]) {
+                    wgpu::RenderPass::set_bind_group(self, index, bind_group, offsets);
+                }
+            }
+            impl<'a> SetBindGroup<'a> for wgpu::ComputePass<'a> {
+                fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup, offsets: &[wgpu::DynamicOffset]) {
+                    wgpu::ComputePass::set_bind_group(self, index, bind_group, offsets);
+                }
+            }
+            impl<'a> SetBindGroup<'a> for wgpu::RenderBundleEncoder<'a> {
+                fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup, offsets: &[wgpu::DynamicOffset]) {
+                    wgpu::RenderBundleEncoder::set_bind_group(self, index, bind_group, offsets);
+                }
+            }
+            "
+            },
+            actual
+        );
+    }
+
     #[test]
     fn set_bind_groups_vertex_fragment() {
         let source = indoc! {r#"
@@ -1019,21 +8747,36 @@ mod test {
         "#};
 
         let module = naga::front::wgsl::parse_str(source).unwrap();
-        let bind_group_data = wgsl::get_bind_group_data(&module).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
 
         let mut actual = String::new();
-        write_set_bind_groups(&mut actual, 0, &bind_group_data, false);
+        write_set_bind_groups(&mut actual, 0, &bind_group_data, &WriterOptions::default());
 
         assert_eq!(
             indoc! {
                 r"
             pub fn set_bind_groups<'a>(
-                pass: &mut wgpu::RenderPass<'a>,
+                pass: &mut impl SetBindGroup<'a>,
                 bind_groups: BindGroups<'a>,
             ) {
                 bind_groups.bind_group0.set(pass);
                 bind_groups.bind_group1.set(pass);
             }
+            /// Set only bind group 0 and leave any other bind groups unchanged.
+            pub fn set_bind_group0<'a>(
+                pass: &mut impl SetBindGroup<'a>,
+                bind_group: &'a BindGroup0,
+            ) {
+                bind_group.set(pass);
+            }
+            /// Set only bind group 1 and leave any other bind groups unchanged.
+            pub fn set_bind_group1<'a>(
+                pass: &mut impl SetBindGroup<'a>,
+                bind_group: &'a BindGroup1,
+            ) {
+                bind_group.set(pass);
+            }
             "
             },
             actual
@@ -1041,7 +8784,7 @@ mod test {
     }
 
     #[test]
-    fn set_bind_groups_compute() {
+    fn set_bind_groups_compute_vertex_fragment() {
         let source = indoc! {r#"
             struct Transforms {};
 
@@ -1049,30 +8792,396 @@ mod test {
             var color_texture: texture_2d<f32>;
             [[group(1), binding(0)]] var<uniform> transforms: Transforms;
 
-            [[stage(compute)]]
-            fn main() {}
+            [[stage(compute), workgroup_size(64)]]
+            fn cs_main() {}
+
+            [[stage(vertex)]]
+            fn vs_main() {}
+
+            [[stage(fragment)]]
+            fn fs_main() {}
         "#};
 
         let module = naga::front::wgsl::parse_str(source).unwrap();
-        let bind_group_data = wgsl::get_bind_group_data(&module).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
 
         let mut actual = String::new();
-        write_set_bind_groups(&mut actual, 0, &bind_group_data, true);
+        write_set_bind_groups(&mut actual, 0, &bind_group_data, &WriterOptions::default());
 
-        // The only change is that the function takes a ComputePass instead.
-        assert_eq!(
-            indoc! {
-                r"
+        // The same function works for render and compute passes.
+        assert!(actual.starts_with(indoc! {
+            r"
             pub fn set_bind_groups<'a>(
-                pass: &mut wgpu::ComputePass<'a>,
+                pass: &mut impl SetBindGroup<'a>,
                 bind_groups: BindGroups<'a>,
             ) {
                 bind_groups.bind_group0.set(pass);
                 bind_groups.bind_group1.set(pass);
             }
             "
-            },
+        }));
+        assert_eq!(1, actual.matches("pub fn set_bind_group0<'a>(").count());
+    }
+
+    #[test]
+    fn set_bind_groups_dynamic_offsets() {
+        let source = indoc! {r#"
+            struct Transforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(1), binding(1)]] var<uniform> material: Transforms;
+            [[group(1), binding(0)]] var<uniform> transforms: Transforms;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let options = WriterOptions {
+            dynamic_offset_bindings: vec!["material".to_string(), "transforms".to_string()],
+            ..Default::default()
+        };
+
+        let mut actual = String::new();
+        write_bind_group_layout_descriptor(&mut actual, 0, 1, &bind_group_data[&1], None, &options);
+        impl_bind_group(
+            &mut actual,
+            0,
+            &module,
+            1,
+            &bind_group_data[&1],
+            None,
+            &options,
+        )
+        .unwrap();
+        write_set_bind_groups(&mut actual, 0, &bind_group_data, &options);
+
+        let expected_set = indoc! {
+            r"
+                /// The dynamic offsets are for transforms, material in that order.
+                pub fn set<'a>(&'a self, pass: &mut impl SetBindGroup<'a>, offsets: &[wgpu::DynamicOffset]) {
+                    pass.set_bind_group(1u32, &self.0, offsets);
+                }
+            "
+        };
+        let expected_set_bind_groups = indoc! {
+            r"
+            pub fn set_bind_groups<'a>(
+                pass: &mut impl SetBindGroup<'a>,
+                bind_groups: BindGroups<'a>,
+            ) {
+                bind_groups.bind_group0.set(pass);
+                bind_groups.bind_group1.set(pass, bind_groups.bind_group1_offsets);
+            }
+            "
+        };
+        let expected_set_bind_group1 = indoc! {
+            r"
+            /// Set only bind group 1 and leave any other bind groups unchanged.
+            pub fn set_bind_group1<'a>(
+                pass: &mut impl SetBindGroup<'a>,
+                bind_group: &'a BindGroup1,
+                offsets: &[wgpu::DynamicOffset],
+            ) {
+                bind_group.set(pass, offsets);
+            }
+            "
+        };
+        assert_eq!(2, actual.matches("has_dynamic_offset: true").count());
+        assert!(actual.contains(&indent(expected_set, 4)));
+        assert!(actual.contains(expected_set_bind_groups));
+        assert!(actual.ends_with(expected_set_bind_group1));
+    }
+
+    #[test]
+    fn set_bind_groups_owned() {
+        let source = indoc! {r#"
+            struct Transforms {
+                a: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(1), binding(0)]] var<uniform> transforms: Transforms;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let bind_group_data = wgsl::get_bind_group_data(&module, &info, source).unwrap();
+
+        let options = WriterOptions {
+            dynamic_offset_bindings: vec!["transforms".to_string()],
+            owned_bind_groups: true,
+            ..Default::default()
+        };
+
+        let mut actual = String::new();
+        write_bind_groups_module(
+            &mut actual,
+            &module,
+            &bind_group_data,
+            &BTreeMap::new(),
+            None,
+            &options,
+        )
+        .unwrap();
+
+        let expected = indoc! {
+            r"
+                pub struct BindGroups {
+                    pub bind_group0: BindGroup0,
+                    pub bind_group1: BindGroup1,
+                    pub bind_group1_offsets: Vec<wgpu::DynamicOffset>,
+                }
+                pub fn set_bind_groups<'a>(
+                    pass: &mut impl SetBindGroup<'a>,
+                    bind_groups: &'a BindGroups,
+                ) {
+                    bind_groups.bind_group0.set(pass);
+                    bind_groups.bind_group1.set(pass, &bind_groups.bind_group1_offsets);
+                }
+            "
+        };
+        assert!(actual.contains(&indent(expected, 4)));
+    }
+
+    #[cfg(feature = "glsl")]
+    #[test]
+    fn create_shader_module_glsl_vertex_fragment() {
+        let vertex_source = indoc! {r#"
+            #version 450
+            layout(location = 0) in vec3 position;
+            layout(location = 1) in float weight;
+            layout(location = 0) out vec3 color;
+            layout(set = 0, binding = 0) uniform Camera {
+                mat4 view_projection;
+            } camera;
+
+            void main() {
+                color = position * weight;
+                gl_Position = camera.view_projection * vec4(position, 1.0);
+            }
+        "#};
+        let fragment_source = indoc! {r#"
+            #version 450
+            layout(location = 0) in vec3 color;
+            layout(location = 0) out vec4 out_color;
+            layout(set = 0, binding = 0) uniform Camera {
+                mat4 view_projection;
+            } camera;
+            layout(set = 1, binding = 0) uniform texture2D color_texture;
+            layout(set = 1, binding = 1) uniform sampler color_sampler;
+
+            void main() {
+                out_color = texture(sampler2D(color_texture, color_sampler), color.xy) * camera.view_projection[0];
+            }
+        "#};
+
+        let actual = create_shader_module_glsl(
+            vertex_source,
+            fragment_source,
+            "shader",
+            WriterOptions::default(),
+        )
+        .unwrap();
+        assert!(actual.contains(indoc! {r"
+            pub struct VsMainOutput {
+                pub color: [f32; 3],
+                pub position: [f32; 4],
+            }
+        "}));
+        assert!(actual.contains(indoc! {r"
+            pub struct VsMainInput {
+                pub position: [f32; 3],
+                pub weight: f32,
+            }
+        "}));
+        // The camera is declared in both stages but only bound once.
+        assert_eq!(
+            1,
             actual
+                .matches("pub camera: wgpu::BufferBinding<'a>,")
+                .count()
+        );
+        assert!(actual.contains("visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,"));
+        assert!(actual.contains("pub const ENTRY_VS_MAIN: &str = \"vs_main\";"));
+        assert!(actual.contains("pub const ENTRY_FS_MAIN: &str = \"fs_main\";"));
+        assert!(actual.contains("const SHADER_WGSL: &str = "));
+    }
+
+    #[cfg(feature = "glsl")]
+    #[test]
+    fn create_shader_module_glsl_binding_mismatch() {
+        let vertex_source = indoc! {r#"
+            #version 450
+            layout(set = 0, binding = 0) uniform A {
+                vec4 a;
+            } a;
+
+            void main() {
+                gl_Position = a.a;
+            }
+        "#};
+        let fragment_source = indoc! {r#"
+            #version 450
+            layout(location = 0) out vec4 color;
+            layout(set = 0, binding = 0) uniform B {
+                vec4 b;
+            } b;
+
+            void main() {
+                color = b.b;
+            }
+        "#};
+
+        let result = create_shader_module_glsl(
+            vertex_source,
+            fragment_source,
+            "shader",
+            WriterOptions::default(),
+        );
+        assert_eq!(
+            Err(CreateModuleError::DuplicateBinding {
+                group: 0,
+                binding: 0,
+                name: "b".to_string(),
+                location: None
+            }),
+            result
         );
     }
+
+    #[cfg(feature = "glsl")]
+    #[test]
+    fn create_shader_module_glsl_parse_error() {
+        let vertex_source = indoc! {r#"
+            #version 450
+            void main() {
+                gl_Position = vec4(0.0)
+            }
+        "#};
+
+        let result =
+            create_shader_module_glsl(vertex_source, "", "shader", WriterOptions::default());
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::GlslParseError {
+                stage: naga::ShaderStage::Vertex,
+                location: Some(SourceLocation { line: 4, .. }),
+                ..
+            })
+        ));
+    }
+
+    #[cfg(feature = "spirv")]
+    fn spirv_bytes(source: &str, flags: naga::back::spv::WriterFlags) -> Vec<u8> {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = wgsl::validate(&module).unwrap();
+        let options = naga::back::spv::Options {
+            flags,
+            ..Default::default()
+        };
+        let words = naga::back::spv::write_vec(&module, &info, &options, None).unwrap();
+        words.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    #[cfg(feature = "spirv")]
+    #[test]
+    fn create_shader_module_spirv_debug_names() {
+        let source = indoc! {r#"
+            struct Camera {
+                view_projection: mat4x4<f32>;
+            };
+            [[group(0), binding(0)]] var<uniform> camera: Camera;
+            [[group(1), binding(0)]] var color_texture: texture_2d<f32>;
+            [[group(1), binding(1)]] var color_sampler: sampler;
+
+            struct VertexOutput {
+                [[builtin(position)]] clip_position: vec4<f32>;
+                [[location(0)]] uv: vec2<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn vs_main([[location(0)]] position: vec3<f32>, [[location(1)]] uv: vec2<f32>) -> VertexOutput {
+                var out: VertexOutput;
+                out.clip_position = camera.view_projection * vec4<f32>(position, 1.0);
+                out.uv = uv;
+                return out;
+            }
+
+            [[stage(fragment)]]
+            fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+                return textureSample(color_texture, color_sampler, in.uv);
+            }
+        "#};
+
+        let spirv = spirv_bytes(source, naga::back::spv::WriterFlags::DEBUG);
+        let actual =
+            create_shader_module_spirv(&spirv, "shader.spv", WriterOptions::default()).unwrap();
+        // naga wraps buffers in a block struct and doesn't write names for entry point arguments.
+        assert!(actual.contains(indoc! {r"
+            pub struct CameraBlock {
+                pub member0: Camera,
+            }
+        "}));
+        assert!(actual.contains(indoc! {r"
+            pub struct VsMainOutput {
+                pub position: [f32; 4],
+                pub location0: [f32; 2],
+            }
+        "}));
+        assert!(actual.contains("pub location1: [f32; 2],"));
+        assert!(actual.contains("/// Group 0 binding 0 for `var<uniform> camera: CameraBlock`."));
+        assert!(actual.contains("pub color_texture: &'a wgpu::TextureView,"));
+        assert!(actual.contains("pub const ENTRY_VS_MAIN: &str = \"vs_main\";"));
+        assert!(actual.contains("pub const ENTRY_FS_MAIN: &str = \"fs_main\";"));
+    }
+
+    #[cfg(feature = "spirv")]
+    #[test]
+    fn create_shader_module_spirv_without_names() {
+        let source = indoc! {r#"
+            struct Camera {
+                view_projection: mat4x4<f32>;
+            };
+            [[group(0), binding(0)]] var<uniform> camera: Camera;
+
+            [[stage(vertex)]]
+            fn vs_main([[location(0)]] position: vec3<f32>) -> [[builtin(position)]] vec4<f32> {
+                return camera.view_projection * vec4<f32>(position, 1.0);
+            }
+        "#};
+
+        let spirv = spirv_bytes(source, naga::back::spv::WriterFlags::empty());
+        let actual =
+            create_shader_module_spirv(&spirv, "shader.spv", WriterOptions::default()).unwrap();
+        assert!(actual.contains(indoc! {r"
+            pub struct Group0Binding0Block {
+                pub member0: Struct3,
+            }
+        "}));
+        assert!(actual.contains("pub group0_binding0: wgpu::BufferBinding<'a>,"));
+        assert!(actual.contains("pub location0: [f32; 3],"));
+    }
+
+    #[cfg(feature = "spirv")]
+    #[test]
+    fn create_shader_module_spirv_invalid() {
+        let result =
+            create_shader_module_spirv(&[0, 1, 2, 3], "shader.spv", WriterOptions::default());
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::SpirvParseError { .. })
+        ));
+    }
 }
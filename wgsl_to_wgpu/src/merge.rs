@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use naga::{Block, Constant, ConstantInner, Expression, Handle, Statement, Type, TypeInner};
+
+use crate::{entry_point_variant_name, name_words, CreateModuleError};
+
+// Copy the items in `source` to `target` with the entry points renamed by `entry_name`.
+// Frontends other than WGSL can leave items unnamed, but the generated Rust items need names.
+pub fn append_module(
+    target: &mut naga::Module,
+    source: naga::Module,
+    entry_name: impl Fn(&str) -> String,
+) -> Result<(), CreateModuleError> {
+    // Outputs are often combined into an unnamed struct, so name it after the entry point.
+    let mut struct_names: HashMap<_, _> = source
+        .entry_points
+        .iter()
+        .filter_map(|e| {
+            let name = entry_name(&e.name);
+            let result = e.function.result.as_ref()?;
+            Some((
+                result.ty,
+                format!("{}Output", entry_point_variant_name(&name)),
+            ))
+        })
+        .collect();
+    // SPIR-V wraps buffer contents in an unnamed block struct, so name it after the buffer.
+    for (_, global) in source.global_variables.iter() {
+        if let Some(name) = global_name(global) {
+            struct_names
+                .entry(global.ty)
+                .or_insert_with(|| format!("{}Block", entry_point_variant_name(&name)));
+        }
+    }
+
+    let mut merger = Merger {
+        source: &source,
+        target,
+        struct_names,
+        types: HashMap::new(),
+        constants: HashMap::new(),
+        globals: HashMap::new(),
+        functions: HashMap::new(),
+    };
+    // Expressions can refer to any type or constant, so everything is mapped before the functions.
+    for (handle, _) in source.types.iter() {
+        merger.map_type(handle);
+    }
+    for (handle, _) in source.constants.iter() {
+        merger.map_constant(handle);
+    }
+    for (handle, _) in source.global_variables.iter() {
+        merger.map_global(handle)?;
+    }
+    // Functions can call functions declared after them, so all handles are reserved first.
+    for (handle, _) in source.functions.iter() {
+        let new_handle = merger
+            .target
+            .functions
+            .append(naga::Function::default(), naga::Span::default());
+        merger.functions.insert(handle, new_handle);
+    }
+
+    let handles: Vec<_> = source.functions.iter().map(|(h, _)| h).collect();
+    let mapping = merger.into_mapping();
+    let naga::Module {
+        functions,
+        entry_points,
+        ..
+    } = source;
+    for (handle, mut function) in handles.into_iter().zip(functions.into_inner()) {
+        mapping.map_function(&mut function);
+        mapping.target.functions[mapping.functions[&handle]] = function;
+    }
+    for mut entry in entry_points {
+        mapping.map_function(&mut entry.function);
+        for (i, argument) in entry.function.arguments.iter_mut().enumerate() {
+            if argument.name.is_none() {
+                argument.name = Some(binding_name(&argument.binding, "argument", i));
+            }
+        }
+        entry.name = entry_name(&entry.name);
+        mapping.target.entry_points.push(entry);
+    }
+    Ok(())
+}
+
+// Bindings are identified by their index, so unnamed bindings use names like "group0_binding1".
+fn global_name(global: &naga::GlobalVariable) -> Option<String> {
+    global.name.clone().or_else(|| {
+        global
+            .binding
+            .as_ref()
+            .map(|b| format!("group{}_binding{}", b.group, b.binding))
+    })
+}
+
+// Use names like "position" for builtins and "location0" for locations.
+fn binding_name(binding: &Option<naga::Binding>, prefix: &str, index: usize) -> String {
+    match binding {
+        Some(naga::Binding::BuiltIn(builtin)) => name_words(&format!("{builtin:?}")).join("_"),
+        Some(naga::Binding::Location { location, .. }) => format!("location{location}"),
+        None => format!("{prefix}{index}"),
+    }
+}
+
+struct Merger<'a, 's> {
+    source: &'s naga::Module,
+    target: &'a mut naga::Module,
+    struct_names: HashMap<Handle<Type>, String>,
+    types: HashMap<Handle<Type>, Handle<Type>>,
+    constants: HashMap<Handle<Constant>, Handle<Constant>>,
+    globals: HashMap<Handle<naga::GlobalVariable>, Handle<naga::GlobalVariable>>,
+    functions: HashMap<Handle<naga::Function>, Handle<naga::Function>>,
+}
+
+impl<'a> Merger<'a, '_> {
+    fn into_mapping(self) -> Mapping<'a> {
+        Mapping {
+            target: self.target,
+            types: self.types,
+            constants: self.constants,
+            globals: self.globals,
+            functions: self.functions,
+        }
+    }
+
+    fn map_type(&mut self, handle: Handle<Type>) -> Handle<Type> {
+        if let Some(mapped) = self.types.get(&handle) {
+            return *mapped;
+        }
+
+        let ty = &self.source.types[handle];
+        // Types aren't Clone, so each variant is rebuilt with the new handles.
+        let inner = match &ty.inner {
+            TypeInner::Scalar { kind, width } => TypeInner::Scalar {
+                kind: *kind,
+                width: *width,
+            },
+            TypeInner::Vector { size, kind, width } => TypeInner::Vector {
+                size: *size,
+                kind: *kind,
+                width: *width,
+            },
+            TypeInner::Matrix {
+                columns,
+                rows,
+                width,
+            } => TypeInner::Matrix {
+                columns: *columns,
+                rows: *rows,
+                width: *width,
+            },
+            TypeInner::Atomic { kind, width } => TypeInner::Atomic {
+                kind: *kind,
+                width: *width,
+            },
+            TypeInner::Pointer { base, class } => TypeInner::Pointer {
+                base: self.map_type(*base),
+                class: *class,
+            },
+            TypeInner::ValuePointer {
+                size,
+                kind,
+                width,
+                class,
+            } => TypeInner::ValuePointer {
+                size: *size,
+                kind: *kind,
+                width: *width,
+                class: *class,
+            },
+            TypeInner::Array { base, size, stride } => TypeInner::Array {
+                base: self.map_type(*base),
+                size: match size {
+                    naga::ArraySize::Constant(c) => {
+                        naga::ArraySize::Constant(self.map_constant(*c))
+                    }
+                    naga::ArraySize::Dynamic => naga::ArraySize::Dynamic,
+                },
+                stride: *stride,
+            },
+            TypeInner::Struct { members, span } => TypeInner::Struct {
+                members: members
+                    .iter()
+                    .enumerate()
+                    .map(|(i, m)| naga::StructMember {
+                        name: m
+                            .name
+                            .clone()
+                            .or_else(|| Some(binding_name(&m.binding, "member", i))),
+                        ty: self.map_type(m.ty),
+                        ..m.clone()
+                    })
+                    .collect(),
+                span: *span,
+            },
+            TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => TypeInner::Image {
+                dim: *dim,
+                arrayed: *arrayed,
+                class: *class,
+            },
+            TypeInner::Sampler { comparison } => TypeInner::Sampler {
+                comparison: *comparison,
+            },
+        };
+        let name = match (&ty.name, &inner) {
+            (Some(name), _) => Some(name.clone()),
+            (None, TypeInner::Struct { .. }) => Some(
+                self.struct_names
+                    .get(&handle)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Struct{}", self.target.types.len())),
+            ),
+            (None, _) => None,
+        };
+
+        // Identical types from both stages are only defined once.
+        let mapped = self
+            .target
+            .types
+            .insert(Type { name, inner }, naga::Span::default());
+        self.types.insert(handle, mapped);
+        mapped
+    }
+
+    fn map_constant(&mut self, handle: Handle<Constant>) -> Handle<Constant> {
+        if let Some(mapped) = self.constants.get(&handle) {
+            return *mapped;
+        }
+
+        let constant = &self.source.constants[handle];
+        let inner = match &constant.inner {
+            ConstantInner::Scalar { width, value } => ConstantInner::Scalar {
+                width: *width,
+                value: *value,
+            },
+            ConstantInner::Composite { ty, components } => ConstantInner::Composite {
+                ty: self.map_type(*ty),
+                components: components.iter().map(|c| self.map_constant(*c)).collect(),
+            },
+        };
+
+        // Reuse identical constants to avoid generating the same Rust constant twice.
+        let mapped = self.target.constants.fetch_or_append(
+            Constant {
+                name: constant.name.clone(),
+                specialization: constant.specialization,
+                inner,
+            },
+            naga::Span::default(),
+        );
+        self.constants.insert(handle, mapped);
+        mapped
+    }
+
+    fn map_global(
+        &mut self,
+        handle: Handle<naga::GlobalVariable>,
+    ) -> Result<(), CreateModuleError> {
+        let global = &self.source.global_variables[handle];
+        let global = naga::GlobalVariable {
+            name: global_name(global),
+            ty: self.map_type(global.ty),
+            init: global.init.map(|c| self.map_constant(c)),
+            ..global.clone()
+        };
+
+        // Resources used in several modules are declared in each module but only bound once.
+        let existing = global.binding.as_ref().and_then(|binding| {
+            self.target
+                .global_variables
+                .iter()
+                .find(|(_, g)| g.binding.as_ref() == Some(binding))
+        });
+        let mapped = match existing {
+            Some((existing, g)) if g.ty == global.ty && g.class == global.class => existing,
+            Some(_) => {
+                let binding = global.binding.as_ref().unwrap();
+                return Err(CreateModuleError::DuplicateBinding {
+                    group: binding.group,
+                    binding: binding.binding,
+                    name: global.name.clone().unwrap_or_default(),
+                    location: None,
+                });
+            }
+            None => self
+                .target
+                .global_variables
+                .append(global, naga::Span::default()),
+        };
+        self.globals.insert(handle, mapped);
+        Ok(())
+    }
+}
+
+// All handles are already mapped, so functions can be moved out of the source module.
+struct Mapping<'a> {
+    target: &'a mut naga::Module,
+    types: HashMap<Handle<Type>, Handle<Type>>,
+    constants: HashMap<Handle<Constant>, Handle<Constant>>,
+    globals: HashMap<Handle<naga::GlobalVariable>, Handle<naga::GlobalVariable>>,
+    functions: HashMap<Handle<naga::Function>, Handle<naga::Function>>,
+}
+
+impl Mapping<'_> {
+    fn map_function(&self, function: &mut naga::Function) {
+        for argument in &mut function.arguments {
+            argument.ty = self.types[&argument.ty];
+        }
+        if let Some(result) = &mut function.result {
+            result.ty = self.types[&result.ty];
+        }
+        for (_, local) in function.local_variables.iter_mut() {
+            local.ty = self.types[&local.ty];
+            local.init = local.init.map(|c| self.constants[&c]);
+        }
+        for (_, expression) in function.expressions.iter_mut() {
+            match expression {
+                Expression::Constant(c) => *c = self.constants[c],
+                Expression::Compose { ty, .. } => *ty = self.types[ty],
+                Expression::GlobalVariable(g) => *g = self.globals[g],
+                Expression::ImageSample {
+                    offset: Some(c), ..
+                } => *c = self.constants[c],
+                Expression::CallResult(f) => *f = self.functions[f],
+                _ => (),
+            }
+        }
+        self.map_block(&mut function.body);
+    }
+
+    fn map_block(&self, block: &mut Block) {
+        for statement in block.iter_mut() {
+            match statement {
+                Statement::Block(block) => self.map_block(block),
+                Statement::If { accept, reject, .. } => {
+                    self.map_block(accept);
+                    self.map_block(reject);
+                }
+                Statement::Switch { cases, .. } => {
+                    for case in cases {
+                        self.map_block(&mut case.body);
+                    }
+                }
+                Statement::Loop { body, continuing } => {
+                    self.map_block(body);
+                    self.map_block(continuing);
+                }
+                Statement::Call { function, .. } => *function = self.functions[function],
+                _ => (),
+            }
+        }
+    }
+}
@@ -0,0 +1,354 @@
+/// The combined WGSL source from [preprocess] and the files it includes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreprocessedSource {
+    /// The WGSL source with each `#include` directive replaced by the included source.
+    pub source: String,
+    /// The paths of all included files in the order they were first included.
+    /// Build scripts can watch these with `cargo:rerun-if-changed`.
+    pub included_files: Vec<String>,
+}
+
+/// Errors while resolving `#include` and conditional directives in [preprocess].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreprocessError {
+    /// The resolver didn't find the source for `path`.
+    /// The `line` starts from 1 and refers to the directive in the including file.
+    MissingInclude { path: String, line: usize },
+
+    /// The directive on `line` doesn't have a path in double quotes like `#include "common.wgsl"`.
+    InvalidInclude { line: usize },
+
+    /// The `#ifdef` or `#ifndef` directive on `line` doesn't have a define name.
+    InvalidConditional { line: usize },
+
+    /// The `#else` or `#endif` directive on `line` doesn't belong to an open `#ifdef` or `#ifndef`.
+    UnmatchedDirective { directive: String, line: usize },
+
+    /// The `#ifdef` or `#ifndef` directive on `line` is never closed with `#endif`.
+    UnclosedConditional { line: usize },
+}
+
+impl std::fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreprocessError::MissingInclude { path, line } => {
+                write!(f, "failed to resolve include `{path}` on line {line}")
+            }
+            PreprocessError::InvalidInclude { line } => {
+                write!(f, "expected a quoted path for the include on line {line}")
+            }
+            PreprocessError::InvalidConditional { line } => {
+                write!(
+                    f,
+                    "expected a define name for the conditional on line {line}"
+                )
+            }
+            PreprocessError::UnmatchedDirective { directive, line } => {
+                write!(
+                    f,
+                    "unexpected `{directive}` without a matching `#ifdef` on line {line}"
+                )
+            }
+            PreprocessError::UnclosedConditional { line } => {
+                write!(f, "missing `#endif` for the conditional on line {line}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PreprocessError {}
+
+/// Replaces `#include "path"` directives in `wgsl_source` with the source returned by `resolve_include`
+/// and removes `#ifdef NAME` or `#ifndef NAME` blocks depending on the names in `defines`.
+///
+/// Conditional blocks can contain an `#else` and must end with `#endif`.
+/// Directives in removed blocks, including includes, are ignored.
+/// The `defines` also apply to the included files.
+///
+/// The `resolve_include` function is called with the path as written in the directive
+/// and returns `None` if the file can't be found.
+/// Included files are preprocessed recursively but only included once,
+/// so shared definitions aren't duplicated and cyclic includes are ignored.
+///
+/// The generated code embeds the file at `wgsl_include_path`,
+/// so write the preprocessed source to a file and use its path for [crate::create_shader_module].
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+let wgsl_source = std::fs::read_to_string("src/shader.wgsl").unwrap();
+let preprocessed = wgsl_to_wgpu::preprocess(&wgsl_source, &["SHADOWS"], |path| {
+    std::fs::read_to_string(format!("src/{path}")).ok()
+})
+.unwrap();
+for path in &preprocessed.included_files {
+    println!("cargo:rerun-if-changed=src/{path}");
+}
+std::fs::write("src/shader.preprocessed.wgsl", &preprocessed.source).unwrap();
+
+let text = wgsl_to_wgpu::create_shader_module(
+    &preprocessed.source,
+    "shader.preprocessed.wgsl",
+    wgsl_to_wgpu::WriterOptions::default(),
+)
+.unwrap();
+std::fs::write("src/shader.rs", text.as_bytes()).unwrap();
+```
+ */
+pub fn preprocess<F>(
+    wgsl_source: &str,
+    defines: &[&str],
+    mut resolve_include: F,
+) -> Result<PreprocessedSource, PreprocessError>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut included_files = Vec::new();
+    let source = preprocess_source(
+        wgsl_source,
+        defines,
+        &mut resolve_include,
+        &mut included_files,
+    )?;
+    Ok(PreprocessedSource {
+        source,
+        included_files,
+    })
+}
+
+struct Conditional {
+    is_active: bool,
+    has_else: bool,
+    line: usize,
+}
+
+fn preprocess_source<F>(
+    wgsl_source: &str,
+    defines: &[&str],
+    resolve_include: &mut F,
+    included_files: &mut Vec<String>,
+) -> Result<String, PreprocessError>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut output = String::new();
+    let mut conditionals: Vec<Conditional> = Vec::new();
+    for (i, line) in wgsl_source.lines().enumerate() {
+        let line_number = i + 1;
+        let trimmed = line.trim();
+        let is_active = conditionals.iter().all(|c| c.is_active);
+
+        let conditional = match (
+            trimmed.strip_prefix("#ifdef"),
+            trimmed.strip_prefix("#ifndef"),
+        ) {
+            (Some(name), _) => Some((name.trim(), true)),
+            (_, Some(name)) => Some((name.trim(), false)),
+            _ => None,
+        };
+
+        if let Some((name, is_defined_block)) = conditional {
+            if name.is_empty() {
+                return Err(PreprocessError::InvalidConditional { line: line_number });
+            }
+            conditionals.push(Conditional {
+                is_active: defines.contains(&name) == is_defined_block,
+                has_else: false,
+                line: line_number,
+            });
+        } else if trimmed == "#else" {
+            match conditionals.last_mut() {
+                Some(conditional) if !conditional.has_else => {
+                    conditional.is_active = !conditional.is_active;
+                    conditional.has_else = true;
+                }
+                _ => {
+                    return Err(PreprocessError::UnmatchedDirective {
+                        directive: trimmed.to_string(),
+                        line: line_number,
+                    })
+                }
+            }
+        } else if trimmed == "#endif" {
+            if conditionals.pop().is_none() {
+                return Err(PreprocessError::UnmatchedDirective {
+                    directive: trimmed.to_string(),
+                    line: line_number,
+                });
+            }
+        } else if !is_active {
+            continue;
+        } else if let Some(directive) = trimmed.strip_prefix("#include") {
+            let path = directive
+                .trim()
+                .strip_prefix('"')
+                .and_then(|d| d.strip_suffix('"'))
+                .filter(|path| !path.is_empty())
+                .ok_or(PreprocessError::InvalidInclude { line: line_number })?;
+
+            if included_files.iter().any(|f| f == path) {
+                continue;
+            }
+            let included_source =
+                resolve_include(path).ok_or_else(|| PreprocessError::MissingInclude {
+                    path: path.to_string(),
+                    line: line_number,
+                })?;
+            included_files.push(path.to_string());
+
+            output +=
+                &preprocess_source(&included_source, defines, resolve_include, included_files)?;
+        } else {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    match conditionals.first() {
+        Some(conditional) => Err(PreprocessError::UnclosedConditional {
+            line: conditional.line,
+        }),
+        None => Ok(output),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn resolve(path: &str) -> Option<String> {
+        match path {
+            "common.wgsl" => Some(indoc! {r#"
+                #include "constants.wgsl"
+                struct Camera {
+                    position: vec4<f32>;
+                };
+            "#}),
+            "constants.wgsl" => Some("let PI: f32 = 3.14159;\n"),
+            "cycle.wgsl" => Some("#include \"cycle.wgsl\"\nlet A: f32 = 1.0;\n"),
+            _ => None,
+        }
+        .map(str::to_string)
+    }
+
+    #[test]
+    fn preprocess_nested_includes() {
+        let source = indoc! {r#"
+            #include "common.wgsl"
+              #include "constants.wgsl"
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+        "#};
+
+        assert_eq!(
+            PreprocessedSource {
+                source: indoc! {r#"
+                    let PI: f32 = 3.14159;
+                    struct Camera {
+                        position: vec4<f32>;
+                    };
+
+                    [[group(0), binding(0)]]
+                    var<uniform> camera: Camera;
+                "#}
+                .to_string(),
+                included_files: vec!["common.wgsl".to_string(), "constants.wgsl".to_string()],
+            },
+            preprocess(source, &[], resolve).unwrap()
+        );
+    }
+
+    #[test]
+    fn preprocess_cyclic_include() {
+        let actual = preprocess("#include \"cycle.wgsl\"\n", &[], resolve).unwrap();
+        assert_eq!("let A: f32 = 1.0;\n", actual.source);
+        assert_eq!(vec!["cycle.wgsl".to_string()], actual.included_files);
+    }
+
+    #[test]
+    fn preprocess_missing_include() {
+        let source = indoc! {r#"
+            let A: f32 = 1.0;
+            #include "missing.wgsl"
+        "#};
+
+        assert_eq!(
+            Err(PreprocessError::MissingInclude {
+                path: "missing.wgsl".to_string(),
+                line: 2
+            }),
+            preprocess(source, &[], resolve)
+        );
+    }
+
+    #[test]
+    fn preprocess_invalid_include() {
+        assert_eq!(
+            Err(PreprocessError::InvalidInclude { line: 1 }),
+            preprocess("#include common.wgsl\n", &[], resolve)
+        );
+    }
+
+    #[test]
+    fn preprocess_defines() {
+        let source = indoc! {r#"
+            #ifdef SHADOWS
+            #include "constants.wgsl"
+            let SHADOWS: bool = true;
+            #else
+            let SHADOWS: bool = false;
+            #endif
+            #ifndef SHADOWS
+            #ifdef MISSING
+            let MISSING: bool = true;
+            #endif
+            let NO_SHADOWS: bool = true;
+            #endif
+        "#};
+
+        assert_eq!(
+            PreprocessedSource {
+                source: "let PI: f32 = 3.14159;\nlet SHADOWS: bool = true;\n".to_string(),
+                included_files: vec!["constants.wgsl".to_string()],
+            },
+            preprocess(source, &["SHADOWS"], resolve).unwrap()
+        );
+        assert_eq!(
+            PreprocessedSource {
+                source: "let SHADOWS: bool = false;\nlet NO_SHADOWS: bool = true;\n".to_string(),
+                included_files: Vec::new(),
+            },
+            preprocess(source, &[], resolve).unwrap()
+        );
+    }
+
+    #[test]
+    fn preprocess_invalid_conditionals() {
+        assert_eq!(
+            Err(PreprocessError::InvalidConditional { line: 1 }),
+            preprocess("#ifdef\n#endif\n", &[], resolve)
+        );
+        assert_eq!(
+            Err(PreprocessError::UnmatchedDirective {
+                directive: "#endif".to_string(),
+                line: 2
+            }),
+            preprocess("let A: f32 = 1.0;\n#endif\n", &[], resolve)
+        );
+        assert_eq!(
+            Err(PreprocessError::UnmatchedDirective {
+                directive: "#else".to_string(),
+                line: 3
+            }),
+            preprocess("#ifdef A\n#else\n#else\n#endif\n", &[], resolve)
+        );
+        assert_eq!(
+            Err(PreprocessError::UnclosedConditional { line: 1 }),
+            preprocess("#ifdef A\n#ifdef B\n#endif\n", &[], resolve)
+        );
+    }
+}
@@ -0,0 +1,443 @@
+use std::fmt::Write;
+
+use crate::{wgsl, CreateModuleError, WriterOptions};
+
+/// Generates a JSON description of the bind groups, vertex inputs, entry points, and structs in `wgsl_source`.
+///
+/// The JSON describes the same shader interface as the Rust module from [crate::create_shader_module],
+/// so tools outside of Rust like web editors can use the reflection data without parsing WGSL themselves.
+/// Vertex formats and step modes use the `options` like the generated vertex buffer layouts.
+/// Struct member offsets and sizes use the WGSL memory layout.
+/// The top level object has arrays for `"bind_groups"`, `"vertex_inputs"`, `"entry_points"`, and `"structs"`.
+///
+/// # Examples
+/**
+```rust no_run
+// build.rs
+let wgsl_source = std::fs::read_to_string("src/shader.wgsl").unwrap();
+let options = wgsl_to_wgpu::WriterOptions::default();
+let json = wgsl_to_wgpu::create_reflection_json(&wgsl_source, options).unwrap();
+std::fs::write("src/shader.json", json.as_bytes()).unwrap();
+```
+ */
+pub fn create_reflection_json(
+    wgsl_source: &str,
+    options: WriterOptions,
+) -> Result<String, CreateModuleError> {
+    let module = crate::parse_wgsl(wgsl_source)?;
+    let info = crate::validate_module(&module, wgsl_source)?;
+    let bind_group_data = crate::get_bind_group_data(&module, &info, wgsl_source, &options)?;
+    wgsl::check_vertex_inputs(&module, wgsl_source, &options.vertex_format_overrides)?;
+
+    let bind_groups = bind_group_data
+        .iter()
+        .map(|(group_no, group)| {
+            let bindings = group
+                .bindings
+                .iter()
+                .map(|binding| binding_json(&module, binding))
+                .collect();
+            Json::Object(vec![
+                ("group", Json::Number(*group_no as u64)),
+                ("bindings", Json::Array(bindings)),
+            ])
+        })
+        .collect();
+
+    let vertex_inputs = wgsl::get_vertex_input_structs(&module)
+        .into_iter()
+        .map(|input| {
+            let step_mode = if options.instance_input_structs.contains(&input.name) {
+                "instance"
+            } else {
+                "vertex"
+            };
+            let attributes = input
+                .fields
+                .iter()
+                .map(|(location, member)| {
+                    let name = member.name.clone().unwrap_or_default();
+                    let format =
+                        wgsl::vertex_field_format(&module, member, &options.vertex_format_overrides)
+                            .unwrap();
+                    Json::Object(vec![
+                        ("name", Json::String(name)),
+                        ("location", Json::Number(*location as u64)),
+                        ("format", Json::String(format!("{format:?}"))),
+                    ])
+                })
+                .collect();
+            Json::Object(vec![
+                ("name", Json::String(input.name)),
+                ("step_mode", Json::String(step_mode.to_string())),
+                ("attributes", Json::Array(attributes)),
+            ])
+        })
+        .collect();
+
+    let entry_points = wgsl::sorted_entry_points(&module)
+        .into_iter()
+        .map(|entry| {
+            let stage = wgsl::shader_stage_name(entry.stage);
+            let mut fields = vec![
+                ("name", Json::String(entry.name.clone())),
+                ("stage", Json::String(stage.to_string())),
+            ];
+            if entry.stage == naga::ShaderStage::Compute {
+                let size = entry.workgroup_size.map(|s| Json::Number(s as u64));
+                fields.push(("workgroup_size", Json::Array(size.into())));
+            }
+            Json::Object(fields)
+        })
+        .collect();
+
+    let structs = wgsl::sorted_struct_types(&module)
+        .into_iter()
+        .filter_map(|(_, ty)| match &ty.inner {
+            naga::TypeInner::Struct { members, span } => {
+                let members = members
+                    .iter()
+                    .map(|member| {
+                        let member_ty = &module.types[member.ty];
+                        Json::Object(vec![
+                            (
+                                "name",
+                                Json::String(member.name.clone().unwrap_or_default()),
+                            ),
+                            (
+                                "type",
+                                Json::String(wgsl::wgsl_type_name(&module, member_ty)),
+                            ),
+                            ("offset", Json::Number(member.offset as u64)),
+                            (
+                                "size",
+                                Json::Number(member_ty.inner.span(&module.constants) as u64),
+                            ),
+                        ])
+                    })
+                    .collect();
+                Some(Json::Object(vec![
+                    ("name", Json::String(ty.name.clone().unwrap_or_default())),
+                    ("size", Json::Number(*span as u64)),
+                    ("members", Json::Array(members)),
+                ]))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let json = Json::Object(vec![
+        ("bind_groups", Json::Array(bind_groups)),
+        ("vertex_inputs", Json::Array(vertex_inputs)),
+        ("entry_points", Json::Array(entry_points)),
+        ("structs", Json::Array(structs)),
+    ]);
+
+    let mut output = String::new();
+    json.write(&mut output, 0);
+    output.push('\n');
+    Ok(output)
+}
+
+fn binding_json(module: &naga::Module, binding: &wgsl::GroupBinding) -> Json {
+    let kind = match (binding.storage_class, &binding.binding_type.inner) {
+        (naga::StorageClass::Uniform, _) => "uniform_buffer",
+        (naga::StorageClass::Storage { access }, _) => {
+            if access.contains(naga::StorageAccess::STORE) {
+                "storage_buffer"
+            } else {
+                "read_only_storage_buffer"
+            }
+        }
+        (
+            _,
+            naga::TypeInner::Image {
+                class: naga::ImageClass::Storage { .. },
+                ..
+            },
+        ) => "storage_texture",
+        (_, naga::TypeInner::Image { .. }) => "texture",
+        (_, naga::TypeInner::Sampler { comparison: true }) => "comparison_sampler",
+        (_, naga::TypeInner::Sampler { comparison: false }) => "sampler",
+        _ => "unknown",
+    };
+
+    let visibility = [
+        naga::ShaderStage::Vertex,
+        naga::ShaderStage::Fragment,
+        naga::ShaderStage::Compute,
+    ]
+    .into_iter()
+    .filter(|stage| binding.visibility.contains(wgsl::shader_stage(*stage)))
+    .map(|stage| Json::String(wgsl::shader_stage_name(stage).to_string()))
+    .collect();
+
+    let mut fields = vec![
+        ("binding", Json::Number(binding.binding_index as u64)),
+        (
+            "name",
+            Json::String(binding.name.clone().unwrap_or_default()),
+        ),
+        ("kind", Json::String(kind.to_string())),
+        (
+            "type",
+            Json::String(wgsl::wgsl_type_name(module, binding.binding_type)),
+        ),
+        ("visibility", Json::Array(visibility)),
+    ];
+    if let Some(size) = binding.min_binding_size {
+        fields.push(("min_binding_size", Json::Number(size)));
+    }
+    Json::Object(fields)
+}
+
+// A minimal JSON value to avoid depending on serde for a single output format.
+enum Json {
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    // Arrays of numbers and strings stay on one line to keep lists like the visibility readable.
+    fn write(&self, f: &mut String, indent: usize) {
+        match self {
+            Json::Number(n) => write!(f, "{n}").unwrap(),
+            Json::String(s) => write_json_string(f, s),
+            Json::Array(values) if values.iter().all(Json::is_scalar) => {
+                f.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.push_str(", ");
+                    }
+                    value.write(f, indent);
+                }
+                f.push(']');
+            }
+            Json::Array(values) => {
+                f.push_str("[\n");
+                for (i, value) in values.iter().enumerate() {
+                    f.push_str(&"  ".repeat(indent + 1));
+                    value.write(f, indent + 1);
+                    f.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                write!(f, "{}]", "  ".repeat(indent)).unwrap();
+            }
+            Json::Object(fields) => {
+                f.push_str("{\n");
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    write!(f, "{}\"{name}\": ", "  ".repeat(indent + 1)).unwrap();
+                    value.write(f, indent + 1);
+                    f.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                write!(f, "{}}}", "  ".repeat(indent)).unwrap();
+            }
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        matches!(self, Json::Number(_) | Json::String(_))
+    }
+}
+
+fn write_json_string(f: &mut String, s: &str) {
+    f.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => f.push_str("\\\""),
+            '\\' => f.push_str("\\\\"),
+            '\n' => f.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32).unwrap(),
+            c => f.push(c),
+        }
+    }
+    f.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn create_reflection_json_render_shader() {
+        let source = indoc! {r#"
+            struct Camera {
+                view_proj: mat4x4<f32>;
+                position: vec3<f32>;
+                scale: f32;
+            };
+
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+                [[location(1)]] uv: vec2<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+            [[group(1), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(1), binding(1)]]
+            var color_sampler: sampler;
+
+            [[stage(vertex)]]
+            fn vs_main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return camera.view_proj * vec4<f32>(in.position, 1.0);
+            }
+
+            [[stage(fragment)]]
+            fn fs_main() -> [[location(0)]] vec4<f32> {
+                return textureSample(color_texture, color_sampler, vec2<f32>(0.0));
+            }
+        "#};
+
+        let options = WriterOptions {
+            instance_input_structs: vec!["VertexInput".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            indoc! {r#"
+                {
+                  "bind_groups": [
+                    {
+                      "group": 0,
+                      "bindings": [
+                        {
+                          "binding": 0,
+                          "name": "camera",
+                          "kind": "uniform_buffer",
+                          "type": "Camera",
+                          "visibility": ["vertex"],
+                          "min_binding_size": 80
+                        }
+                      ]
+                    },
+                    {
+                      "group": 1,
+                      "bindings": [
+                        {
+                          "binding": 0,
+                          "name": "color_texture",
+                          "kind": "texture",
+                          "type": "texture_2d<f32>",
+                          "visibility": ["fragment"]
+                        },
+                        {
+                          "binding": 1,
+                          "name": "color_sampler",
+                          "kind": "sampler",
+                          "type": "sampler",
+                          "visibility": ["fragment"]
+                        }
+                      ]
+                    }
+                  ],
+                  "vertex_inputs": [
+                    {
+                      "name": "VertexInput",
+                      "step_mode": "instance",
+                      "attributes": [
+                        {
+                          "name": "position",
+                          "location": 0,
+                          "format": "Float32x3"
+                        },
+                        {
+                          "name": "uv",
+                          "location": 1,
+                          "format": "Float32x2"
+                        }
+                      ]
+                    }
+                  ],
+                  "entry_points": [
+                    {
+                      "name": "fs_main",
+                      "stage": "fragment"
+                    },
+                    {
+                      "name": "vs_main",
+                      "stage": "vertex"
+                    }
+                  ],
+                  "structs": [
+                    {
+                      "name": "Camera",
+                      "size": 80,
+                      "members": [
+                        {
+                          "name": "view_proj",
+                          "type": "mat4x4<f32>",
+                          "offset": 0,
+                          "size": 64
+                        },
+                        {
+                          "name": "position",
+                          "type": "vec3<f32>",
+                          "offset": 64,
+                          "size": 12
+                        },
+                        {
+                          "name": "scale",
+                          "type": "f32",
+                          "offset": 76,
+                          "size": 4
+                        }
+                      ]
+                    },
+                    {
+                      "name": "VertexInput",
+                      "size": 32,
+                      "members": [
+                        {
+                          "name": "position",
+                          "type": "vec3<f32>",
+                          "offset": 0,
+                          "size": 12
+                        },
+                        {
+                          "name": "uv",
+                          "type": "vec2<f32>",
+                          "offset": 16,
+                          "size": 8
+                        }
+                      ]
+                    }
+                  ]
+                }
+            "#},
+            create_reflection_json(source, options).unwrap()
+        );
+    }
+
+    #[test]
+    fn create_reflection_json_compute_shader() {
+        let source = indoc! {r#"
+            struct Particles {
+                positions: array<vec4<f32>>;
+            };
+
+            [[group(0), binding(0)]]
+            var<storage, read_write> particles: Particles;
+
+            [[stage(compute), workgroup_size(64)]]
+            fn main() {
+                particles.positions[0] = vec4<f32>(0.0);
+            }
+        "#};
+
+        let actual = create_reflection_json(source, WriterOptions::default()).unwrap();
+        assert!(actual.contains(r#""kind": "storage_buffer","#));
+        assert!(actual.contains(r#""visibility": ["compute"]"#));
+        assert!(actual.contains(r#""workgroup_size": [64, 1, 1]"#));
+    }
+
+    #[test]
+    fn write_json_string_escapes() {
+        let mut actual = String::new();
+        write_json_string(&mut actual, "a\"b\\c\nd\t");
+        assert_eq!(r#""a\"b\\c\nd\u0009""#, actual);
+    }
+}
@@ -0,0 +1,100 @@
+use std::ops::ControlFlow;
+use std::time::{Duration, SystemTime};
+
+use crate::{ConfigError, ShaderConfig};
+
+/// Regenerate each shader whenever the modification time of its input file changes.
+///
+/// All shaders are generated once at the start, and the inputs are checked again after each `interval`.
+/// The `on_generate` function receives the result for each generated shader,
+/// so errors like invalid WGSL can be reported without stopping the watch.
+/// Watching continues until `on_generate` returns [ControlFlow::Break].
+///
+/// # Examples
+/**
+```rust no_run
+use std::ops::ControlFlow;
+
+let shaders = wgsl_to_wgpu::read_config("wgsl_to_wgpu.toml").unwrap();
+wgsl_to_wgpu::watch(&shaders, std::time::Duration::from_millis(500), |shader, result| {
+    match result {
+        Ok(()) => println!("generated {}", shader.output.display()),
+        Err(e) => eprintln!("error: {e}"),
+    }
+    ControlFlow::Continue(())
+});
+```
+ */
+pub fn watch<F>(shaders: &[ShaderConfig], interval: Duration, mut on_generate: F)
+where
+    F: FnMut(&ShaderConfig, Result<(), ConfigError>) -> ControlFlow<()>,
+{
+    // Start with unknown times to generate everything on the first check.
+    let mut modified_times: Vec<Option<SystemTime>> = vec![None; shaders.len()];
+    let mut is_first_check = true;
+    loop {
+        for (shader, last_modified) in shaders.iter().zip(&mut modified_times) {
+            let modified = std::fs::metadata(&shader.input)
+                .and_then(|m| m.modified())
+                .ok();
+            if is_first_check || modified != *last_modified {
+                *last_modified = modified;
+                if on_generate(shader, shader.generate()).is_break() {
+                    return;
+                }
+            }
+        }
+        is_first_check = false;
+
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn watch_generates_and_reports_errors() {
+        let dir = std::env::temp_dir().join(format!("wgsl_to_wgpu_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("shader.wgsl"), "let A: f32 = 1.0;\n").unwrap();
+
+        let shaders = vec![
+            ShaderConfig {
+                input: dir.join("shader.wgsl"),
+                output: dir.join("shader.rs"),
+                options: Default::default(),
+            },
+            ShaderConfig {
+                input: dir.join("missing.wgsl"),
+                output: dir.join("missing.rs"),
+                options: Default::default(),
+            },
+        ];
+
+        let mut generated = Vec::new();
+        watch(&shaders, Duration::ZERO, |shader, result| {
+            generated.push((shader.output.clone(), result.is_ok()));
+            if generated.len() == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+        assert_eq!(
+            vec![
+                (dir.join("shader.rs"), true),
+                (dir.join("missing.rs"), false)
+            ],
+            generated
+        );
+        assert!(std::fs::read_to_string(dir.join("shader.rs"))
+            .unwrap()
+            .contains("include_str!(\"shader.wgsl\")"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
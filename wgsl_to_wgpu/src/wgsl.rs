@@ -1,7 +1,7 @@
 use naga::StructMember;
-use std::collections::{BTreeMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use crate::CreateModuleError;
+use crate::{CreateModuleError, MatrixVectorTypes, SourceLocation};
 
 pub struct GroupData<'a> {
     pub bindings: Vec<GroupBinding<'a>>,
@@ -12,71 +12,190 @@ pub struct GroupBinding<'a> {
     pub binding_index: u32,
     pub binding_type: &'a naga::Type,
     pub storage_class: naga::StorageClass,
+    pub visibility: wgpu::ShaderStages,
+    pub location: Option<SourceLocation>,
+    pub min_binding_size: Option<u64>,
 }
 
 // TODO: Improve error handling/error reporting.
 pub fn shader_stages(module: &naga::Module) -> wgpu::ShaderStages {
     let mut shader_stages = wgpu::ShaderStages::NONE;
     for entry in &module.entry_points {
-        match entry.stage {
-            naga::ShaderStage::Vertex => shader_stages.insert(wgpu::ShaderStages::VERTEX),
-            naga::ShaderStage::Fragment => shader_stages.insert(wgpu::ShaderStages::FRAGMENT),
-            naga::ShaderStage::Compute => shader_stages.insert(wgpu::ShaderStages::COMPUTE),
-        }
+        shader_stages.insert(shader_stage(entry.stage));
     }
     shader_stages
 }
 
+pub fn shader_stage(stage: naga::ShaderStage) -> wgpu::ShaderStages {
+    match stage {
+        naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+        naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+        naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+    }
+}
+
+pub fn validate(
+    module: &naga::Module,
+) -> Result<naga::valid::ModuleInfo, naga::WithSpan<naga::valid::ValidationError>> {
+    // Capabilities depend on the device, so leave checking features and limits to wgpu.
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(module)
+}
+
+// Find the stages of the entry points that access the global.
+fn binding_visibility(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+    handle: naga::Handle<naga::GlobalVariable>,
+) -> wgpu::ShaderStages {
+    let mut stages = wgpu::ShaderStages::NONE;
+    for (i, entry) in module.entry_points.iter().enumerate() {
+        if !info.get_entry_point(i)[handle].is_empty() {
+            stages.insert(shader_stage(entry.stage));
+        }
+    }
+
+    // Unused bindings are still part of the layout, so keep them visible to the module's stages.
+    if stages.is_empty() {
+        shader_stages(module)
+    } else {
+        stages
+    }
+}
+
 fn rust_scalar_type(kind: naga::ScalarKind, width: u8) -> String {
     // TODO: Support other widths?
+    // The 64-bit types have the same 8 byte alignment in Rust and WGSL.
     match (kind, width) {
         (naga::ScalarKind::Sint, 4) => "i32".to_string(),
         (naga::ScalarKind::Uint, 4) => "u32".to_string(),
         (naga::ScalarKind::Float, 4) => "f32".to_string(),
+        (naga::ScalarKind::Sint, 8) => "i64".to_string(),
+        (naga::ScalarKind::Uint, 8) => "u64".to_string(),
+        (naga::ScalarKind::Float, 8) => "f64".to_string(),
         // TODO: Do booleans have a width?
         (naga::ScalarKind::Bool, _) => "bool".to_string(),
         _ => todo!(),
     }
 }
 
-pub fn buffer_binding_type(storage: naga::StorageClass) -> String {
+// The Rust type and value for scalar constants like `let MAX_LIGHTS: u32 = 16u;`.
+pub fn rust_constant(constant: &naga::Constant) -> Option<(String, String)> {
+    match constant.inner {
+        naga::ConstantInner::Scalar { width, value } => {
+            let (kind, value) = match value {
+                naga::ScalarValue::Sint(v) => (naga::ScalarKind::Sint, v.to_string()),
+                naga::ScalarValue::Uint(v) => (naga::ScalarKind::Uint, v.to_string()),
+                // Use the shortest representation that round trips for the constant's width.
+                naga::ScalarValue::Float(v) if width == 4 => {
+                    (naga::ScalarKind::Float, format!("{:?}", v as f32))
+                }
+                naga::ScalarValue::Float(v) => (naga::ScalarKind::Float, format!("{v:?}")),
+                naga::ScalarValue::Bool(v) => (naga::ScalarKind::Bool, v.to_string()),
+            };
+            Some((rust_scalar_type(kind, width), value))
+        }
+        // TODO: Support vector and array constants?
+        naga::ConstantInner::Composite { .. } => None,
+    }
+}
+
+pub fn buffer_binding_type(storage: naga::StorageClass, wgpu: &str) -> String {
     match storage {
-        naga::StorageClass::Uniform => "wgpu::BufferBindingType::Uniform".to_string(),
+        naga::StorageClass::Uniform => format!("{wgpu}::BufferBindingType::Uniform"),
         naga::StorageClass::Storage { access } => {
             let _is_read = access.contains(naga::StorageAccess::LOAD);
             let is_write = access.contains(naga::StorageAccess::STORE);
 
             // TODO: Is this correct?
             if is_write {
-                "wgpu::BufferBindingType::Storage { read_only: false }".to_string()
+                format!("{wgpu}::BufferBindingType::Storage {{ read_only: false }}")
             } else {
-                "wgpu::BufferBindingType::Storage { read_only: true }".to_string()
+                format!("{wgpu}::BufferBindingType::Storage {{ read_only: true }}")
             }
         }
         _ => todo!(),
     }
 }
 
-pub fn rust_type(module: &naga::Module, ty: &naga::Type) -> String {
+fn rust_vector_type(
+    size: naga::VectorSize,
+    kind: naga::ScalarKind,
+    width: u8,
+    types: MatrixVectorTypes,
+    glam: &str,
+) -> String {
+    let n = size as u8;
+    let scalar = rust_scalar_type(kind, width);
+    match (types, kind, width) {
+        (MatrixVectorTypes::Glam, naga::ScalarKind::Float, 4) => format!("{glam}::Vec{n}"),
+        (MatrixVectorTypes::Glam, naga::ScalarKind::Float, 8) => format!("{glam}::DVec{n}"),
+        (MatrixVectorTypes::Glam, naga::ScalarKind::Sint, 4) => format!("{glam}::IVec{n}"),
+        (MatrixVectorTypes::Glam, naga::ScalarKind::Uint, 4) => format!("{glam}::UVec{n}"),
+        (MatrixVectorTypes::Nalgebra, _, _) => format!("nalgebra::SVector<{scalar}, {n}>"),
+        (MatrixVectorTypes::Cgmath, _, _) => format!("cgmath::Vector{n}<{scalar}>"),
+        (MatrixVectorTypes::Mint, _, _) => format!("mint::Vector{n}<{scalar}>"),
+        _ => format!("[{scalar}; {n}]"),
+    }
+}
+
+fn rust_matrix_type(
+    rows: naga::VectorSize,
+    columns: naga::VectorSize,
+    width: u8,
+    types: MatrixVectorTypes,
+    glam: &str,
+) -> String {
+    let scalar = rust_scalar_type(naga::ScalarKind::Float, width);
+    let (r, c) = (rows as u8, columns as u8);
+    match (types, rows, columns) {
+        // Columns with three rows have the same size and alignment as vec4,
+        // which doesn't match the unpadded library types.
+        (_, naga::VectorSize::Tri, _) | (MatrixVectorTypes::Rust, _, _) => {
+            let r = if rows == naga::VectorSize::Tri { 4 } else { r };
+            format!("[[{scalar}; {r}]; {c}]")
+        }
+        (MatrixVectorTypes::Glam | MatrixVectorTypes::GlamMatrices, _, _) if rows == columns => {
+            let prefix = if width == 8 { "D" } else { "" };
+            format!("{glam}::{prefix}Mat{r}")
+        }
+        (MatrixVectorTypes::Nalgebra, _, _) => format!("nalgebra::SMatrix<{scalar}, {r}, {c}>"),
+        (MatrixVectorTypes::Cgmath, _, _) if rows == columns => {
+            format!("cgmath::Matrix{r}<{scalar}>")
+        }
+        (MatrixVectorTypes::Mint, _, _) if rows == columns => {
+            format!("mint::ColumnMatrix{r}<{scalar}>")
+        }
+        (MatrixVectorTypes::Mint, _, _) => format!("mint::ColumnMatrix{r}x{c}<{scalar}>"),
+        // glam and cgmath only have square matrices.
+        _ => format!("[[{scalar}; {r}]; {c}]"),
+    }
+}
+
+pub fn rust_type(
+    module: &naga::Module,
+    ty: &naga::Type,
+    types: MatrixVectorTypes,
+    glam: &str,
+) -> String {
     match &ty.inner {
         naga::TypeInner::Scalar { kind, width } => rust_scalar_type(*kind, *width),
-        naga::TypeInner::Vector { size, kind, width } => match size {
-            naga::VectorSize::Bi => format!("[{}; 2]", rust_scalar_type(*kind, *width)),
-            naga::VectorSize::Tri => format!("[{}; 3]", rust_scalar_type(*kind, *width)),
-            naga::VectorSize::Quad => format!("[{}; 4]", rust_scalar_type(*kind, *width)),
-        },
+        naga::TypeInner::Vector { size, kind, width } => {
+            rust_vector_type(*size, *kind, *width, types, glam)
+        }
         naga::TypeInner::Matrix {
             columns,
             rows,
             width,
-        } => match (rows, columns, width) {
-            // TODO: Don't force glam here?
-            (naga::VectorSize::Quad, naga::VectorSize::Quad, 4) => "glam::Mat4".to_string(),
-            _ => todo!(),
-        },
+        } => rust_matrix_type(*rows, *columns, *width, types, glam),
         naga::TypeInner::Image { .. } => todo!(),
         naga::TypeInner::Sampler { .. } => todo!(),
-        naga::TypeInner::Atomic { kind: _, width: _ } => todo!(),
+        // Atomics have the same layout as the underlying scalar.
+        // Atomic operations only apply on the GPU, so plain integers can initialize and read back buffers.
+        naga::TypeInner::Atomic { kind, width } => rust_scalar_type(*kind, *width),
         naga::TypeInner::Pointer { base: _, class: _ } => todo!(),
         naga::TypeInner::ValuePointer {
             size: _,
@@ -84,41 +203,263 @@ pub fn rust_type(module: &naga::Module, ty: &naga::Type) -> String {
             width: _,
             class: _,
         } => todo!(),
-        naga::TypeInner::Array { base, size, stride: _ } => {
+        naga::TypeInner::Array { base, size, stride } => {
             // TODO: Support arrays other than arrays with a static size?
-            let element_type = rust_type(module, &module.types[*base]);
+            let element = &module.types[*base];
+            let element_type = match element.inner {
+                // Pad elements like vec3<f32> to the array stride of 16 bytes.
+                // Matrices and structs are already padded to their stride.
+                naga::TypeInner::Scalar { kind, width }
+                | naga::TypeInner::Vector { kind, width, .. }
+                    if *stride > element.inner.span(&module.constants) =>
+                {
+                    let scalar = rust_scalar_type(kind, width);
+                    format!("[{scalar}; {}]", stride / width as u32)
+                }
+                _ => rust_type(module, element, types, glam),
+            };
             let count = array_length(size, module);
             format!("[{element_type}; {count}]")
         }
         naga::TypeInner::Struct { members: _, span: _ } => {
-            // TODO: Support structs?
-            ty.name.as_ref().unwrap().to_string()
+            // Nested structs use the Rust struct generated for the WGSL struct.
+            crate::rust_ident(ty.name.as_ref().unwrap())
         }
     }
 }
 
-pub fn vertex_format(ty: &naga::Type) -> wgpu::VertexFormat {
-    // Not all wgsl types work as vertex attributes in wgpu.
+// Arrays only implement Default for up to 32 elements, so build arrays from their elements instead.
+pub fn rust_default_value(module: &naga::Module, ty: &naga::Type) -> String {
     match &ty.inner {
-        naga::TypeInner::Scalar { kind: _, width: _ } => todo!(),
-        naga::TypeInner::Vector { size, kind, width } => match size {
-            naga::VectorSize::Bi => match (kind, width) {
-                (naga::ScalarKind::Uint, 4) => wgpu::VertexFormat::Uint32x2,
-                (naga::ScalarKind::Float, 4) => wgpu::VertexFormat::Float32x2,
-                _ => todo!(),
-            },
-            naga::VectorSize::Tri => match (kind, width) {
-                (naga::ScalarKind::Uint, 4) => wgpu::VertexFormat::Uint32x3,
-                (naga::ScalarKind::Float, 4) => wgpu::VertexFormat::Float32x3,
-                _ => todo!(),
-            },
-            naga::VectorSize::Quad => match (kind, width) {
-                (naga::ScalarKind::Uint, 4) => wgpu::VertexFormat::Uint32x4,
-                (naga::ScalarKind::Float, 4) => wgpu::VertexFormat::Float32x4,
-                _ => todo!(),
-            },
-        },
-        _ => todo!(), // are these types even valid as attributes?
+        naga::TypeInner::Array { base, size, .. } => {
+            let element = rust_default_value(module, &module.types[*base]);
+            let count = array_length(size, module);
+            format!("[{element}; {count}]")
+        }
+        _ => "Default::default()".to_string(),
+    }
+}
+
+pub struct FragmentOutput {
+    pub location: u32,
+    pub kind: naga::ScalarKind,
+    pub components: u32,
+}
+
+// Find the color outputs of a fragment entry point sorted by location.
+pub fn fragment_outputs(module: &naga::Module, entry: &naga::EntryPoint) -> Vec<FragmentOutput> {
+    let mut outputs = Vec::new();
+    if let Some(result) = &entry.function.result {
+        match (&result.binding, &module.types[result.ty].inner) {
+            (Some(binding), inner) => add_fragment_output(&mut outputs, binding, inner),
+            (None, naga::TypeInner::Struct { members, .. }) => {
+                for member in members {
+                    if let Some(binding) = &member.binding {
+                        add_fragment_output(&mut outputs, binding, &module.types[member.ty].inner);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    outputs.sort_by_key(|output| output.location);
+    outputs
+}
+
+fn add_fragment_output(
+    outputs: &mut Vec<FragmentOutput>,
+    binding: &naga::Binding,
+    inner: &naga::TypeInner,
+) {
+    // Builtins like frag_depth don't correspond to a color target.
+    if let naga::Binding::Location { location, .. } = binding {
+        let (kind, components) = match inner {
+            naga::TypeInner::Scalar { kind, .. } => (*kind, 1),
+            naga::TypeInner::Vector { size, kind, .. } => (*kind, *size as u32),
+            _ => return,
+        };
+        outputs.push(FragmentOutput {
+            location: *location,
+            kind,
+            components,
+        });
+    }
+}
+
+// Use arrays of the components since Rust doesn't have types for normalized or half precision values.
+pub fn vertex_format_rust_type(format: wgpu::VertexFormat) -> String {
+    let (component, count) = match format {
+        wgpu::VertexFormat::Uint8x2 | wgpu::VertexFormat::Unorm8x2 => ("u8", 2),
+        wgpu::VertexFormat::Uint8x4 | wgpu::VertexFormat::Unorm8x4 => ("u8", 4),
+        wgpu::VertexFormat::Sint8x2 | wgpu::VertexFormat::Snorm8x2 => ("i8", 2),
+        wgpu::VertexFormat::Sint8x4 | wgpu::VertexFormat::Snorm8x4 => ("i8", 4),
+        wgpu::VertexFormat::Uint16x2
+        | wgpu::VertexFormat::Unorm16x2
+        | wgpu::VertexFormat::Float16x2 => ("u16", 2),
+        wgpu::VertexFormat::Uint16x4
+        | wgpu::VertexFormat::Unorm16x4
+        | wgpu::VertexFormat::Float16x4 => ("u16", 4),
+        wgpu::VertexFormat::Sint16x2 | wgpu::VertexFormat::Snorm16x2 => ("i16", 2),
+        wgpu::VertexFormat::Sint16x4 | wgpu::VertexFormat::Snorm16x4 => ("i16", 4),
+        wgpu::VertexFormat::Float32 => ("f32", 1),
+        wgpu::VertexFormat::Float32x2 => ("f32", 2),
+        wgpu::VertexFormat::Float32x3 => ("f32", 3),
+        wgpu::VertexFormat::Float32x4 => ("f32", 4),
+        wgpu::VertexFormat::Uint32 => ("u32", 1),
+        wgpu::VertexFormat::Uint32x2 => ("u32", 2),
+        wgpu::VertexFormat::Uint32x3 => ("u32", 3),
+        wgpu::VertexFormat::Uint32x4 => ("u32", 4),
+        wgpu::VertexFormat::Sint32 => ("i32", 1),
+        wgpu::VertexFormat::Sint32x2 => ("i32", 2),
+        wgpu::VertexFormat::Sint32x3 => ("i32", 3),
+        wgpu::VertexFormat::Sint32x4 => ("i32", 4),
+        wgpu::VertexFormat::Float64 => ("f64", 1),
+        wgpu::VertexFormat::Float64x2 => ("f64", 2),
+        wgpu::VertexFormat::Float64x3 => ("f64", 3),
+        wgpu::VertexFormat::Float64x4 => ("f64", 4),
+    };
+    if count == 1 {
+        component.to_string()
+    } else {
+        format!("[{component}; {count}]")
+    }
+}
+
+// Not all wgsl types work as vertex attributes in wgpu.
+pub fn vertex_format(ty: &naga::Type) -> Option<wgpu::VertexFormat> {
+    let (kind, width, components) = match &ty.inner {
+        naga::TypeInner::Scalar { kind, width } => (*kind, *width, 1),
+        naga::TypeInner::Vector { size, kind, width } => (*kind, *width, *size as u32),
+        _ => return None,
+    };
+    let format = match (kind, width, components) {
+        (naga::ScalarKind::Float, 4, 1) => wgpu::VertexFormat::Float32,
+        (naga::ScalarKind::Float, 4, 2) => wgpu::VertexFormat::Float32x2,
+        (naga::ScalarKind::Float, 4, 3) => wgpu::VertexFormat::Float32x3,
+        (naga::ScalarKind::Float, 4, 4) => wgpu::VertexFormat::Float32x4,
+        (naga::ScalarKind::Float, 8, 1) => wgpu::VertexFormat::Float64,
+        (naga::ScalarKind::Float, 8, 2) => wgpu::VertexFormat::Float64x2,
+        (naga::ScalarKind::Float, 8, 3) => wgpu::VertexFormat::Float64x3,
+        (naga::ScalarKind::Float, 8, 4) => wgpu::VertexFormat::Float64x4,
+        (naga::ScalarKind::Uint, 4, 1) => wgpu::VertexFormat::Uint32,
+        (naga::ScalarKind::Uint, 4, 2) => wgpu::VertexFormat::Uint32x2,
+        (naga::ScalarKind::Uint, 4, 3) => wgpu::VertexFormat::Uint32x3,
+        (naga::ScalarKind::Uint, 4, 4) => wgpu::VertexFormat::Uint32x4,
+        (naga::ScalarKind::Sint, 4, 1) => wgpu::VertexFormat::Sint32,
+        (naga::ScalarKind::Sint, 4, 2) => wgpu::VertexFormat::Sint32x2,
+        (naga::ScalarKind::Sint, 4, 3) => wgpu::VertexFormat::Sint32x3,
+        (naga::ScalarKind::Sint, 4, 4) => wgpu::VertexFormat::Sint32x4,
+        _ => return None,
+    };
+    Some(format)
+}
+
+// The vertex format for a field using the override if present.
+pub fn vertex_field_format(
+    module: &naga::Module,
+    member: &StructMember,
+    overrides: &HashMap<String, wgpu::VertexFormat>,
+) -> Option<wgpu::VertexFormat> {
+    member
+        .name
+        .as_ref()
+        .and_then(|name| overrides.get(name))
+        .copied()
+        .or_else(|| vertex_format(&module.types[member.ty]))
+}
+
+// Vertex inputs must have a format unless the format is overridden.
+pub fn check_vertex_inputs(
+    module: &naga::Module,
+    source: &str,
+    overrides: &HashMap<String, wgpu::VertexFormat>,
+) -> Result<(), CreateModuleError> {
+    for input in get_vertex_input_structs(module) {
+        for (_, member) in &input.fields {
+            if vertex_field_format(module, member, overrides).is_none() {
+                // Arguments aren't part of a struct definition in the WGSL source.
+                let location = module
+                    .types
+                    .iter()
+                    .find(|(_, ty)| !input.is_synthesized && ty.name.as_ref() == Some(&input.name))
+                    .and_then(|(handle, _)| source_location(source, module.types.get_span(handle)));
+                return Err(CreateModuleError::UnsupportedVertexInput {
+                    name: input.name,
+                    field: member.name.clone().unwrap_or_default(),
+                    ty: wgsl_type_name(module, &module.types[member.ty]),
+                    location,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn texture_view_dimension(
+    dim: naga::ImageDimension,
+    arrayed: bool,
+) -> wgpu::TextureViewDimension {
+    match (dim, arrayed) {
+        (naga::ImageDimension::D1, _) => wgpu::TextureViewDimension::D1,
+        (naga::ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+        (naga::ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+        (naga::ImageDimension::D3, _) => wgpu::TextureViewDimension::D3,
+        (naga::ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+        (naga::ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray,
+    }
+}
+
+pub fn is_multisampled(class: naga::ImageClass) -> bool {
+    matches!(
+        class,
+        naga::ImageClass::Sampled { multi: true, .. } | naga::ImageClass::Depth { multi: true }
+    )
+}
+
+pub fn storage_texture_format(format: naga::StorageFormat) -> wgpu::TextureFormat {
+    match format {
+        naga::StorageFormat::R8Unorm => wgpu::TextureFormat::R8Unorm,
+        naga::StorageFormat::R8Snorm => wgpu::TextureFormat::R8Snorm,
+        naga::StorageFormat::R8Uint => wgpu::TextureFormat::R8Uint,
+        naga::StorageFormat::R8Sint => wgpu::TextureFormat::R8Sint,
+        naga::StorageFormat::R16Uint => wgpu::TextureFormat::R16Uint,
+        naga::StorageFormat::R16Sint => wgpu::TextureFormat::R16Sint,
+        naga::StorageFormat::R16Float => wgpu::TextureFormat::R16Float,
+        naga::StorageFormat::Rg8Unorm => wgpu::TextureFormat::Rg8Unorm,
+        naga::StorageFormat::Rg8Snorm => wgpu::TextureFormat::Rg8Snorm,
+        naga::StorageFormat::Rg8Uint => wgpu::TextureFormat::Rg8Uint,
+        naga::StorageFormat::Rg8Sint => wgpu::TextureFormat::Rg8Sint,
+        naga::StorageFormat::R32Uint => wgpu::TextureFormat::R32Uint,
+        naga::StorageFormat::R32Sint => wgpu::TextureFormat::R32Sint,
+        naga::StorageFormat::R32Float => wgpu::TextureFormat::R32Float,
+        naga::StorageFormat::Rg16Uint => wgpu::TextureFormat::Rg16Uint,
+        naga::StorageFormat::Rg16Sint => wgpu::TextureFormat::Rg16Sint,
+        naga::StorageFormat::Rg16Float => wgpu::TextureFormat::Rg16Float,
+        naga::StorageFormat::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm,
+        naga::StorageFormat::Rgba8Snorm => wgpu::TextureFormat::Rgba8Snorm,
+        naga::StorageFormat::Rgba8Uint => wgpu::TextureFormat::Rgba8Uint,
+        naga::StorageFormat::Rgba8Sint => wgpu::TextureFormat::Rgba8Sint,
+        naga::StorageFormat::Rgb10a2Unorm => wgpu::TextureFormat::Rgb10a2Unorm,
+        naga::StorageFormat::Rg11b10Float => wgpu::TextureFormat::Rg11b10Float,
+        naga::StorageFormat::Rg32Uint => wgpu::TextureFormat::Rg32Uint,
+        naga::StorageFormat::Rg32Sint => wgpu::TextureFormat::Rg32Sint,
+        naga::StorageFormat::Rg32Float => wgpu::TextureFormat::Rg32Float,
+        naga::StorageFormat::Rgba16Uint => wgpu::TextureFormat::Rgba16Uint,
+        naga::StorageFormat::Rgba16Sint => wgpu::TextureFormat::Rgba16Sint,
+        naga::StorageFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+        naga::StorageFormat::Rgba32Uint => wgpu::TextureFormat::Rgba32Uint,
+        naga::StorageFormat::Rgba32Sint => wgpu::TextureFormat::Rgba32Sint,
+        naga::StorageFormat::Rgba32Float => wgpu::TextureFormat::Rgba32Float,
+    }
+}
+
+pub fn storage_texture_access(access: naga::StorageAccess) -> wgpu::StorageTextureAccess {
+    let is_read = access.contains(naga::StorageAccess::LOAD);
+    let is_write = access.contains(naga::StorageAccess::STORE);
+    match (is_read, is_write) {
+        (true, true) => wgpu::StorageTextureAccess::ReadWrite,
+        (false, true) => wgpu::StorageTextureAccess::WriteOnly,
+        _ => wgpu::StorageTextureAccess::ReadOnly,
     }
 }
 
@@ -137,7 +478,399 @@ fn array_length(size: &naga::ArraySize, module: &naga::Module) -> usize {
     }
 }
 
-pub fn get_bind_group_data(module: &naga::Module) -> Result<BTreeMap<u32, GroupData>, CreateModuleError> {
+// Runtime sized arrays don't have a fixed size to validate against.
+// Empty structs also don't have a valid minimum size.
+fn min_binding_size(module: &naga::Module, ty: &naga::Type) -> Option<u64> {
+    let is_dynamic = |ty: &naga::Type| {
+        matches!(
+            ty.inner,
+            naga::TypeInner::Array {
+                size: naga::ArraySize::Dynamic,
+                ..
+            }
+        )
+    };
+    let is_runtime_sized = match &ty.inner {
+        naga::TypeInner::Struct { members, .. } => members
+            .last()
+            .map(|m| is_dynamic(&module.types[m.ty]))
+            .unwrap_or(false),
+        _ => is_dynamic(ty),
+    };
+
+    if is_runtime_sized {
+        None
+    } else {
+        Some(ty.inner.span(&module.constants) as u64).filter(|size| *size > 0)
+    }
+}
+
+// Only these types have a corresponding binding type in the generated code.
+// The current version of naga only accepts structs for uniform and storage buffers.
+fn is_supported_binding_type(ty: &naga::Type, storage_class: naga::StorageClass) -> bool {
+    match storage_class {
+        naga::StorageClass::Uniform | naga::StorageClass::Storage { .. } => {
+            matches!(ty.inner, naga::TypeInner::Struct { .. })
+        }
+        _ => matches!(
+            ty.inner,
+            naga::TypeInner::Image { .. } | naga::TypeInner::Sampler { .. }
+        ),
+    }
+}
+
+// Uniform and storage globals are bound as buffers.
+pub fn is_buffer_binding(binding: &GroupBinding) -> bool {
+    matches!(
+        binding.storage_class,
+        naga::StorageClass::Uniform | naga::StorageClass::Storage { .. }
+    )
+}
+
+fn wgsl_scalar_name(kind: naga::ScalarKind, width: u8) -> String {
+    match kind {
+        naga::ScalarKind::Sint => format!("i{}", width * 8),
+        naga::ScalarKind::Uint => format!("u{}", width * 8),
+        naga::ScalarKind::Float => format!("f{}", width * 8),
+        naga::ScalarKind::Bool => "bool".to_string(),
+    }
+}
+
+// A human readable type name for error messages.
+pub fn wgsl_type_name(module: &naga::Module, ty: &naga::Type) -> String {
+    if let Some(name) = &ty.name {
+        return name.clone();
+    }
+
+    match &ty.inner {
+        naga::TypeInner::Scalar { kind, width } => wgsl_scalar_name(*kind, *width),
+        naga::TypeInner::Vector { size, kind, width } => {
+            format!("vec{}<{}>", *size as u8, wgsl_scalar_name(*kind, *width))
+        }
+        naga::TypeInner::Matrix {
+            columns,
+            rows,
+            width,
+        } => format!(
+            "mat{}x{}<{}>",
+            *columns as u8,
+            *rows as u8,
+            wgsl_scalar_name(naga::ScalarKind::Float, *width)
+        ),
+        naga::TypeInner::Atomic { kind, width } => {
+            format!("atomic<{}>", wgsl_scalar_name(*kind, *width))
+        }
+        naga::TypeInner::Array { base, size, .. } => {
+            let base = wgsl_type_name(module, &module.types[*base]);
+            match size {
+                naga::ArraySize::Constant(_) => {
+                    format!("array<{base}, {}>", array_length(size, module))
+                }
+                naga::ArraySize::Dynamic => format!("array<{base}>"),
+            }
+        }
+        naga::TypeInner::Image {
+            dim,
+            arrayed,
+            class,
+        } => {
+            let dim = match dim {
+                naga::ImageDimension::D1 => "1d",
+                naga::ImageDimension::D2 => "2d",
+                naga::ImageDimension::D3 => "3d",
+                naga::ImageDimension::Cube => "cube",
+            };
+            let array = if *arrayed { "_array" } else { "" };
+            match class {
+                naga::ImageClass::Sampled { kind, multi } => {
+                    let multisampled = if *multi { "multisampled_" } else { "" };
+                    let kind = wgsl_scalar_name(*kind, 4);
+                    format!("texture_{multisampled}{dim}{array}<{kind}>")
+                }
+                naga::ImageClass::Depth { multi } => {
+                    let multisampled = if *multi { "multisampled_" } else { "" };
+                    format!("texture_depth_{multisampled}{dim}{array}")
+                }
+                naga::ImageClass::Storage { format, access } => {
+                    let format = format!("{format:?}").to_lowercase();
+                    let access = wgsl_access_name(*access);
+                    format!("texture_storage_{dim}{array}<{format}, {access}>")
+                }
+            }
+        }
+        naga::TypeInner::Sampler { comparison: false } => "sampler".to_string(),
+        naga::TypeInner::Sampler { comparison: true } => "sampler_comparison".to_string(),
+        inner => format!("{inner:?}"),
+    }
+}
+
+pub fn shader_stage_name(stage: naga::ShaderStage) -> &'static str {
+    match stage {
+        naga::ShaderStage::Vertex => "vertex",
+        naga::ShaderStage::Fragment => "fragment",
+        naga::ShaderStage::Compute => "compute",
+    }
+}
+
+fn wgsl_access_name(access: naga::StorageAccess) -> &'static str {
+    if access.contains(naga::StorageAccess::LOAD | naga::StorageAccess::STORE) {
+        "read_write"
+    } else if access.contains(naga::StorageAccess::STORE) {
+        "write"
+    } else {
+        "read"
+    }
+}
+
+// Describe the binding like its WGSL declaration such as "var<uniform> camera: Camera".
+pub fn wgsl_binding_declaration(module: &naga::Module, binding: &GroupBinding) -> String {
+    let name = binding.name.as_deref().unwrap_or_default();
+    let ty = wgsl_type_name(module, binding.binding_type);
+    match binding.storage_class {
+        naga::StorageClass::Uniform => format!("var<uniform> {name}: {ty}"),
+        naga::StorageClass::Storage { access } => {
+            let access = wgsl_access_name(access);
+            format!("var<storage, {access}> {name}: {ty}")
+        }
+        _ => format!("var {name}: {ty}"),
+    }
+}
+
+// Compile the module like wgpu would compile WGSL instead of converting to Vulkan conventions,
+// since wgpu still translates the SPIR-V for the backend.
+pub fn spirv(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+) -> Result<Vec<u32>, naga::back::spv::Error> {
+    let options = naga::back::spv::Options {
+        flags: naga::back::spv::WriterFlags::empty(),
+        ..Default::default()
+    };
+    naga::back::spv::write_vec(module, info, &options, None)
+}
+
+pub fn wgsl(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+) -> Result<String, naga::back::wgsl::Error> {
+    naga::back::wgsl::write_string(module, info, naga::back::wgsl::WriterFlags::empty())
+}
+
+// Remove comments and whitespace without joining characters into different tokens.
+// Whitespace is only removed entirely next to delimiters, since the lexer treats
+// sequences like "]]" or "-1" differently than "] ]" or "- 1".
+pub fn minify(source: &str) -> String {
+    fn is_delimiter(c: char) -> bool {
+        matches!(c, '(' | ')' | '{' | '}' | ';' | ',' | ':')
+    }
+
+    let mut output = String::new();
+    let mut has_whitespace = false;
+    for line in source.lines() {
+        let code = line.split("//").next().unwrap_or_default();
+        for c in code.chars() {
+            if c.is_whitespace() {
+                has_whitespace = true;
+                continue;
+            }
+
+            if has_whitespace
+                && !is_delimiter(c)
+                && !output.is_empty()
+                && !output.ends_with(is_delimiter)
+            {
+                output.push(' ');
+            }
+            has_whitespace = false;
+            output.push(c);
+        }
+        has_whitespace = true;
+    }
+    output
+}
+
+pub fn source_location(source: &str, span: naga::Span) -> Option<SourceLocation> {
+    // Spans are byte offsets, but columns should count characters.
+    let start = span.to_range()?.start;
+    // An empty source means the source isn't available.
+    let before = source.get(..start).filter(|_| !source.is_empty())?;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    Some(SourceLocation {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    })
+}
+
+// Sort by name since the order of the type arena depends on how naga inserts types while parsing.
+pub fn sorted_struct_types(module: &naga::Module) -> Vec<(naga::Handle<naga::Type>, &naga::Type)> {
+    let mut types: Vec<_> = module
+        .types
+        .iter()
+        .filter(|(_, ty)| matches!(ty.inner, naga::TypeInner::Struct { .. }))
+        .collect();
+    types.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+    types
+}
+
+// Sort by name to avoid depending on the declaration order.
+pub fn sorted_entry_points(module: &naga::Module) -> Vec<&naga::EntryPoint> {
+    let mut entry_points: Vec<_> = module.entry_points.iter().collect();
+    entry_points.sort_by(|a, b| a.name.cmp(&b.name));
+    entry_points
+}
+
+fn add_struct_types(
+    module: &naga::Module,
+    handle: naga::Handle<naga::Type>,
+    types: &mut BTreeSet<naga::Handle<naga::Type>>,
+) {
+    match &module.types[handle].inner {
+        naga::TypeInner::Struct { members, .. } => {
+            // Skip structs that were already visited.
+            if !types.insert(handle) {
+                return;
+            }
+            for member in members {
+                add_struct_types(module, member.ty, types);
+            }
+        }
+        naga::TypeInner::Array { base, .. } => add_struct_types(module, *base, types),
+        _ => (),
+    }
+}
+
+// Find the structs used by globals in matching storage classes including any nested structs.
+fn struct_types(
+    module: &naga::Module,
+    filter: fn(naga::StorageClass) -> bool,
+) -> BTreeSet<naga::Handle<naga::Type>> {
+    let mut types = BTreeSet::new();
+    for (_, global) in module.global_variables.iter() {
+        if filter(global.class) {
+            add_struct_types(module, global.ty, &mut types);
+        }
+    }
+    types
+}
+
+pub fn buffer_struct_types(module: &naga::Module) -> BTreeSet<naga::Handle<naga::Type>> {
+    struct_types(module, |class| {
+        matches!(
+            class,
+            naga::StorageClass::Uniform | naga::StorageClass::Storage { .. }
+        )
+    })
+}
+
+// Structs in these storage classes use the WGSL memory layout with explicit offsets.
+pub fn host_shareable_struct_types(module: &naga::Module) -> BTreeSet<naga::Handle<naga::Type>> {
+    struct_types(module, |class| {
+        matches!(
+            class,
+            naga::StorageClass::Uniform
+                | naga::StorageClass::Storage { .. }
+                | naga::StorageClass::PushConstant
+        )
+    })
+}
+
+pub fn uniform_struct_types(module: &naga::Module) -> BTreeSet<naga::Handle<naga::Type>> {
+    struct_types(module, |class| class == naga::StorageClass::Uniform)
+}
+
+pub fn storage_struct_types(module: &naga::Module) -> BTreeSet<naga::Handle<naga::Type>> {
+    struct_types(module, |class| {
+        matches!(class, naga::StorageClass::Storage { .. })
+    })
+}
+
+// Find the fixed size structs bound directly as buffers and the usages required by their bindings.
+pub fn buffer_binding_structs(
+    module: &naga::Module,
+) -> BTreeMap<naga::Handle<naga::Type>, wgpu::BufferUsages> {
+    let mut structs = BTreeMap::new();
+    for (_, global) in module.global_variables.iter() {
+        let usage = match global.class {
+            naga::StorageClass::Uniform => wgpu::BufferUsages::UNIFORM,
+            naga::StorageClass::Storage { .. } => wgpu::BufferUsages::STORAGE,
+            _ => continue,
+        };
+        let ty = &module.types[global.ty];
+        if matches!(ty.inner, naga::TypeInner::Struct { .. })
+            && min_binding_size(module, ty).is_some()
+        {
+            *structs
+                .entry(global.ty)
+                .or_insert(wgpu::BufferUsages::empty()) |= usage;
+        }
+    }
+    structs
+}
+
+// Empty structs are often placeholders, so only report them when requested.
+pub fn check_empty_structs(module: &naga::Module, source: &str) -> Result<(), CreateModuleError> {
+    for (handle, ty) in sorted_struct_types(module) {
+        if let naga::TypeInner::Struct { members, .. } = &ty.inner {
+            if members.is_empty() {
+                return Err(CreateModuleError::EmptyStruct {
+                    name: ty.name.clone().unwrap_or_default(),
+                    location: source_location(source, module.types.get_span(handle)),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn contains_bool(module: &naga::Module, ty: &naga::Type) -> bool {
+    match &ty.inner {
+        naga::TypeInner::Scalar { kind, .. } | naga::TypeInner::Vector { kind, .. } => {
+            *kind == naga::ScalarKind::Bool
+        }
+        naga::TypeInner::Array { base, .. } => contains_bool(module, &module.types[*base]),
+        _ => false,
+    }
+}
+
+// Booleans have no host-shareable layout and don't implement bytemuck::Pod.
+// Nested structs are checked separately, so report the struct that defines the field.
+pub fn check_bool_members(module: &naga::Module, source: &str) -> Result<(), CreateModuleError> {
+    for (handle, ty) in module.types.iter() {
+        if let naga::TypeInner::Struct { members, .. } = &ty.inner {
+            if let Some(member) = members
+                .iter()
+                .find(|m| contains_bool(module, &module.types[m.ty]))
+            {
+                return Err(CreateModuleError::UnsupportedStructMember {
+                    name: ty.name.clone().unwrap_or_default(),
+                    member: member.name.clone().unwrap_or_default(),
+                    ty: wgsl_type_name(module, &module.types[member.ty]),
+                    location: source_location(source, module.types.get_span(handle)),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn get_bind_group_data<'a>(
+    module: &'a naga::Module,
+    info: &naga::valid::ModuleInfo,
+    source: &str,
+) -> Result<BTreeMap<u32, GroupData<'a>>, CreateModuleError> {
+    let groups = get_sparse_bind_group_data(module, info, source)?;
+    check_consecutive_bind_groups(&groups)?;
+    Ok(groups)
+}
+
+// The bind groups without requiring consecutive group indices for placeholder layouts.
+pub fn get_sparse_bind_group_data<'a>(
+    module: &'a naga::Module,
+    info: &naga::valid::ModuleInfo,
+    source: &str,
+) -> Result<BTreeMap<u32, GroupData<'a>>, CreateModuleError> {
+    check_binding_types(module, source)?;
+
     // Use a BTree to sort type and field names by group index.
     // This isn't strictly necessary but makes the generated code cleaner.
     let mut groups = BTreeMap::new();
@@ -149,82 +882,503 @@ pub fn get_bind_group_data(module: &naga::Module) -> Result<BTreeMap<u32, GroupD
                 bindings: Vec::new(),
             });
             let binding_type = &module.types[module.global_variables[global_handle.0].ty];
+            let location =
+                source_location(source, module.global_variables.get_span(global_handle.0));
 
             let group_binding = GroupBinding {
                 name: global.name.clone(),
                 binding_index: binding.binding,
                 binding_type,
                 storage_class: global.class,
+                visibility: binding_visibility(module, info, global_handle.0),
+                location,
+                min_binding_size: min_binding_size(module, binding_type),
             };
             // Repeated bindings will probably cause a compile error.
             // We'll still check for it here just in case.
             if group.bindings.iter().any(|g| g.binding_index == binding.binding) {
-                return Err(CreateModuleError::DuplicateBinding { binding: binding.binding });
+                return Err(CreateModuleError::DuplicateBinding {
+                    group: binding.group,
+                    binding: binding.binding,
+                    name: global.name.clone().unwrap_or_default(),
+                    location,
+                });
             }
             group.bindings.push(group_binding);
         }
     }
 
+    // Sort by binding index instead of declaration order to match the layout entries in the shader.
+    for group in groups.values_mut() {
+        group.bindings.sort_by_key(|b| b.binding_index);
+    }
+
+    Ok(groups)
+}
+
+// The binding types don't depend on the validation info, so this also works for unvalidated modules.
+fn check_binding_types(module: &naga::Module, source: &str) -> Result<(), CreateModuleError> {
+    for (handle, global) in module.global_variables.iter() {
+        if let Some(binding) = &global.binding {
+            let binding_type = &module.types[global.ty];
+            if !is_supported_binding_type(binding_type, global.class) {
+                return Err(CreateModuleError::UnsupportedBinding {
+                    group: binding.group,
+                    binding: binding.binding,
+                    name: global.name.clone().unwrap_or_default(),
+                    ty: wgsl_type_name(module, binding_type),
+                    location: source_location(source, module.global_variables.get_span(handle)),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_consecutive_bind_groups(
+    groups: &BTreeMap<u32, GroupData>,
+) -> Result<(), CreateModuleError> {
     // wgpu expects bind groups to be consecutive starting from 0.
-    // TODO: Use a result instead?
-    if groups.iter().map(|(i, _)| *i as usize).eq(0..groups.len()) {
-        Ok(groups)
-    } else {
-        Err(CreateModuleError::NonConsecutiveBindGroups)
+    // Report the first group that doesn't have the expected index.
+    for (expected, (group_no, group)) in groups.iter().enumerate() {
+        if *group_no as usize != expected {
+            let first = &group.bindings[0];
+            return Err(CreateModuleError::NonConsecutiveBindGroups {
+                group: *group_no,
+                name: first.name.clone().unwrap_or_default(),
+                location: first.location,
+            });
+        }
     }
+    Ok(())
+}
+
+// Unused groups before the last group still count towards the number of bind groups.
+pub fn bind_group_count(groups: &BTreeMap<u32, GroupData>) -> u32 {
+    groups.keys().last().map_or(0, |group_no| group_no + 1)
+}
+
+// Groups without bindings between used groups need an empty layout in the pipeline layout.
+pub fn has_bind_group_gaps(groups: &BTreeMap<u32, GroupData>) -> bool {
+    groups
+        .keys()
+        .enumerate()
+        .any(|(i, group_no)| *group_no as usize != i)
+}
+
+fn is_float64(ty: &naga::Type) -> bool {
+    matches!(
+        ty.inner,
+        naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Float,
+            width: 8,
+        } | naga::TypeInner::Vector {
+            kind: naga::ScalarKind::Float,
+            width: 8,
+            ..
+        } | naga::TypeInner::Matrix { width: 8, .. }
+    )
+}
+
+// Newer WGSL uses attributes like @group(0) instead of [[group(0)]], which naga 0.8 can't parse.
+pub fn uses_at_attributes(source: &str) -> bool {
+    source
+        .lines()
+        .any(|line| line.split("//").next().unwrap_or_default().contains('@'))
+}
+
+// Texture samples refer to the global variables directly unless the texture or sampler is a function argument.
+pub fn samplers_used_with_textures(module: &naga::Module, textures: &[String]) -> BTreeSet<String> {
+    let functions = module
+        .functions
+        .iter()
+        .map(|(_, f)| f)
+        .chain(module.entry_points.iter().map(|e| &e.function));
+
+    let mut samplers = BTreeSet::new();
+    for function in functions {
+        let global_name = |expression| match function.expressions[expression] {
+            naga::Expression::GlobalVariable(handle) => {
+                module.global_variables[handle].name.as_ref()
+            }
+            _ => None,
+        };
+        for (_, expression) in function.expressions.iter() {
+            if let naga::Expression::ImageSample { image, sampler, .. } = expression {
+                if let (Some(image), Some(sampler)) = (global_name(*image), global_name(*sampler)) {
+                    if textures.contains(image) {
+                        samplers.insert(sampler.clone());
+                    }
+                }
+            }
+        }
+    }
+    samplers
+}
+
+// Builtins can be entry point arguments or results or members of their structs.
+fn entry_point_builtins(module: &naga::Module) -> Vec<naga::BuiltIn> {
+    let mut builtins = Vec::new();
+    for entry in &module.entry_points {
+        let arguments = entry.function.arguments.iter().map(|a| (&a.binding, a.ty));
+        let result = entry.function.result.iter().map(|r| (&r.binding, r.ty));
+        for (binding, ty) in arguments.chain(result) {
+            match (binding, &module.types[ty].inner) {
+                (Some(naga::Binding::BuiltIn(builtin)), _) => builtins.push(*builtin),
+                (None, naga::TypeInner::Struct { members, .. }) => {
+                    for member in members {
+                        if let Some(naga::Binding::BuiltIn(builtin)) = member.binding {
+                            builtins.push(builtin);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+    builtins
+}
+
+fn is_writable_storage(binding: &GroupBinding) -> bool {
+    match (binding.storage_class, &binding.binding_type.inner) {
+        (naga::StorageClass::Storage { access }, _) => access.contains(naga::StorageAccess::STORE),
+        (
+            _,
+            naga::TypeInner::Image {
+                class: naga::ImageClass::Storage { access, .. },
+                ..
+            },
+        ) => access.contains(naga::StorageAccess::STORE),
+        _ => false,
+    }
+}
+
+// Storage texture formats without guaranteed support depend on the adapter.
+fn requires_adapter_format_features(binding: &GroupBinding) -> bool {
+    match binding.binding_type.inner {
+        naga::TypeInner::Image {
+            class: naga::ImageClass::Storage { format, access },
+            ..
+        } => {
+            let features = storage_texture_format(format)
+                .describe()
+                .guaranteed_format_features;
+            let is_read_write =
+                access.contains(naga::StorageAccess::LOAD | naga::StorageAccess::STORE);
+            !features
+                .allowed_usages
+                .contains(wgpu::TextureUsages::STORAGE_BINDING)
+                || (is_read_write
+                    && !features
+                        .flags
+                        .contains(wgpu::TextureFormatFeatureFlags::STORAGE_READ_WRITE))
+        }
+        _ => false,
+    }
+}
+
+// Find the device features needed to create the shader module, layouts, and pipelines.
+pub fn required_features(
+    module: &naga::Module,
+    bind_group_data: &BTreeMap<u32, GroupData>,
+) -> wgpu::Features {
+    let mut features = wgpu::Features::empty();
+    if get_push_constant(module).is_some() {
+        features |= wgpu::Features::PUSH_CONSTANTS;
+    }
+    if module.types.iter().any(|(_, ty)| is_float64(ty)) {
+        features |= wgpu::Features::SHADER_FLOAT64;
+    }
+    for builtin in entry_point_builtins(module) {
+        match builtin {
+            naga::BuiltIn::PrimitiveIndex => features |= wgpu::Features::SHADER_PRIMITIVE_INDEX,
+            naga::BuiltIn::ViewIndex => features |= wgpu::Features::MULTIVIEW,
+            _ => (),
+        }
+    }
+    for binding in bind_group_data.values().flat_map(|group| &group.bindings) {
+        if binding.visibility.contains(wgpu::ShaderStages::VERTEX) && is_writable_storage(binding) {
+            features |= wgpu::Features::VERTEX_WRITABLE_STORAGE;
+        }
+        if requires_adapter_format_features(binding) {
+            features |= wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+        }
+    }
+    features
+}
+
+// Find the device limits with higher requirements than zero and their names in wgpu::Limits.
+pub fn required_limits(
+    module: &naga::Module,
+    bind_group_data: &BTreeMap<u32, GroupData>,
+) -> Vec<(&'static str, u32)> {
+    let mut limits = vec![("max_bind_groups", bind_group_count(bind_group_data))];
+
+    // Bindings only count towards the limits of the stages that can access them.
+    let bindings: Vec<_> = bind_group_data
+        .values()
+        .flat_map(|group| &group.bindings)
+        .collect();
+    let max_per_stage = |filter: fn(&GroupBinding) -> bool| {
+        [
+            wgpu::ShaderStages::VERTEX,
+            wgpu::ShaderStages::FRAGMENT,
+            wgpu::ShaderStages::COMPUTE,
+        ]
+        .into_iter()
+        .map(|stage| {
+            bindings
+                .iter()
+                .filter(|b| b.visibility.contains(stage) && filter(b))
+                .count() as u32
+        })
+        .max()
+        .unwrap_or_default()
+    };
+    limits.extend([
+        (
+            "max_sampled_textures_per_shader_stage",
+            max_per_stage(|b| {
+                matches!(
+                    b.binding_type.inner,
+                    naga::TypeInner::Image {
+                        class: naga::ImageClass::Sampled { .. } | naga::ImageClass::Depth { .. },
+                        ..
+                    }
+                )
+            }),
+        ),
+        (
+            "max_samplers_per_shader_stage",
+            max_per_stage(|b| matches!(b.binding_type.inner, naga::TypeInner::Sampler { .. })),
+        ),
+        (
+            "max_storage_buffers_per_shader_stage",
+            max_per_stage(|b| matches!(b.storage_class, naga::StorageClass::Storage { .. })),
+        ),
+        (
+            "max_storage_textures_per_shader_stage",
+            max_per_stage(|b| {
+                matches!(
+                    b.binding_type.inner,
+                    naga::TypeInner::Image {
+                        class: naga::ImageClass::Storage { .. },
+                        ..
+                    }
+                )
+            }),
+        ),
+        (
+            "max_uniform_buffers_per_shader_stage",
+            max_per_stage(|b| b.storage_class == naga::StorageClass::Uniform),
+        ),
+    ]);
+
+    let max_binding_size = |filter: fn(naga::StorageClass) -> bool| {
+        bindings
+            .iter()
+            .filter(|b| filter(b.storage_class))
+            .filter_map(|b| b.min_binding_size)
+            .max()
+            .unwrap_or_default() as u32
+    };
+    limits.extend([
+        (
+            "max_uniform_buffer_binding_size",
+            max_binding_size(|class| class == naga::StorageClass::Uniform),
+        ),
+        (
+            "max_storage_buffer_binding_size",
+            max_binding_size(|class| matches!(class, naga::StorageClass::Storage { .. })),
+        ),
+    ]);
+
+    let vertex_inputs = get_vertex_input_structs(module);
+    let vertex_entries = module
+        .entry_points
+        .iter()
+        .filter(|e| e.stage == naga::ShaderStage::Vertex);
+    let (mut vertex_buffers, mut vertex_attributes) = (0, 0);
+    for entry in vertex_entries {
+        let names = vertex_entry_input_names(module, entry);
+        let attributes = vertex_inputs
+            .iter()
+            .filter(|input| names.contains(&input.name))
+            .map(|input| input.fields.len() as u32)
+            .sum();
+        vertex_buffers = vertex_buffers.max(names.len() as u32);
+        vertex_attributes = vertex_attributes.max(attributes);
+    }
+    limits.extend([
+        ("max_vertex_buffers", vertex_buffers),
+        ("max_vertex_attributes", vertex_attributes),
+        (
+            "max_push_constant_size",
+            get_push_constant(module)
+                .map(|p| p.size)
+                .unwrap_or_default(),
+        ),
+    ]);
+
+    let workgroup_sizes: Vec<_> = module
+        .entry_points
+        .iter()
+        .filter(|e| e.stage == naga::ShaderStage::Compute)
+        .map(|e| e.workgroup_size)
+        .collect();
+    let max_workgroup_size =
+        |f: fn(&[u32; 3]) -> u32| workgroup_sizes.iter().map(f).max().unwrap_or_default();
+    limits.extend([
+        ("max_compute_workgroup_size_x", max_workgroup_size(|s| s[0])),
+        ("max_compute_workgroup_size_y", max_workgroup_size(|s| s[1])),
+        ("max_compute_workgroup_size_z", max_workgroup_size(|s| s[2])),
+        (
+            "max_compute_invocations_per_workgroup",
+            max_workgroup_size(|s| s.iter().product()),
+        ),
+    ]);
+
+    limits.retain(|(_, limit)| *limit > 0);
+    limits
+}
+
+pub struct PushConstant<'a> {
+    pub ty: &'a naga::Type,
+    pub size: u32,
+}
+
+// wgpu only supports a single push constant range per stage.
+pub fn get_push_constant(module: &naga::Module) -> Option<PushConstant<'_>> {
+    module
+        .global_variables
+        .iter()
+        .find(|(_, global)| global.class == naga::StorageClass::PushConstant)
+        .map(|(_, global)| {
+            let ty = &module.types[global.ty];
+            PushConstant {
+                ty,
+                size: ty.inner.span(&module.constants),
+            }
+        })
 }
 
 pub struct VertexInput {
     pub name: String,
+    /// The fields with locations and their offsets in the vertex buffer.
     pub fields: Vec<(u32, StructMember)>,
+    /// The size in bytes of the struct including padding.
+    pub span: u32,
+    /// `true` if this struct is generated from entry point arguments instead of a WGSL struct.
+    pub is_synthesized: bool,
 }
 
-// TODO: Handle errors.
-// Collect the necessary data to generate an equivalent Rust struct.
 pub fn get_vertex_input_structs(module: &naga::Module) -> Vec<VertexInput> {
-    let mut structs = Vec::new();
+    let mut structs: Vec<VertexInput> = Vec::new();
 
-    // TODO: Just map/collect?
-    if let Some(vertex_entry) = module
-        .entry_points
-        .iter()
-        .find(|e| e.stage == naga::ShaderStage::Vertex)
+    // Arguments don't have offsets, so lay them out like the members of a WGSL struct.
+    let mut layouter = naga::proc::Layouter::default();
+    layouter.update(&module.types, &module.constants).unwrap();
+
+    for vertex_entry in sorted_entry_points(module)
+        .into_iter()
+        .filter(|e| e.stage == naga::ShaderStage::Vertex)
     {
+        // Arguments with a location are combined into a single struct for the entry point.
+        let mut argument_fields = Vec::new();
+        let mut end = 0;
+        let mut alignment = naga::proc::Alignment::new(1).unwrap();
+
         for argument in &vertex_entry.function.arguments {
             // For entry points, arguments must have a binding unless they are a structure.
-            if let Some(_binding) = &argument.binding {
-                // TODO: How to create a structure for regular bindings?
-            } else {
-                let arg_type = &module.types[argument.ty];
-                match &arg_type.inner {
-                    naga::TypeInner::Struct { members, span: _ } => {
-                        let input = VertexInput {
-                            name: arg_type.name.as_ref().unwrap().clone(),
-                            fields: members
-                                .iter()
-                                .map(|member| {
-                                    let location = match member.binding.as_ref().unwrap() {
-                                        naga::Binding::BuiltIn(_) => todo!(), // TODO: is it possible to have builtins for inputs?
-                                        naga::Binding::Location { location, .. } => *location,
-                                    };
-
-                                    (location, member.clone())
-                                })
-                                .collect(),
-                        };
-
-                        structs.push(input);
+            match &argument.binding {
+                Some(naga::Binding::Location { location, .. }) => {
+                    let (range, member_alignment) =
+                        layouter.member_placement(end, argument.ty, None, None);
+                    end = range.end;
+                    alignment = alignment.max(member_alignment);
+                    let member = StructMember {
+                        name: argument.name.clone(),
+                        ty: argument.ty,
+                        binding: argument.binding.clone(),
+                        offset: range.start,
+                    };
+                    argument_fields.push((*location, member));
+                }
+                Some(naga::Binding::BuiltIn(_)) => (),
+                None => {
+                    let arg_type = &module.types[argument.ty];
+                    match &arg_type.inner {
+                        naga::TypeInner::Struct { members, span } => {
+                            let name = arg_type.name.as_ref().unwrap().clone();
+                            // Entry points may share the same input structs.
+                            if structs.iter().any(|s| s.name == name) {
+                                continue;
+                            }
+
+                            let input = VertexInput {
+                                name,
+                                // Builtins like vertex_index aren't part of the vertex buffer.
+                                fields: members
+                                    .iter()
+                                    .filter_map(|member| match member.binding.as_ref().unwrap() {
+                                        naga::Binding::BuiltIn(_) => None,
+                                        naga::Binding::Location { location, .. } => {
+                                            Some((*location, member.clone()))
+                                        }
+                                    })
+                                    .collect(),
+                                span: *span,
+                                is_synthesized: false,
+                            };
+
+                            structs.push(input);
+                        }
+                        // This case should be prevented by the checks above.
+                        _ => unreachable!(),
                     }
-                    // This case should be prevented by the checks above.
-                    _ => unreachable!(),
                 }
             }
         }
+
+        if !argument_fields.is_empty() {
+            structs.push(VertexInput {
+                name: vertex_arguments_struct_name(&vertex_entry.name),
+                fields: argument_fields,
+                span: naga::proc::Layouter::round_up(alignment, end),
+                is_synthesized: true,
+            });
+        }
+    }
+
+    structs
+}
+
+// Find the names of the vertex input structs for an entry point in parameter order.
+// Arguments with locations are combined into a single struct at the position of the first argument.
+pub fn vertex_entry_input_names(module: &naga::Module, entry: &naga::EntryPoint) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut has_argument_struct = false;
+    for argument in &entry.function.arguments {
+        match &argument.binding {
+            Some(naga::Binding::Location { .. }) => {
+                if !has_argument_struct {
+                    names.push(vertex_arguments_struct_name(&entry.name));
+                    has_argument_struct = true;
+                }
+            }
+            Some(naga::Binding::BuiltIn(_)) => (),
+            None => names.extend(module.types[argument.ty].name.clone()),
+        }
     }
+    names
+}
 
-    structs
+// Convert names like "vs_main" to "VsMainInput".
+pub fn vertex_arguments_struct_name(entry_name: &str) -> String {
+    format!("{}Input", crate::entry_point_variant_name(entry_name))
 }
 
+#[allow(dead_code)]
 pub fn get_vertex_input_locations(module: &naga::Module) -> Vec<(String, u32)> {
     let mut shader_locations = Vec::new();
 
@@ -384,6 +1538,144 @@ mod test {
         assert_eq!(5, vertex_inputs[1].fields[2].0);
     }
 
+    #[test]
+    fn vertex_input_structs_arguments() {
+        let source = indoc! {r#"
+            [[stage(vertex)]]
+            fn vs_main(
+                [[builtin(vertex_index)]] index: u32,
+                [[location(0)]] position: vec3<f32>,
+                [[location(1)]] uv: vec2<f32>
+            ) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(position, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let vertex_inputs = get_vertex_input_structs(&module);
+        assert_eq!(1, vertex_inputs.len());
+
+        assert_eq!("VsMainInput", vertex_inputs[0].name);
+        assert!(vertex_inputs[0].is_synthesized);
+        assert_eq!(2, vertex_inputs[0].fields.len());
+        assert_eq!("uv", vertex_inputs[0].fields[1].1.name.as_ref().unwrap());
+        assert_eq!(1, vertex_inputs[0].fields[1].0);
+
+        // The arguments use the same layout as the members of a WGSL struct.
+        assert_eq!(0, vertex_inputs[0].fields[0].1.offset);
+        assert_eq!(16, vertex_inputs[0].fields[1].1.offset);
+        assert_eq!(32, vertex_inputs[0].span);
+    }
+
+    #[test]
+    fn vertex_input_unsupported_type() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+                [[location(1)]] flag: bool;
+            };
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let result = check_vertex_inputs(&module, source, &HashMap::new());
+        assert!(matches!(
+            result,
+            Err(CreateModuleError::UnsupportedVertexInput {
+                name,
+                field,
+                ty,
+                location: Some(SourceLocation { line: 1, .. }),
+            }) if name == "VertexInput" && field == "flag" && ty == "bool"
+        ));
+
+        // An override provides a format for types without a default.
+        let overrides = HashMap::from([("flag".to_string(), wgpu::VertexFormat::Uint32)]);
+        assert!(check_vertex_inputs(&module, source, &overrides).is_ok());
+    }
+
+    #[test]
+    fn vertex_input_structs_builtins() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[builtin(vertex_index)]] index: u32;
+                [[location(0)]] position: vec3<f32>;
+                [[builtin(instance_index)]] instance: u32;
+                [[location(1)]] uv: vec2<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn main(in: VertexInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        let vertex_inputs = get_vertex_input_structs(&module);
+        assert_eq!(1, vertex_inputs.len());
+
+        assert_eq!("VertexInput", vertex_inputs[0].name);
+        assert_eq!(2, vertex_inputs[0].fields.len());
+        assert_eq!(
+            "position",
+            vertex_inputs[0].fields[0].1.name.as_ref().unwrap()
+        );
+        assert_eq!("uv", vertex_inputs[0].fields[1].1.name.as_ref().unwrap());
+        assert_eq!(1, vertex_inputs[0].fields[1].0);
+    }
+
+    #[test]
+    fn vertex_entry_input_names_multiple_entries() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+            };
+
+            struct InstanceInput {
+                [[location(2)]] offset: vec3<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn vs_model(in: VertexInput, instance: InstanceInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position + instance.offset, 1.0);
+            }
+
+            [[stage(vertex)]]
+            fn vs_quad(
+                [[builtin(vertex_index)]] index: u32,
+                [[location(0)]] uv: vec2<f32>,
+                instance: InstanceInput,
+                [[location(1)]] color: vec4<f32>
+            ) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(uv, 0.0, 1.0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+
+        assert_eq!(
+            vec!["VertexInput", "InstanceInput"],
+            vertex_entry_input_names(&module, &module.entry_points[0])
+        );
+        assert_eq!(
+            vec!["VsQuadInput", "InstanceInput"],
+            vertex_entry_input_names(&module, &module.entry_points[1])
+        );
+
+        // Shared structs are only included once.
+        let vertex_inputs = get_vertex_input_structs(&module);
+        assert_eq!(3, vertex_inputs.len());
+        assert_eq!("VsQuadInput", vertex_inputs[2].name);
+        assert_eq!(2, vertex_inputs[2].fields.len());
+    }
+
     #[test]
     fn vertex_locations_struct_two_fields() {
         let source = indoc! {r#"
@@ -485,43 +1777,490 @@ mod test {
     #[test]
     fn bind_group_data_consecutive_bind_groups() {
         let source = indoc! {r#"
-            [[group(0), binding(0)]] var<uniform> a: vec4<f32>;
-            [[group(1), binding(0)]] var<uniform> b: vec4<f32>;
-            [[group(2), binding(0)]] var<uniform> c: vec4<f32>;
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(0), binding(0)]] var<uniform> a: A;
+            [[group(1), binding(0)]] var<uniform> b: A;
+            [[group(2), binding(0)]] var<uniform> c: A;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        assert_eq!(3, get_bind_group_data(&module, &info, source).unwrap().len());
+    }
+
+    #[test]
+    fn bind_group_data_bindings_sorted_by_index() {
+        let source = indoc! {r#"
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(0), binding(2)]] var<uniform> c: A;
+            [[group(0), binding(0)]] var<uniform> a: A;
+            [[group(0), binding(1)]] var<uniform> b: A;
+
+            [[stage(fragment)]]
+            fn main() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+        let bindings: Vec<_> = bind_group_data[&0]
+            .bindings
+            .iter()
+            .map(|b| (b.binding_index, b.name.clone().unwrap()))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, "a".to_string()),
+                (1, "b".to_string()),
+                (2, "c".to_string())
+            ],
+            bindings
+        );
+    }
+
+    #[test]
+    fn bind_group_data_visibility() {
+        let source = indoc! {r#"
+            struct Transforms {
+                offset: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]] var<uniform> transforms: Transforms;
+            [[group(0), binding(1)]] var color_texture: texture_2d<f32>;
+            [[group(0), binding(2)]] var color_sampler: sampler;
+            [[group(0), binding(3)]] var<uniform> unused: Transforms;
+            [[group(0), binding(4)]] var<uniform> shared: Transforms;
+
+            [[stage(vertex)]]
+            fn vs_main() -> [[builtin(position)]] vec4<f32> {
+                return transforms.offset + shared.offset;
+            }
+
+            [[stage(fragment)]]
+            fn fs_main() -> [[location(0)]] vec4<f32> {
+                return textureSample(color_texture, color_sampler, vec2<f32>(0.0)) + shared.offset;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+        let visibility: Vec<_> = bind_group_data[&0]
+            .bindings
+            .iter()
+            .map(|b| b.visibility)
+            .collect();
+        assert_eq!(
+            vec![
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::ShaderStages::VERTEX_FRAGMENT,
+                wgpu::ShaderStages::VERTEX_FRAGMENT,
+            ],
+            visibility
+        );
+    }
+
+    #[test]
+    fn attribute_syntax() {
+        assert!(uses_at_attributes(
+            "@group(0) @binding(0)\nvar t: texture_2d<f32>;"
+        ));
+        assert!(!uses_at_attributes(
+            "[[group(0), binding(0)]]\nvar t: texture_2d<f32>;"
+        ));
+        assert!(!uses_at_attributes(
+            "// Use @group(0) after upgrading naga.\nlet a = 1;"
+        ));
+    }
+
+    #[test]
+    fn samplers_used_with_non_filterable_textures() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]] var data_texture: texture_2d<f32>;
+            [[group(0), binding(1)]] var color_texture: texture_2d<f32>;
+            [[group(0), binding(2)]] var data_sampler: sampler;
+            [[group(0), binding(3)]] var color_sampler: sampler;
+
+            fn sample_data(uv: vec2<f32>) -> vec4<f32> {
+                return textureSampleLevel(data_texture, data_sampler, uv, 0.0);
+            }
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return sample_data(vec2<f32>(0.0)) + textureSample(color_texture, color_sampler, vec2<f32>(0.0));
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        assert_eq!(
+            BTreeSet::from(["data_sampler".to_string()]),
+            samplers_used_with_textures(&module, &["data_texture".to_string()])
+        );
+        assert!(samplers_used_with_textures(&module, &[]).is_empty());
+    }
+
+    #[test]
+    fn binding_declarations() {
+        let source = indoc! {r#"
+            struct Camera {
+                position: vec4<f32>;
+            };
+            struct Particles {
+                positions: array<vec4<f32>>;
+            };
+            struct Counts {
+                counts: array<u32>;
+            };
+            [[group(0), binding(0)]] var<uniform> camera: Camera;
+            [[group(0), binding(1)]] var<storage, read> particles: Particles;
+            [[group(0), binding(2)]] var<storage, read_write> counts: Counts;
+            [[group(0), binding(3)]] var color: texture_2d_array<f32>;
+            [[group(0), binding(4)]] var samples: texture_multisampled_2d<i32>;
+            [[group(0), binding(5)]] var depth: texture_depth_cube;
+            [[group(0), binding(6)]] var output: texture_storage_3d<rgba8unorm, write>;
+            [[group(0), binding(7)]] var shadow: sampler_comparison;
+
+            [[stage(compute), workgroup_size(1)]]
+            fn main() {
+                let a = camera.position;
+                let b = particles.positions[0];
+                counts.counts[0] = 1u;
+                let c = textureLoad(color, vec2<i32>(0), 0, 0);
+                let d = textureLoad(samples, vec2<i32>(0), 0);
+                let e = textureDimensions(depth);
+                textureStore(output, vec3<i32>(0), vec4<f32>(0.0));
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+        let declarations: Vec<_> = bind_group_data[&0]
+            .bindings
+            .iter()
+            .map(|b| wgsl_binding_declaration(&module, b))
+            .collect();
+        assert_eq!(
+            vec![
+                "var<uniform> camera: Camera",
+                "var<storage, read> particles: Particles",
+                "var<storage, read_write> counts: Counts",
+                "var color: texture_2d_array<f32>",
+                "var samples: texture_multisampled_2d<i32>",
+                "var depth: texture_depth_cube",
+                "var output: texture_storage_3d<rgba8unorm, write>",
+                "var shadow: sampler_comparison",
+            ],
+            declarations
+        );
+    }
+
+    #[test]
+    fn bind_group_data_unsupported_binding() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]] var<storage, read_write> a: atomic<u32>;
+        "#};
+
+        // The validator already rejects this module, so check the binding types directly.
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        assert!(validate(&module).is_err());
+        match check_binding_types(&module, source) {
+            Err(CreateModuleError::UnsupportedBinding {
+                group,
+                binding,
+                name,
+                ty,
+                location,
+            }) => {
+                assert_eq!(0, group);
+                assert_eq!(0, binding);
+                assert_eq!("a", name);
+                assert_eq!("atomic<u32>", ty);
+                assert_eq!(1, location.unwrap().line);
+            }
+            _ => panic!("expected an unsupported binding error"),
+        }
+    }
+
+    #[test]
+    fn texture_view_dimension_arrays() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]] var shadow_maps: texture_depth_2d_array;
+            [[group(0), binding(1)]] var cube_maps: texture_cube_array<f32>;
 
             [[stage(fragment)]]
             fn main() {}
         "#};
 
         let module = naga::front::wgsl::parse_str(source).unwrap();
-        assert_eq!(3, get_bind_group_data(&module).unwrap().len());
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+        let dimensions: Vec<_> = bind_group_data[&0]
+            .bindings
+            .iter()
+            .map(|b| match b.binding_type.inner {
+                naga::TypeInner::Image { dim, arrayed, .. } => texture_view_dimension(dim, arrayed),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                wgpu::TextureViewDimension::D2Array,
+                wgpu::TextureViewDimension::CubeArray
+            ],
+            dimensions
+        );
     }
 
     #[test]
     fn bind_group_data_first_group_not_zero() {
         let source = indoc! {r#"
-            [[group(1), binding(0)]] var<uniform> a: vec4<f32>;
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(1), binding(0)]] var<uniform> a: A;
 
             [[stage(fragment)]]
             fn main() {}
         "#};
 
         let module = naga::front::wgsl::parse_str(source).unwrap();
-        assert!(matches!(get_bind_group_data(&module), Err(CreateModuleError::NonConsecutiveBindGroups)));
+        let info = validate(&module).unwrap();
+        assert!(matches!(get_bind_group_data(&module, &info, source), Err(CreateModuleError::NonConsecutiveBindGroups { .. })));
     }
 
     #[test]
     fn bind_group_data_non_consecutive_bind_groups() {
         let source = indoc! {r#"
-            [[group(0), binding(0)]] var<uniform> a: vec4<f32>;
-            [[group(1), binding(0)]] var<uniform> b: vec4<f32>;
-            [[group(3), binding(0)]] var<uniform> c: vec4<f32>;
+            struct A {
+                f: vec4<f32>;
+            };
+            [[group(0), binding(0)]] var<uniform> a: A;
+            [[group(1), binding(0)]] var<uniform> b: A;
+            [[group(3), binding(0)]] var<uniform> c: A;
 
             [[stage(fragment)]]
             fn main() {}
         "#};
 
         let module = naga::front::wgsl::parse_str(source).unwrap();
-        assert!(matches!(get_bind_group_data(&module), Err(CreateModuleError::NonConsecutiveBindGroups)));
+        let info = validate(&module).unwrap();
+        assert!(matches!(get_bind_group_data(&module, &info, source), Err(CreateModuleError::NonConsecutiveBindGroups { .. })));
+    }
+
+    #[test]
+    fn required_features_empty() {
+        let source = indoc! {r#"
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(color_texture, vec2<i32>(0), 0);
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+        assert_eq!(
+            wgpu::Features::empty(),
+            required_features(&module, &bind_group_data)
+        );
+    }
+
+    #[test]
+    fn required_features_push_constants_float64_builtins() {
+        let source = indoc! {r#"
+            struct PushConstants {
+                color: vec4<f32>;
+            };
+            var<push_constant> constants: PushConstants;
+
+            struct FragmentInput {
+                [[builtin(primitive_index)]] primitive_index: u32;
+                [[builtin(view_index)]] view_index: i32;
+            };
+
+            [[stage(fragment)]]
+            fn main(in: FragmentInput) -> [[location(0)]] vec4<f32> {
+                let scale: f64 = f64(1.0);
+                return constants.color;
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+        assert_eq!(
+            wgpu::Features::PUSH_CONSTANTS
+                | wgpu::Features::SHADER_FLOAT64
+                | wgpu::Features::SHADER_PRIMITIVE_INDEX
+                | wgpu::Features::MULTIVIEW,
+            required_features(&module, &bind_group_data)
+        );
+    }
+
+    #[test]
+    fn required_features_storage() {
+        let source = indoc! {r#"
+            struct Positions {
+                positions: array<vec4<f32>>;
+            };
+
+            [[group(0), binding(0)]]
+            var<storage, read_write> positions: Positions;
+            [[group(0), binding(1)]]
+            var read_write_texture: texture_storage_2d<rgba8unorm, read_write>;
+            [[group(0), binding(2)]]
+            var write_texture: texture_storage_2d<rgba8unorm, write>;
+
+            [[stage(vertex)]]
+            fn vs_main([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+                positions.positions[index] = vec4<f32>(0.0);
+                return positions.positions[index];
+            }
+
+            [[stage(fragment)]]
+            fn fs_main() -> [[location(0)]] vec4<f32> {
+                textureStore(write_texture, vec2<i32>(0), vec4<f32>(0.0));
+                return textureLoad(read_write_texture, vec2<i32>(0));
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+
+        // Rgba8Unorm supports write only storage textures on every adapter.
+        assert_eq!(
+            wgpu::Features::VERTEX_WRITABLE_STORAGE
+                | wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+            required_features(&module, &bind_group_data)
+        );
+    }
+
+    #[test]
+    fn required_limits_bindings() {
+        let source = indoc! {r#"
+            struct Transforms {
+                a: vec4<f32>;
+                b: vec4<f32>;
+            };
+
+            [[group(0), binding(0)]]
+            var color_texture: texture_2d<f32>;
+            [[group(0), binding(1)]]
+            var normal_texture: texture_2d<f32>;
+            [[group(0), binding(2)]]
+            var color_sampler: sampler;
+            [[group(1), binding(0)]]
+            var<uniform> transforms: Transforms;
+
+            [[stage(vertex)]]
+            fn vs_main() -> [[builtin(position)]] vec4<f32> {
+                return transforms.a;
+            }
+
+            [[stage(fragment)]]
+            fn fs_main() -> [[location(0)]] vec4<f32> {
+                let color = textureSample(color_texture, color_sampler, vec2<f32>(0.0));
+                return color + textureSample(normal_texture, color_sampler, vec2<f32>(0.0));
+            }
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+        assert_eq!(
+            vec![
+                ("max_bind_groups", 2),
+                ("max_sampled_textures_per_shader_stage", 2),
+                ("max_samplers_per_shader_stage", 1),
+                ("max_uniform_buffers_per_shader_stage", 1),
+                ("max_uniform_buffer_binding_size", 32),
+            ],
+            required_limits(&module, &bind_group_data)
+        );
+    }
+
+    #[test]
+    fn required_limits_vertex_compute() {
+        let source = indoc! {r#"
+            struct VertexInput {
+                [[location(0)]] position: vec3<f32>;
+                [[location(1)]] normal: vec3<f32>;
+            };
+
+            struct InstanceInput {
+                [[location(2)]] offset: vec3<f32>;
+            };
+
+            [[stage(vertex)]]
+            fn vs_main(in: VertexInput, instance: InstanceInput) -> [[builtin(position)]] vec4<f32> {
+                return vec4<f32>(in.position + instance.offset, 1.0);
+            }
+
+            [[stage(compute), workgroup_size(8, 4, 2)]]
+            fn cs_main() {}
+
+            [[stage(compute), workgroup_size(256)]]
+            fn cs_main2() {}
+        "#};
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = validate(&module).unwrap();
+        let bind_group_data = get_bind_group_data(&module, &info, source).unwrap();
+        assert_eq!(
+            vec![
+                ("max_vertex_buffers", 2),
+                ("max_vertex_attributes", 3),
+                ("max_compute_workgroup_size_x", 256),
+                ("max_compute_workgroup_size_y", 4),
+                ("max_compute_workgroup_size_z", 2),
+                ("max_compute_invocations_per_workgroup", 256),
+            ],
+            required_limits(&module, &bind_group_data)
+        );
+    }
+
+    #[test]
+    fn minify_comments_whitespace() {
+        let source = indoc! {r#"
+            // Camera data.
+            struct Camera {
+                view_proj: mat4x4<f32>; // Column major.
+                indices: array<vec4<u32>, 2>;
+            };
+
+            [[group(0), binding(0)]]
+            var<uniform> camera: Camera;
+
+            [[stage(vertex)]]
+            fn main([[location(0)]] position: vec3<f32>) -> [[builtin(position)]] vec4<f32> {
+                let i = camera.indices[camera.indices[0].x ].y;
+                return camera.view_proj * vec4<f32>(position - 1.0, f32(i) - -1.0);
+            }
+        "#};
+
+        let actual = minify(source);
+        assert_eq!(
+            "struct Camera{view_proj:mat4x4<f32>;indices:array<vec4<u32>,2>;};\
+             [[group(0),binding(0)]] var<uniform> camera:Camera;\
+             [[stage(vertex)]] fn main([[location(0)]] position:vec3<f32>)-> [[builtin(position)]] vec4<f32>{\
+             let i = camera.indices[camera.indices[0].x ].y;\
+             return camera.view_proj * vec4<f32>(position - 1.0,f32(i)- -1.0);}",
+            actual
+        );
+        naga::front::wgsl::parse_str(&actual).unwrap();
     }
 }
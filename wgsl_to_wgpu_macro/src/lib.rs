@@ -0,0 +1,77 @@
+//! # wgsl_to_wgpu_macro
+//! Procedural macros for generating the bindings from [wgsl_to_wgpu](https://docs.rs/wgsl_to_wgpu) at compile time.
+//!
+//! The [wgsl_module] macro generates the same code as [wgsl_to_wgpu::create_shader_module] without a build script.
+//! This is convenient for small examples and tests.
+//! Build scripts are still recommended for larger projects since the generated code is easier to inspect
+//! and options like [wgsl_to_wgpu::WriterOptions::derive_bytemuck] can be configured.
+use std::path::Path;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, LitStr};
+
+/// Generate the bindings for the WGSL file at the given path relative to the crate root.
+///
+/// The generated items are placed at the location of the macro,
+/// so the macro is usually the only item in a module.
+/// The generated code includes the WGSL file with `include_str!`,
+/// so changes to the shader also recompile the crate.
+///
+/// # Examples
+/**
+```rust ignore
+mod shader {
+    wgsl_to_wgpu_macro::wgsl_module!("src/shader.wgsl");
+}
+```
+ */
+#[proc_macro]
+pub fn wgsl_module(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    match generate(&path.value(), Path::new(&manifest_dir)) {
+        Ok(tokens) => tokens.into(),
+        Err(message) => syn::Error::new(path.span(), message)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+fn generate(path: &str, manifest_dir: &Path) -> Result<proc_macro2::TokenStream, String> {
+    let path = manifest_dir.join(path);
+    let wgsl_source = std::fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+
+    // Use an absolute path with forward slashes since the code isn't written to a file.
+    let include_path = path.to_string_lossy().replace('\\', "/");
+    wgsl_to_wgpu::create_shader_module_tokens(
+        &wgsl_source,
+        &include_path,
+        wgsl_to_wgpu::WriterOptions::default(),
+    )
+    .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generate_example_shader() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let actual = generate("../example/src/shader.wgsl", manifest_dir)
+            .unwrap()
+            .to_string();
+        assert!(actual.contains("pub mod bind_groups"));
+        assert!(actual.contains("/example/src/shader.wgsl\")"));
+    }
+
+    #[test]
+    fn generate_missing_shader() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert!(generate("missing.wgsl", manifest_dir)
+            .unwrap_err()
+            .starts_with("failed to read"));
+    }
+}